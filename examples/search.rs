@@ -6,8 +6,8 @@
 use std::env;
 
 use anyhow::Result;
-use docsearch::{Index, SimplePath, Version};
-use reqwest::redirect::Policy;
+use docsearch::{Index, Request, SimplePath, Version};
+use reqwest::{redirect::Policy, Response};
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
@@ -31,21 +31,14 @@ async fn main() -> Result<()> {
 
 async fn search(name: &str, version: Version) -> Result<Index> {
     let state = docsearch::start_search(name, version);
-    let content = reqwest::Client::builder()
-        .redirect(Policy::limited(10))
-        .build()?
-        .get(state.url())
-        .send()
-        .await?
-        .error_for_status()?
-        .text()
-        .await?;
+    let resp = send(&state.request()).await?;
+
+    let state = state.resolve_version(resp.url().as_str());
+    let status = resp.status().as_u16();
+    let content = resp.text().await?;
 
-    let state = state.find_index(&content)?;
-    let content = reqwest::Client::builder()
-        .build()?
-        .get(state.url())
-        .send()
+    let state = state.find_index_with_status(status, &content)?;
+    let content = send(&state.request())
         .await?
         .error_for_status()?
         .text()
@@ -54,6 +47,29 @@ async fn search(name: &str, version: Version) -> Result<Index> {
     state.transform_index(&content).map_err(Into::into)
 }
 
+/// Send the request as described by the search state, including its headers and redirect policy.
+/// Error statuses are kept in the response, so the search can report them in detail.
+async fn send(req: &Request) -> Result<Response> {
+    let client = reqwest::Client::builder()
+        .redirect(if req.follow_redirects {
+            Policy::limited(10)
+        } else {
+            Policy::none()
+        })
+        .gzip(true)
+        .build()?;
+
+    let mut builder = client.request(req.method.as_str().parse()?, &req.url);
+    for (name, value) in &req.headers {
+        // Compression is handled by reqwest itself.
+        if *name != "Accept-Encoding" {
+            builder = builder.header(*name, *value);
+        }
+    }
+
+    builder.send().await.map_err(Into::into)
+}
+
 /// Parse the arguments of this example. Uses panic for the sake of simplicity.
 fn parse_args() -> SimplePath {
     match env::args().nth(1) {
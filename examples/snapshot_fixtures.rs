@@ -0,0 +1,105 @@
+//! Development tool that downloads fresh docs pages and search indexes from docs.rs, and saves
+//! them as fixtures for the tests of this crate. This allows to quickly capture changes to the
+//! format of `rustdoc` and turn them into regression tests.
+//!
+//! Each argument is a crate, optionally followed by a version, like `anyhow@1.0.72`, `syn` or
+//! `std@1.70.0`. The page is saved as `src/fixtures/<name>-<version>.html` and the index as
+//! `src/index/fixtures/<name>-<version>.js`, where the version is resolved to a concrete one if
+//! possible.
+//!
+//! ```sh
+//! cargo run --features blocking --example snapshot_fixtures -- anyhow@1.0.72 std
+//! ```
+//!
+//! Afterwards, run the tests and review the new snapshots with `cargo insta review`.
+
+use std::{env, error::Error, fs, path::Path, process};
+
+use docsearch::{HttpFetch, Response, Version};
+
+/// Directory for the docs page fixtures.
+const PAGE_DIR: &str = "src/fixtures";
+/// Directory for the search index fixtures.
+const INDEX_DIR: &str = "src/index/fixtures";
+
+fn main() {
+    let specs = env::args().skip(1).collect::<Vec<_>>();
+    if specs.is_empty() {
+        eprintln!("Usage: snapshot_fixtures <crate>[@<version>]...");
+        process::exit(2);
+    }
+
+    let agent = ureq::Agent::new();
+    let mut failed = false;
+
+    for spec in &specs {
+        if let Err(e) = snapshot(&agent, spec) {
+            eprintln!("{spec}: {e}");
+            failed = true;
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// Download the page and index of a single crate, and save them as fixtures.
+fn snapshot(agent: &ureq::Agent, spec: &str) -> Result<(), Box<dyn Error>> {
+    let (name, version) = spec.split_once('@').unwrap_or((spec, "latest"));
+    let requested = version.parse::<Version>()?;
+
+    let state = docsearch::start_search(name, requested);
+    let page = fetch(agent, &state.request())?;
+    let state = match &page.final_url {
+        Some(url) => state.resolve_version(url),
+        None => state,
+    };
+
+    let state = state.find_index_with_status(page.status, &page.body)?;
+    let index = fetch(agent, &state.request())?;
+    if index.status != 200 {
+        return Err(format!("index download failed with status {}", index.status).into());
+    }
+
+    // Still save the fixtures if the index can't be parsed, as that's usually the reason to
+    // capture it in the first place.
+    let version = match state.transform_index(&index.body) {
+        Ok(parsed) => {
+            println!(
+                "{spec}: parsed {} items from a {:?} index",
+                parsed.mapping.len(),
+                parsed.format
+            );
+            parsed.version.to_string()
+        }
+        Err(e) => {
+            println!("{spec}: failed parsing the index: {e}");
+            version.to_owned()
+        }
+    };
+
+    let file_name = format!("{name}-{version}");
+    save(
+        Path::new(PAGE_DIR).join(format!("{file_name}.html")),
+        &page.body,
+    )?;
+    save(
+        Path::new(INDEX_DIR).join(format!("{file_name}.js")),
+        &index.body,
+    )?;
+
+    Ok(())
+}
+
+fn fetch(agent: &ureq::Agent, request: &docsearch::Request) -> Result<Response, Box<dyn Error>> {
+    agent.fetch(request).map_err(Into::into)
+}
+
+fn save(path: impl AsRef<Path>, content: &str) -> Result<(), Box<dyn Error>> {
+    let path = path.as_ref();
+    fs::write(path, content)?;
+    println!("saved {}", path.display());
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Example for WebAssembly environments like browser extensions or Cloudflare Workers, that uses
+//! the `fetch` API of the host to download the docs page and index.
+//!
+//! Build it with `cargo build --example wasm --target wasm32-unknown-unknown` and generate the
+//! JavaScript bindings with `wasm-bindgen`. The exported `find_link` function takes an item path
+//! and returns a promise, that resolves to the link of the item or `undefined` if it wasn't found.
+//!
+//! On any other target, this example is empty.
+
+#[cfg(target_arch = "wasm32")]
+mod wasm {
+    use docsearch::{Request, SimplePath, Version};
+    use wasm_bindgen::{prelude::*, JsCast};
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{RequestInit, RequestRedirect, Response};
+
+    #[wasm_bindgen]
+    extern "C" {
+        /// The global `fetch` function, which exists in browsers, web workers and Cloudflare
+        /// Workers alike.
+        #[wasm_bindgen(js_name = fetch)]
+        fn fetch_with_request(request: &web_sys::Request) -> js_sys::Promise;
+    }
+
+    /// Search the index of the crate in the given path, and find the link to the item.
+    #[wasm_bindgen]
+    pub async fn find_link(path: String) -> Result<Option<String>, JsError> {
+        let path = path.parse::<SimplePath>()?;
+        let index = docsearch::search_with(path.crate_name(), Version::Latest, fetch).await?;
+
+        Ok(index.find_link(&path))
+    }
+
+    /// Send the request as described by the search state, including its headers and redirect
+    /// policy.
+    async fn fetch(req: Request) -> Result<String, String> {
+        let init = RequestInit::new();
+        init.set_method(req.method.as_str());
+        init.set_redirect(if req.follow_redirects {
+            RequestRedirect::Follow
+        } else {
+            RequestRedirect::Manual
+        });
+
+        let request = web_sys::Request::new_with_str_and_init(&req.url, &init).map_err(js_error)?;
+        for (name, value) in &req.headers {
+            // Headers like `Accept-Encoding` are controlled by the host and can't be set.
+            request.headers().set(name, value).ok();
+        }
+
+        let resp: Response = JsFuture::from(fetch_with_request(&request))
+            .await
+            .map_err(js_error)?
+            .unchecked_into();
+        if !resp.ok() {
+            return Err(format!("request failed with status {}", resp.status()));
+        }
+
+        JsFuture::from(resp.text().map_err(js_error)?)
+            .await
+            .map_err(js_error)?
+            .as_string()
+            .ok_or_else(|| "response body isn't text".to_owned())
+    }
+
+    fn js_error(value: JsValue) -> String {
+        format!("{value:?}")
+    }
+}
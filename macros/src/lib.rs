@@ -0,0 +1,211 @@
+//! Resolve docs links of crate items at compile time, with the help of [`docsearch`].
+//!
+//! The [`doc_link!`] macro expands to the docs.rs (or stdlib) link of an item as string literal,
+//! and fails the build if the item doesn't exist. This keeps hardcoded links in websites or bots
+//! correct, even as the linked crates evolve.
+//!
+//! ```ignore
+//! const RESULT_DOCS: &str = docsearch_macros::doc_link!("anyhow::Result");
+//! const CONTEXT_DOCS: &str = docsearch_macros::doc_link!("anyhow::Context", "1.0.72");
+//! ```
+//!
+//! # Cache
+//!
+//! The indexes are shared with the cache of the `docsearch` command line tool, which is located in
+//! the user's cache directory (like `~/.cache/docsearch` on Linux), or the directory given by the
+//! `DOCSEARCH_CACHE_DIR` environment variable. Missing indexes are downloaded once and saved to
+//! the cache, so later builds don't need any network access. If `DOCSEARCH_OFFLINE` is set, the
+//! build fails for missing indexes instead.
+//!
+//! Cached indexes are never updated by the macro. Run `docsearch cache warm <crate>` to pick up
+//! newer docs for the `latest` version.
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms, clippy::all, clippy::pedantic)]
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use docsearch::{CacheKey, Index, Search, SimplePath, StdChannel, Version};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, LitStr, Token,
+};
+
+thread_local! {
+    /// Indexes that were already loaded during this compilation, by their cache file.
+    static LOADED: RefCell<HashMap<PathBuf, Rc<Index>>> = RefCell::new(HashMap::new());
+}
+
+/// Resolve the docs link of an item, given by its path and optionally the crate version. The
+/// version defaults to `latest`, but can be a concrete version like `1.0.72` or a requirement
+/// like `^1.0` as well.
+///
+/// ```ignore
+/// const VEC_DOCS: &str = docsearch_macros::doc_link!("std::vec::Vec");
+/// const SELECT_DOCS: &str = docsearch_macros::doc_link!("tokio::select!", "^1.35");
+/// ```
+///
+/// Compilation fails if the item doesn't exist in the docs of the crate.
+#[proc_macro]
+pub fn doc_link(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+    let path = input.path.value();
+    let version = input.version.as_ref().map(LitStr::value);
+
+    let result = cache_dir().and_then(|dir| {
+        find_link(
+            &dir,
+            &path,
+            version.as_deref(),
+            env::var_os("DOCSEARCH_OFFLINE").is_some(),
+        )
+    });
+
+    match result {
+        Ok(link) => quote!(#link).into(),
+        Err(e) => syn::Error::new(input.path.span(), e)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// Arguments of the [`doc_link!`] macro.
+struct Input {
+    path: LitStr,
+    version: Option<LitStr>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut version = None;
+
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            version = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+
+        Ok(Self { path, version })
+    }
+}
+
+/// Location of the index cache, which is the same as for the `docsearch` command line tool.
+fn cache_dir() -> Result<PathBuf, String> {
+    match env::var_os("DOCSEARCH_CACHE_DIR") {
+        Some(dir) => Ok(dir.into()),
+        None => dirs::cache_dir()
+            .map(|dir| dir.join("docsearch"))
+            .ok_or_else(|| "failed locating the cache directory".to_owned()),
+    }
+}
+
+/// Find the link for the item path, in the index of the given crate version. Without any version,
+/// the latest one is used.
+fn find_link(
+    dir: &Path,
+    path: &str,
+    version: Option<&str>,
+    offline: bool,
+) -> Result<String, String> {
+    let path = path.parse::<SimplePath>().map_err(|e| e.to_string())?;
+    let version = version
+        .map_or(Ok(Version::Latest), str::parse)
+        .map_err(|e| e.to_string())?;
+
+    let index = load_index(dir, path.crate_name(), &version, offline)?;
+
+    index.find_link(&path).ok_or_else(|| {
+        format!(
+            "`{path}` not found in the docs of `{}@{}`",
+            index.name, index.version
+        )
+    })
+}
+
+/// Load the index from the cache, or download and save it if it isn't cached yet.
+fn load_index(
+    dir: &Path,
+    name: &str,
+    version: &Version,
+    offline: bool,
+) -> Result<Rc<Index>, String> {
+    // Stdlib crates always use the nightly docs, like the command line tool does by default.
+    let file = dir.join(CacheKey::new(name, version, StdChannel::Nightly).file_name());
+
+    if let Some(index) = LOADED.with(|loaded| loaded.borrow().get(&file).cloned()) {
+        return Ok(index);
+    }
+
+    let index = match fs::read_to_string(&file) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string())?,
+        Err(_) if offline => return Err(format!("`{name}@{version}` isn't cached yet")),
+        Err(_) => {
+            let index = Search::new(name, version.clone())
+                .run(&ureq::Agent::new())
+                .map_err(|e| format!("failed retrieving the docs of `{name}@{version}`: {e}"))?;
+
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            fs::write(
+                &file,
+                serde_json::to_string(&index).map_err(|e| e.to_string())?,
+            )
+            .map_err(|e| e.to_string())?;
+
+            index
+        }
+    };
+
+    let index = Rc::new(index);
+    LOADED.with(|loaded| loaded.borrow_mut().insert(file, Rc::clone(&index)));
+
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_find_link() {
+        let dir = env::temp_dir().join(format!("docsearch-macros-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mapping = BTreeMap::from([(
+            "my_crate::Config".to_owned(),
+            "my_crate/struct.Config.html".to_owned(),
+        )]);
+        for version in [Version::Latest, "^1.2".parse().unwrap()] {
+            let index = Index::from_mapping("my_crate", version.clone(), mapping.clone());
+            fs::write(
+                dir.join(CacheKey::new("my_crate", &version, StdChannel::Nightly).file_name()),
+                serde_json::to_string(&index).unwrap(),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            Ok("https://docs.rs/my_crate/latest/my_crate/struct.Config.html".to_owned()),
+            find_link(&dir, "my_crate::Config", None, true)
+        );
+        assert!(find_link(&dir, "my_crate::Config", Some("^1.2"), true).is_ok());
+        assert_eq!(
+            Err("`my_crate::Missing` not found in the docs of `my_crate@latest`".to_owned()),
+            find_link(&dir, "my_crate::Missing", None, true)
+        );
+        assert_eq!(
+            Err("`other@latest` isn't cached yet".to_owned()),
+            find_link(&dir, "other::Config", None, true)
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+//! Cargo subcommand to find the docs link of any item, for the exact versions of the current
+//! project's dependencies as resolved in its `Cargo.lock`.
+//!
+//! ```sh
+//! cargo docsearch tokio::select!
+//! cargo docsearch --manifest-path ../other/Cargo.toml serde::Serialize
+//! ```
+//!
+//! Crates that aren't a dependency of the project are searched in their latest version, and all
+//! options of the standalone `docsearch` tool are available as well.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{path::PathBuf, process::ExitCode};
+
+#[cfg(not(target_arch = "wasm32"))]
+use clap::{Args, Parser};
+#[cfg(not(target_arch = "wasm32"))]
+use docsearch::{workspace::Workspace, SimplePath, Version};
+
+#[cfg(not(target_arch = "wasm32"))]
+use self::cli::{Cli, Resolved};
+
+#[cfg(not(target_arch = "wasm32"))]
+#[path = "docsearch/cache.rs"]
+mod cache;
+#[cfg(not(target_arch = "wasm32"))]
+#[path = "docsearch/cli.rs"]
+mod cli;
+
+/// Cargo passes the subcommand name as first argument, so the command is wrapped accordingly.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cargo {
+    Docsearch(Docsearch),
+}
+
+/// Find the docs.rs (or stdlib) link of any item, for the exact version that the project depends
+/// on.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Args)]
+#[command(
+    disable_version_flag = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Docsearch {
+    /// Path to the `Cargo.toml` of the project, instead of locating it from the current directory.
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+    #[command(flatten)]
+    cli: Cli,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> ExitCode {
+    let Cargo::Docsearch(args) = Cargo::parse();
+    cli::run(args.cli, |path, version| {
+        resolve(args.manifest_path, path, version)
+    })
+}
+
+/// Pick the exact version that the project depends on, unless a version was given explicitly.
+/// Renamed dependencies are translated to their actual name in either case.
+///
+/// Any other crate, like the stdlib, uses the latest version. The same applies when running
+/// outside of a Cargo project, as long as no manifest path was given explicitly.
+#[cfg(not(target_arch = "wasm32"))]
+fn resolve(manifest_path: Option<PathBuf>, path: SimplePath, version: Option<Version>) -> Resolved {
+    let workspace = match Workspace::read(manifest_path.as_deref()) {
+        Ok(workspace) => workspace,
+        Err(_) if manifest_path.is_none() => {
+            return Ok((path, version.unwrap_or(Version::Latest)));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let version = version.unwrap_or_else(|| {
+        workspace
+            .find(&path)
+            .map_or(Version::Latest, |dep| Version::SemVer(dep.version.clone()))
+    });
+
+    Ok((workspace.translate(&path), version))
+}
+
+/// The tool needs network and file system access, which isn't available on WebAssembly.
+#[cfg(target_arch = "wasm32")]
+fn main() {}
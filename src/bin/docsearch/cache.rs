@@ -0,0 +1,86 @@
+//! Persistent cache of retrieved indexes, so repeated lookups don't download the multi-megabyte
+//! search indexes again.
+
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use docsearch::{CacheKey, Index};
+
+/// Directory with one JSON file for each cached index.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// Open the cache in the directory given by the `DOCSEARCH_CACHE_DIR` environment variable, or
+    /// the user's cache directory (like `~/.cache/docsearch` on Linux) otherwise.
+    pub fn open() -> Result<Self, Box<dyn Error>> {
+        let dir = match env::var_os("DOCSEARCH_CACHE_DIR") {
+            Some(dir) => dir.into(),
+            None => dirs::cache_dir()
+                .ok_or("failed locating the cache directory")?
+                .join("docsearch"),
+        };
+
+        Ok(Self { dir })
+    }
+
+    /// Location of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Load the index for the crate and requested version, if it's cached. Broken entries, for
+    /// example from an older release, are treated as missing.
+    pub fn load(&self, key: &CacheKey<'_>) -> Option<Index> {
+        let content = fs::read_to_string(self.dir.join(key.file_name())).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Save the index, replacing any previous entry.
+    pub fn save(&self, key: &CacheKey<'_>, index: &Index) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(
+            self.dir.join(key.file_name()),
+            serde_json::to_string(index)?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Load all cached indexes, sorted by name and version.
+    pub fn list(&self) -> Result<Vec<Index>, Box<dyn Error>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut indexes = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "json") {
+                if let Ok(index) = serde_json::from_str::<Index>(&fs::read_to_string(path)?) {
+                    indexes.push(index);
+                }
+            }
+        }
+
+        indexes.sort_by_cached_key(|index| (index.name.clone(), index.requested.to_string()));
+
+        Ok(indexes)
+    }
+
+    /// Remove all cached indexes.
+    pub fn clear(&self) -> Result<(), Box<dyn Error>> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
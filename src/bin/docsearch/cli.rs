@@ -0,0 +1,307 @@
+//! Implementation of the command line interface, that is shared between the standalone
+//! `docsearch` binary and the `cargo docsearch` subcommand.
+
+use std::{error::Error, process::ExitCode};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use docsearch::{
+    error::ErrorKind, CacheKey, DocHost, HttpFetch, Index, LinkStyle, Refresh, RefreshStep, Search,
+    SimplePath, StdChannel, Version,
+};
+
+use crate::cache::Cache;
+
+/// Item path and crate version to search for.
+pub type Resolved = Result<(SimplePath, Version), Box<dyn Error>>;
+
+/// Find the docs.rs (or stdlib) link of any item in a crate by its path.
+#[derive(Parser)]
+#[command(
+    disable_version_flag = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    lookup: Lookup,
+}
+
+#[derive(Args)]
+struct Lookup {
+    /// Version of the crate, either `latest`, a concrete version like `1.0.72` or a requirement
+    /// like `^1.0`. Defaults to `latest`, or the exact version that the project depends on with
+    /// `cargo docsearch`.
+    #[arg(short = 'V', long)]
+    version: Option<Version>,
+    #[command(flatten)]
+    options: Options,
+    /// Only answer from the cache, without any network access.
+    #[arg(long)]
+    offline: bool,
+    /// Output format of the link.
+    #[arg(long, value_enum, default_value = "plain")]
+    format: Format,
+    /// Pick the item from a fuzzy-searchable list of all items in the crate, instead of giving
+    /// its full path.
+    #[arg(short, long)]
+    interactive: bool,
+    /// Path of the item, like `anyhow::Result`, `std::vec::Vec` or `std::vec!`. Only the crate
+    /// name is used in interactive mode.
+    #[arg(required = true)]
+    path: Option<SimplePath>,
+}
+
+#[derive(Args)]
+struct Options {
+    /// Release channel of the stdlib docs, for paths into crates like `std` or `core`.
+    #[arg(long, value_enum, default_value = "nightly")]
+    std_channel: Channel,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage the cache of retrieved indexes.
+    #[command(subcommand)]
+    Cache(CacheCommand),
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// List all cached indexes.
+    List,
+    /// Remove all cached indexes.
+    Clear,
+    /// Retrieve the indexes of crates ahead of time, or update them if the docs changed.
+    Warm {
+        #[command(flatten)]
+        options: Options,
+        /// Crates to retrieve, optionally with a version like `anyhow@1.0.72`.
+        #[arg(required = true)]
+        crates: Vec<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Only the link itself.
+    Plain,
+    /// JSON object with the path, crate, version and link, for scripts.
+    Json,
+    /// Markdown link with the path as text, for chat messages or docs.
+    Markdown,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Channel {
+    Nightly,
+    Beta,
+    Stable,
+}
+
+impl From<Channel> for StdChannel {
+    fn from(channel: Channel) -> Self {
+        match channel {
+            Channel::Nightly => Self::Nightly,
+            Channel::Beta => Self::Beta,
+            Channel::Stable => Self::Stable,
+        }
+    }
+}
+
+/// Run the command, reporting any errors on stderr.
+///
+/// The `resolve` function decides on the crate version, and receives the one given with
+/// `--version`, if any. It can translate the item path as well, for example to follow renamed
+/// dependencies.
+pub fn run(cli: Cli, resolve: impl FnOnce(SimplePath, Option<Version>) -> Resolved) -> ExitCode {
+    let result = match cli.command {
+        Some(Command::Cache(command)) => cache(command),
+        None => lookup(cli.lookup, resolve),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Print the link for a single item path.
+fn lookup(
+    lookup: Lookup,
+    resolve: impl FnOnce(SimplePath, Option<Version>) -> Resolved,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let path = lookup.path.ok_or("missing item path")?;
+    let (path, version) = resolve(path, lookup.version)?;
+
+    let cache = Cache::open()?;
+    let index = load_index(
+        &cache,
+        path.crate_name(),
+        &version,
+        lookup.options.std_channel,
+        lookup.offline,
+    )?;
+
+    let path = if lookup.interactive {
+        match pick(&index)? {
+            Some(path) => path,
+            None => return Ok(ExitCode::FAILURE),
+        }
+    } else {
+        path
+    };
+
+    Ok(if let Some(link) = index.find_link(&path) {
+        print_link(lookup.format, &index, &path, &link);
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("error: `{path}` not found in the docs");
+        ExitCode::FAILURE
+    })
+}
+
+/// Let the user pick one of the items in the index with a fuzzy search. Returns [`None`] if the
+/// picker was aborted.
+fn pick(index: &Index) -> Result<Option<SimplePath>, Box<dyn Error>> {
+    if !dialoguer::console::Term::stderr().is_term() {
+        return Err("interactive mode requires a terminal".into());
+    }
+
+    let items = index.mapping.keys().collect::<Vec<_>>();
+    let selection = dialoguer::FuzzySelect::new()
+        .with_prompt(format!("Search {}", index.name))
+        .items(&items)
+        .interact_opt()?;
+
+    selection
+        .map(|pos| items[pos].parse().map_err(Into::into))
+        .transpose()
+}
+
+fn print_link(format: Format, index: &Index, path: &SimplePath, link: &str) {
+    match format {
+        Format::Plain => println!("{link}"),
+        Format::Json => println!(
+            "{}",
+            serde_json::json!({
+                "path": path,
+                "crate": index.name,
+                "version": index.version.to_string(),
+                "url": link,
+            })
+        ),
+        Format::Markdown => println!("{}", LinkStyle::Markdown.render(path.as_ref(), link)),
+    }
+}
+
+fn cache(command: CacheCommand) -> Result<ExitCode, Box<dyn Error>> {
+    let cache = Cache::open()?;
+
+    match command {
+        CacheCommand::List => {
+            for index in cache.list()? {
+                println!(
+                    "{}@{} ({}, {} items)",
+                    index.name,
+                    index.requested,
+                    index.version,
+                    index.mapping.len()
+                );
+            }
+        }
+        CacheCommand::Clear => {
+            cache.clear()?;
+            println!("cleared {}", cache.dir().display());
+        }
+        CacheCommand::Warm { options, crates } => {
+            for spec in crates {
+                let (name, version) = spec.split_once('@').unwrap_or((&spec, "latest"));
+                let version = version.parse::<Version>()?;
+                let index = load_index(&cache, name, &version, options.std_channel, false)?;
+                println!("cached {}@{} ({})", index.name, version, index.version);
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Load the index for the crate from the cache, and refresh it if the docs changed. Without a
+/// cached index, it's retrieved with a new search.
+fn load_index(
+    cache: &Cache,
+    name: &str,
+    version: &Version,
+    channel: Channel,
+    offline: bool,
+) -> Result<Index, Box<dyn Error>> {
+    let key = CacheKey::new(name, version, channel.into());
+    let cached = cache.load(&key);
+
+    if offline {
+        return cached.ok_or_else(|| format!("`{name}@{version}` isn't cached yet").into());
+    }
+
+    let agent = ureq::Agent::new();
+    let index = match cached {
+        Some(cached) => refresh(&agent, cached)?,
+        None => {
+            let state = docsearch::start_search(name, version.clone())
+                .with_host(DocHost::default().std_channel(channel.into()));
+            Search::from(state).run(&agent)?
+        }
+    };
+
+    cache.save(&key, &index)?;
+
+    Ok(index)
+}
+
+/// Check whether the cached index is still up-to-date, and retrieve the new one otherwise. Like a
+/// [`Search`], the check is retried with the alternative spelling of the crate name if the docs
+/// aren't found. If the docs can't be retrieved temporarily, the cached index is kept.
+fn refresh(agent: &ureq::Agent, cached: Index) -> Result<Index, Box<dyn Error>> {
+    let step = match check(agent, docsearch::refresh(&cached)) {
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            match docsearch::refresh(&cached).with_alternative_name() {
+                Some(state) => check(agent, state),
+                None => Err(e),
+            }
+        }
+        step => step,
+    };
+
+    let result = match step {
+        Ok(RefreshStep::Unchanged) => return Ok(cached),
+        Ok(RefreshStep::Changed(state)) => Search::from(*state).run(agent),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(index) => Ok(index),
+        Err(e) if matches!(e.kind(), ErrorKind::RateLimited | ErrorKind::Http) => {
+            eprintln!(
+                "warning: failed refreshing `{}`, using the cached index: {e}",
+                cached.name
+            );
+            Ok(cached)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Download the docs page of the refresh state and compare it with the cached index.
+fn check<'a>(agent: &ureq::Agent, state: Refresh<'a>) -> docsearch::error::Result<RefreshStep<'a>> {
+    let page = agent.fetch(&state.request())?;
+    let state = match &page.final_url {
+        Some(url) => state.resolve_version(url),
+        None => state,
+    };
+
+    state.check_with_status(page.status, &page.body)
+}
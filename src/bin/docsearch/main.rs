@@ -0,0 +1,33 @@
+//! Command line tool to find the docs link of any item in a crate or the stdlib.
+//!
+//! ```sh
+//! docsearch anyhow::Result
+//! docsearch --version 1.0.72 anyhow::Context
+//! docsearch --std-channel stable std::vec::Vec
+//! docsearch --offline anyhow::Error
+//! docsearch --format markdown anyhow::bail!
+//! docsearch --interactive anyhow
+//! docsearch cache warm anyhow@1.0.72 std
+//! ```
+//!
+//! Retrieved indexes are cached, and only downloaded again if the docs changed. The cache is
+//! located in the user's cache directory, or the directory given by `DOCSEARCH_CACHE_DIR`.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod cache;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> std::process::ExitCode {
+    use clap::Parser;
+    use docsearch::Version;
+
+    cli::run(cli::Cli::parse(), |path, version| {
+        Ok((path, version.unwrap_or(Version::Latest)))
+    })
+}
+
+/// The tool needs network and file system access, which isn't available on WebAssembly.
+#[cfg(target_arch = "wasm32")]
+fn main() {}
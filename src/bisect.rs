@@ -0,0 +1,191 @@
+//! Bisection over the releases of a crate, to find the version that introduced an item.
+
+use crate::{Index, SearchPage, SimplePath, Version};
+
+/// Start the search for the first version of a crate, whose index contains the given item. This
+/// answers questions like _"since when does `X` exist"_.
+///
+/// The versions are checked with a binary search, so only a few indexes need to be retrieved, even
+/// for crates with many releases. The list of versions can be retrieved from the sparse index or
+/// with [`list_versions`](crate::list_versions) for example. It is assumed, that an item is never
+/// removed again once it was introduced.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn retrieve_index(state: docsearch::SearchPage<'_>) -> docsearch::Index { unimplemented!() }
+/// use docsearch::BisectStep;
+///
+/// let path = "anyhow::Chain".parse().unwrap();
+/// let versions = ["1.0.0", "1.0.10", "1.0.20", "1.0.30"].map(|v| v.parse().unwrap());
+///
+/// let mut step = docsearch::bisect_introduction(path, versions);
+/// let version = loop {
+///     match step {
+///         BisectStep::Check(bisect) => {
+///             let index = retrieve_index(bisect.start_search());
+///             step = bisect.check(&index);
+///         }
+///         BisectStep::Done(version) => break version,
+///     }
+/// };
+///
+/// match version {
+///     Some(version) => println!("introduced in {version}"),
+///     None => println!("not found in any version"),
+/// }
+/// ```
+pub fn bisect_introduction(
+    path: SimplePath,
+    versions: impl IntoIterator<Item = semver::Version>,
+) -> BisectStep {
+    let mut versions = versions.into_iter().collect::<Vec<_>>();
+    versions.sort();
+    versions.dedup();
+
+    Bisect {
+        path,
+        low: 0,
+        high: versions.len(),
+        versions,
+    }
+    .step()
+}
+
+/// State of the bisection, that describes the version to check next. Use [`Self::start_search`]
+/// to retrieve the [`Index`] of that version, and pass it to [`Self::check`] to continue.
+pub struct Bisect {
+    path: SimplePath,
+    versions: Vec<semver::Version>,
+    /// Lower bound of the remaining range of candidates (inclusive).
+    low: usize,
+    /// Upper bound of the remaining range of candidates (exclusive). A value equal to the amount
+    /// of versions means the item wasn't found in any checked version so far.
+    high: usize,
+}
+
+impl Bisect {
+    /// The version that needs to be checked next.
+    #[must_use]
+    pub fn version(&self) -> &semver::Version {
+        &self.versions[self.mid()]
+    }
+
+    /// Start the search for the [`Index`] of the version that needs to be checked next.
+    #[must_use]
+    pub fn start_search(&self) -> SearchPage<'_> {
+        crate::start_search(
+            self.path.crate_name(),
+            Version::SemVer(self.version().clone()),
+        )
+    }
+
+    /// Upper bound of the indexes that still need to be checked, including the current one. This
+    /// allows to plan the remaining retrievals, for example to show progress.
+    #[must_use]
+    pub fn remaining_steps(&self) -> u32 {
+        (self.high - self.low + 1)
+            .next_power_of_two()
+            .trailing_zeros()
+    }
+
+    /// Check whether the index of [`Self::version`] contains the item, and continue the
+    /// bisection accordingly.
+    #[must_use]
+    pub fn check(self, index: &Index) -> BisectStep {
+        let found = index.contains(&self.path);
+        self.record(found)
+    }
+
+    /// Record whether [`Self::version`] contains the item, without an [`Index`]. This is useful
+    /// if the existence is known from other sources, like a cache.
+    #[must_use]
+    pub fn record(mut self, found: bool) -> BisectStep {
+        let mid = self.mid();
+
+        if found {
+            self.high = mid;
+        } else {
+            self.low = mid + 1;
+        }
+
+        self.step()
+    }
+
+    fn mid(&self) -> usize {
+        self.low + (self.high - self.low) / 2
+    }
+
+    fn step(self) -> BisectStep {
+        if self.low < self.high {
+            BisectStep::Check(self)
+        } else {
+            BisectStep::Done(self.versions.into_iter().nth(self.low))
+        }
+    }
+}
+
+/// Result of a single step of the bisection.
+pub enum BisectStep {
+    /// Another version needs to be checked.
+    Check(Bisect),
+    /// The bisection finished with the first version that contains the item, or [`None`] if none
+    /// of the versions contain it.
+    Done(Option<semver::Version>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(versions: &[&str], introduced: Option<usize>) -> (Option<semver::Version>, usize) {
+        let versions = versions
+            .iter()
+            .map(|v| v.parse().unwrap())
+            .collect::<Vec<semver::Version>>();
+        let threshold = introduced.map(|i| versions[i].clone());
+
+        let mut step = bisect_introduction("anyhow::Chain".parse().unwrap(), versions);
+        let mut checks = 0;
+
+        loop {
+            match step {
+                BisectStep::Check(bisect) => {
+                    checks += 1;
+                    let found = threshold.as_ref().map_or(false, |t| bisect.version() >= t);
+                    step = bisect.record(found);
+                }
+                BisectStep::Done(version) => return (version, checks),
+            }
+        }
+    }
+
+    #[test]
+    fn test_bisect_introduction() {
+        let versions = [
+            "1.0.0", "1.0.1", "1.0.2", "1.0.3", "1.0.4", "1.0.5", "1.0.6",
+        ];
+
+        for i in 0..versions.len() {
+            let (version, checks) = run(&versions, Some(i));
+            assert_eq!(Some(versions[i].parse().unwrap()), version);
+            assert!(checks <= 3);
+        }
+
+        assert_eq!((None, 3), run(&versions, None));
+        assert_eq!((None, 0), run(&[], None));
+    }
+
+    #[test]
+    fn test_remaining_steps() {
+        let step = bisect_introduction(
+            "anyhow::Chain".parse().unwrap(),
+            (0..7).map(|patch| semver::Version::new(1, 0, patch)),
+        );
+
+        match step {
+            BisectStep::Check(bisect) => assert_eq!(3, bisect.remaining_steps()),
+            BisectStep::Done(_) => panic!("expected bisection to continue"),
+        }
+    }
+}
@@ -0,0 +1,597 @@
+//! Information about the releases of a crate and their builds on docs.rs, as well as a fallback to
+//! the most recent version, whose docs were built successfully.
+
+use std::borrow::Cow;
+
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{
+    crates,
+    error::{Error, Result},
+    host::DOCSRS_URL,
+    metadata, registry, DocHost, Request, SearchPage, Version,
+};
+
+/// Start the search for the most recent version of a crate, that is older than the given one and
+/// whose docs were built successfully. This is the follow-up for an
+/// [`Error::DocsBuildFailed`](crate::error::Error::DocsBuildFailed) error.
+///
+/// The flow first retrieves all releases from the crates.io sparse index, and then checks the
+/// docs.rs builds of each release from newest to oldest, until a successful one is found.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn download(url: &str) -> String { unimplemented!() }
+/// use docsearch::BuildStep;
+///
+/// let state = docsearch::start_build_fallback("anyhow", "1.0.72".parse().unwrap());
+/// let body = download(state.url());
+/// let mut state = state.find_candidates(&body).unwrap();
+///
+/// let page = loop {
+///     let body = download(state.url());
+///     match state.check_build(&body).unwrap() {
+///         BuildStep::Found(page) => break *page,
+///         BuildStep::Next(next) => state = next,
+///     }
+/// };
+///
+/// // Continue with the search as usual.
+/// println!("{}", page.url());
+/// ```
+#[must_use]
+pub fn start_build_fallback(name: &str, failed: semver::Version) -> BuildFallback<'_> {
+    BuildFallback {
+        name,
+        failed,
+        url: registry::get_index_url(name),
+    }
+}
+
+/// First state of the build fallback, that retrieves the list of all releases. Use the
+/// [`Self::url`] function to get the URL of the sparse index file to download. Its content must
+/// then be passed to [`Self::find_candidates`] to get to the next state.
+pub struct BuildFallback<'a> {
+    name: &'a str,
+    failed: semver::Version,
+    url: String,
+}
+
+impl<'a> BuildFallback<'a> {
+    /// URL to the sparse index file that should be retrieved and passed to
+    /// [`Self::find_candidates`].
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Full description of the request to retrieve the content for [`Self::find_candidates`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        Request::get(&self.url)
+    }
+
+    /// Collect all releases older than the failed version as candidates, excluding yanked ones
+    /// and pre-releases (unless the failed version was a pre-release itself).
+    pub fn find_candidates(self, body: &str) -> Result<BuildCheck<'a>> {
+        let allow_pre = !self.failed.pre.is_empty();
+        let mut candidates = registry::parse_releases(body)
+            .map_err(|e| e.with_url(&self.url))?
+            .into_iter()
+            .filter(|r| !r.yanked && r.vers < self.failed && (allow_pre || r.vers.pre.is_empty()))
+            .map(|r| r.vers)
+            .collect::<Vec<_>>();
+
+        // Sorted in ascending order, so the most recent version can be popped from the end.
+        candidates.sort();
+        debug!(
+            "found {} candidates for the build fallback",
+            candidates.len()
+        );
+
+        BuildCheck::new(self.name, candidates)
+    }
+}
+
+/// State that checks the docs.rs builds of a single candidate version. Use the [`Self::url`]
+/// function to get the URL of the build list to download. Its content must then be passed to
+/// [`Self::check_build`], which either finishes the fallback or continues with the next candidate.
+pub struct BuildCheck<'a> {
+    name: &'a str,
+    candidates: Vec<semver::Version>,
+    current: semver::Version,
+    url: String,
+}
+
+impl<'a> BuildCheck<'a> {
+    fn new(name: &'a str, mut candidates: Vec<semver::Version>) -> Result<Self> {
+        let current = candidates
+            .pop()
+            .ok_or_else(|| Error::NoSuccessfulBuild(name.to_owned()))?;
+        let url = format!("{DOCSRS_URL}/crate/{name}/{current}/builds.json");
+
+        Ok(Self {
+            name,
+            candidates,
+            current,
+            url,
+        })
+    }
+
+    /// The candidate version, whose builds are currently checked.
+    #[must_use]
+    pub fn version(&self) -> &semver::Version {
+        &self.current
+    }
+
+    /// URL to the build list that should be retrieved and passed to [`Self::check_build`].
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Full description of the request to retrieve the content for [`Self::check_build`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        Request::get(&self.url)
+    }
+
+    /// Check the build list downloaded from [`Self::url`]. If any of the builds succeeded, the
+    /// search is retargeted at the candidate version. Otherwise, the next older candidate is
+    /// checked, or an error returned if no candidates are left.
+    pub fn check_build(self, body: &str) -> Result<BuildStep<'a>> {
+        let builds = serde_json::from_str::<Vec<Build>>(body)
+            .map_err(|e| Error::from(e).with_url(&self.url))?;
+
+        if builds.iter().any(|build| build.build_status.is_success()) {
+            debug!("found successful build for version {}", self.current);
+
+            let version = Version::SemVer(self.current);
+            let host = DocHost::default();
+            let url = crates::get_page_url(&host, false, self.name, &version);
+
+            Ok(BuildStep::Found(Box::new(SearchPage {
+                name: Cow::Borrowed(self.name),
+                requested: version.clone(),
+                version,
+                std: false,
+                host,
+                root: None,
+                url,
+                observer: None,
+            })))
+        } else {
+            Self::new(self.name, self.candidates).map(BuildStep::Next)
+        }
+    }
+}
+
+/// Result of checking the builds of a single candidate version.
+pub enum BuildStep<'a> {
+    /// A successfully built version was found and the search can continue with its docs page.
+    Found(Box<SearchPage<'a>>),
+    /// The candidate didn't build successfully either, continue with the next one.
+    Next(BuildCheck<'a>),
+}
+
+/// List all versions of a crate together with their status. This is useful to build version
+/// pickers, for example in bots or CLIs.
+///
+/// The list is retrieved from the JSON API of crates.io, which is the source of truth for the
+/// published versions. It doesn't contain the docs.rs build status, which is retrieved for each
+/// release separately by the follow-up [`ReleaseBuilds`] state.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn download(url: &str) -> String { unimplemented!() }
+/// use docsearch::ReleaseBuildStep;
+///
+/// let state = docsearch::list_versions("anyhow");
+/// let body = download(state.url());
+/// let mut state = state.parse_with_builds(&body).unwrap();
+///
+/// let releases = loop {
+///     let body = download(state.url());
+///     match state.check_build(&body).unwrap() {
+///         ReleaseBuildStep::Done(releases) => break releases,
+///         ReleaseBuildStep::Next(next) => state = next,
+///     }
+/// };
+///
+/// for release in releases {
+///     println!("{}: {:?}, docs {:?}", release.version, release.status, release.docs);
+/// }
+/// ```
+#[must_use]
+pub fn list_versions(name: &str) -> VersionList<'_> {
+    VersionList {
+        name,
+        url: metadata::get_url(name),
+    }
+}
+
+/// Single state to retrieve the list of versions of a crate. Use the [`Self::url`] function to get
+/// the URL of the crates.io API to download. Its content must then be passed to [`Self::parse`].
+pub struct VersionList<'a> {
+    name: &'a str,
+    url: String,
+}
+
+impl<'a> VersionList<'a> {
+    /// URL to the crates.io API that should be retrieved and passed to [`Self::parse`].
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Full description of the request to retrieve the content for [`Self::parse`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        Request::get(&self.url)
+    }
+
+    /// Parse the releases from the content downloaded from [`Self::url`] and continue with the
+    /// retrieval of their docs.rs build status, starting with the newest release.
+    pub fn parse_with_builds(self, body: &str) -> Result<ReleaseBuilds<'a>> {
+        let name = self.name;
+        let releases = self.parse(body)?;
+
+        Ok(ReleaseBuilds::new(name, releases, 0))
+    }
+
+    /// Parse the releases from the content downloaded from [`Self::url`]. The releases are ordered
+    /// from the newest to the oldest version. Their [`Release::docs`] status isn't known yet, use
+    /// [`Self::parse_with_builds`] to retrieve it as well.
+    pub fn parse(self, body: &str) -> Result<Vec<Release>> {
+        #[derive(Deserialize)]
+        struct Response {
+            #[serde(default)]
+            versions: Vec<ApiVersion>,
+        }
+
+        #[derive(Deserialize)]
+        struct ApiVersion {
+            #[serde(deserialize_with = "crate::version::deserialize_semver")]
+            num: semver::Version,
+            #[serde(default)]
+            yanked: bool,
+        }
+
+        let response = serde_json::from_str::<Response>(body)
+            .map_err(|e| Error::from(e).with_url(&self.url))?;
+        if response.versions.is_empty() {
+            return Err(Error::CrateNotFound(self.name.to_owned()));
+        }
+
+        let mut releases = response
+            .versions
+            .into_iter()
+            .map(|version| Release {
+                version: version.num,
+                status: if version.yanked {
+                    ReleaseStatus::Yanked
+                } else {
+                    ReleaseStatus::Available
+                },
+                docs: None,
+            })
+            .collect::<Vec<_>>();
+
+        releases.sort_by(|a, b| b.version.cmp(&a.version));
+        releases.dedup_by(|a, b| a.version == b.version);
+
+        Ok(releases)
+    }
+}
+
+/// State that retrieves the docs.rs build status of each release in a list, one after another.
+/// Use the [`Self::url`] function to get the URL of the build list to download. Its content must
+/// then be passed to [`Self::check_build`], which either continues with the next release or
+/// finishes with the complete list.
+///
+/// As this needs one request for every release, [`Self::finish`] allows to stop early, for
+/// example after the most recent releases.
+pub struct ReleaseBuilds<'a> {
+    name: &'a str,
+    releases: Vec<Release>,
+    current: usize,
+    url: String,
+}
+
+impl<'a> ReleaseBuilds<'a> {
+    fn new(name: &'a str, releases: Vec<Release>, current: usize) -> Self {
+        let url = format!(
+            "{DOCSRS_URL}/crate/{name}/{}/builds.json",
+            releases[current].version
+        );
+
+        Self {
+            name,
+            releases,
+            current,
+            url,
+        }
+    }
+
+    /// The release, whose builds are currently checked.
+    #[must_use]
+    pub fn version(&self) -> &semver::Version {
+        &self.releases[self.current].version
+    }
+
+    /// URL to the build list that should be retrieved and passed to [`Self::check_build`].
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Full description of the request to retrieve the content for [`Self::check_build`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        Request::get(&self.url)
+    }
+
+    /// Record the build status from the build list downloaded from [`Self::url`] and continue with
+    /// the next release, or return all releases if this was the last one.
+    pub fn check_build(mut self, body: &str) -> Result<ReleaseBuildStep<'a>> {
+        let builds = serde_json::from_str::<Vec<Build>>(body)
+            .map_err(|e| Error::from(e).with_url(&self.url))?;
+        self.releases[self.current].docs = Some(DocsStatus::from_builds(&builds));
+
+        let next = self.current + 1;
+        Ok(if next < self.releases.len() {
+            ReleaseBuildStep::Next(Self::new(self.name, self.releases, next))
+        } else {
+            ReleaseBuildStep::Done(self.releases)
+        })
+    }
+
+    /// Stop checking the builds and return all releases. The [`Release::docs`] status of the
+    /// current and all following releases stays unknown.
+    #[must_use]
+    pub fn finish(self) -> Vec<Release> {
+        self.releases
+    }
+}
+
+/// Result of checking the builds of a single release.
+pub enum ReleaseBuildStep<'a> {
+    /// The builds of all releases were checked.
+    Done(Vec<Release>),
+    /// Continue with the builds of the next release.
+    Next(ReleaseBuilds<'a>),
+}
+
+/// Single release of a crate, as listed on crates.io.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Release {
+    /// Version of the release.
+    pub version: semver::Version,
+    /// Status of the release.
+    pub status: ReleaseStatus,
+    /// Status of the docs on docs.rs, or [`None`] if it wasn't retrieved with [`ReleaseBuilds`].
+    pub docs: Option<DocsStatus>,
+}
+
+/// Status of a release on crates.io.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ReleaseStatus {
+    /// The release can be used.
+    Available,
+    /// The release was yanked.
+    Yanked,
+}
+
+/// Status of the docs of a release on docs.rs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DocsStatus {
+    /// At least one build succeeded and the docs are available.
+    Built,
+    /// The docs are currently being built, or are queued for it.
+    InProgress,
+    /// All builds failed, so there are no docs.
+    Failed,
+    /// The release was never built, for example because it's too old for docs.rs.
+    NotBuilt,
+}
+
+impl DocsStatus {
+    /// Determine the overall status from the list of all builds of a release.
+    fn from_builds(builds: &[Build]) -> Self {
+        if builds.iter().any(|build| build.build_status.is_success()) {
+            Self::Built
+        } else if builds.iter().any(|build| build.build_status.is_pending()) {
+            Self::InProgress
+        } else if builds.is_empty() {
+            Self::NotBuilt
+        } else {
+            Self::Failed
+        }
+    }
+}
+
+/// Single build entry of the docs.rs builds API.
+#[derive(Deserialize)]
+struct Build {
+    build_status: BuildStatus,
+}
+
+/// Status of a docs.rs build, which used to be a simple flag but is a descriptive string in newer
+/// versions of the API.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BuildStatus {
+    Flag(bool),
+    Name(String),
+}
+
+impl BuildStatus {
+    fn is_success(&self) -> bool {
+        match self {
+            Self::Flag(success) => *success,
+            Self::Name(name) => name == "success",
+        }
+    }
+
+    fn is_pending(&self) -> bool {
+        matches!(self, Self::Name(name) if name == "in_progress" || name == "queued")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_versions() {
+        let state = list_versions("anyhow");
+        assert_eq!("https://crates.io/api/v1/crates/anyhow", state.url());
+
+        let releases = state
+            .parse(
+                r#"{"crate":{"name":"anyhow"},"versions":[
+                    {"num":"1.0.46","yanked":true},
+                    {"num":"1.0.53","yanked":false},
+                    {"num":"1.0.9","yanked":false}
+                ]}"#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                Release {
+                    version: semver::Version::new(1, 0, 53),
+                    status: ReleaseStatus::Available,
+                    docs: None,
+                },
+                Release {
+                    version: semver::Version::new(1, 0, 46),
+                    status: ReleaseStatus::Yanked,
+                    docs: None,
+                },
+                Release {
+                    version: semver::Version::new(1, 0, 9),
+                    status: ReleaseStatus::Available,
+                    docs: None,
+                },
+            ],
+            releases
+        );
+
+        let err = list_versions("nope")
+            .parse(r#"{"errors":[{"detail":"crate `nope` does not exist"}]}"#)
+            .unwrap_err();
+        assert!(matches!(err, Error::CrateNotFound(name) if name == "nope"));
+    }
+
+    #[test]
+    fn test_release_builds() {
+        let state = list_versions("anyhow")
+            .parse_with_builds(
+                r#"{"versions":[
+                    {"num":"1.0.70","yanked":false},
+                    {"num":"1.0.71","yanked":false},
+                    {"num":"1.0.72","yanked":true}
+                ]}"#,
+            )
+            .unwrap();
+        assert_eq!(
+            "https://docs.rs/crate/anyhow/1.0.72/builds.json",
+            state.url()
+        );
+
+        let state = match state
+            .check_build(r#"[{"id":2,"build_status":"failure"},{"id":1,"build_status":true}]"#)
+            .unwrap()
+        {
+            ReleaseBuildStep::Next(state) => state,
+            ReleaseBuildStep::Done(_) => panic!("more releases expected"),
+        };
+        assert_eq!("1.0.71", state.version().to_string());
+
+        let state = match state
+            .check_build(r#"[{"id":3,"build_status":"in_progress"}]"#)
+            .unwrap()
+        {
+            ReleaseBuildStep::Next(state) => state,
+            ReleaseBuildStep::Done(_) => panic!("more releases expected"),
+        };
+
+        let partial = state.finish();
+        assert_eq!(
+            vec![Some(DocsStatus::Built), Some(DocsStatus::InProgress), None],
+            partial.iter().map(|r| r.docs).collect::<Vec<_>>()
+        );
+
+        let state = ReleaseBuilds::new("anyhow", partial, 2);
+        match state.check_build("[]").unwrap() {
+            ReleaseBuildStep::Done(releases) => {
+                assert_eq!(Some(DocsStatus::NotBuilt), releases[2].docs);
+            }
+            ReleaseBuildStep::Next(_) => panic!("no more releases expected"),
+        }
+
+        let state = ReleaseBuilds::new(
+            "anyhow",
+            vec![Release {
+                version: semver::Version::new(1, 0, 0),
+                status: ReleaseStatus::Available,
+                docs: None,
+            }],
+            0,
+        );
+        match state
+            .check_build(r#"[{"id":4,"build_status":"failure"}]"#)
+            .unwrap()
+        {
+            ReleaseBuildStep::Done(releases) => {
+                assert_eq!(Some(DocsStatus::Failed), releases[0].docs);
+            }
+            ReleaseBuildStep::Next(_) => panic!("no more releases expected"),
+        }
+    }
+
+    #[test]
+    fn test_fallback() {
+        let state = start_build_fallback("anyhow", "1.0.72".parse().unwrap());
+        assert_eq!("https://index.crates.io/an/yh/anyhow", state.url());
+
+        let state = state
+            .find_candidates(concat!(
+                r#"{"name":"anyhow","vers":"1.0.70","yanked":false}"#,
+                "\n",
+                r#"{"name":"anyhow","vers":"1.0.71","yanked":false}"#,
+                "\n",
+                r#"{"name":"anyhow","vers":"1.0.72","yanked":false}"#,
+            ))
+            .unwrap();
+        assert_eq!(
+            "https://docs.rs/crate/anyhow/1.0.71/builds.json",
+            state.url()
+        );
+
+        let state = match state
+            .check_build(r#"[{"id":2,"build_status":"failure"},{"id":1,"build_status":false}]"#)
+            .unwrap()
+        {
+            BuildStep::Next(state) => state,
+            BuildStep::Found(_) => panic!("no successful build expected"),
+        };
+        assert_eq!("1.0.70", state.version().to_string());
+
+        match state
+            .check_build(r#"[{"id":3,"build_status":"success"}]"#)
+            .unwrap()
+        {
+            BuildStep::Found(page) => {
+                assert_eq!("https://docs.rs/anyhow/1.0.70/anyhow/", page.url());
+            }
+            BuildStep::Next(_) => panic!("successful build expected"),
+        }
+    }
+}
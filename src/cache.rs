@@ -0,0 +1,113 @@
+//! Shared naming of cached indexes, so different tools can use the same cache directory.
+
+use std::fmt::Write;
+
+use crate::{crates, StdChannel, Version, STD_CRATES};
+
+/// Identifier of a cached [`Index`](crate::Index), which is the crate together with the requested
+/// version. For stdlib crates, the release channel of the docs is part of it as well.
+///
+/// The crate name is normalized to its spelling in paths, so `tokio-util` and `tokio_util` share
+/// the same entry. Tools that keep indexes in the same directory, like the `docsearch` command
+/// line tool and the `doc_link!` macro, find each other's entries through [`Self::file_name`].
+///
+/// ```rust
+/// use docsearch::{CacheKey, StdChannel, Version};
+///
+/// let key = CacheKey::new("tokio-util", &Version::Latest, StdChannel::Stable);
+/// assert_eq!("tokio_util-latest.json", key.file_name());
+///
+/// let key = CacheKey::new("std", &Version::Latest, StdChannel::Stable);
+/// assert_eq!("std-stable-latest.json", key.file_name());
+///
+/// let version = "^0.7".parse().unwrap();
+/// let key = CacheKey::new("tokio-util", &version, StdChannel::Stable);
+/// assert_eq!("tokio_util-%5E0.7.json", key.file_name());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey<'a> {
+    name: &'a str,
+    version: &'a Version,
+    channel: Option<StdChannel>,
+}
+
+impl<'a> CacheKey<'a> {
+    /// Create the key for the crate and requested version. The channel is only used for crates
+    /// of the stdlib.
+    #[must_use]
+    pub fn new(name: &'a str, version: &'a Version, channel: StdChannel) -> Self {
+        let std = STD_CRATES.contains(&&*crates::crate_name(name));
+
+        Self {
+            name,
+            version,
+            channel: std.then_some(channel),
+        }
+    }
+
+    /// Name of the cache file for this key, like `anyhow-1.0.72.json`. Any characters that aren't
+    /// safe in file names, like the `^` of version requirements, are percent-encoded. Therefore,
+    /// different requirements like `^0.7` and `~0.7` never share the same file.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        let name = crates::crate_name(self.name);
+        let name = match self.channel {
+            Some(channel) => format!("{name}-{}-{}", channel_name(channel), self.version),
+            None => format!("{name}-{}", self.version),
+        };
+
+        let mut file_name = String::with_capacity(name.len() + 5);
+        for b in name.bytes() {
+            if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.') {
+                file_name.push(b as char);
+            } else {
+                write!(file_name, "%{b:02X}").ok();
+            }
+        }
+
+        file_name.push_str(".json");
+        file_name
+    }
+}
+
+const fn channel_name(channel: StdChannel) -> &'static str {
+    match channel {
+        StdChannel::Nightly => "nightly",
+        StdChannel::Beta => "beta",
+        StdChannel::Stable => "stable",
+        StdChannel::Unversioned => "unversioned",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        let file_name = |name, version: &str, channel| {
+            CacheKey::new(name, &version.parse().unwrap(), channel).file_name()
+        };
+
+        assert_eq!(
+            "anyhow-latest.json",
+            file_name("anyhow", "latest", StdChannel::Nightly)
+        );
+        assert_eq!(
+            "std-nightly-1.70.0.json",
+            file_name("std", "1.70.0", StdChannel::Nightly)
+        );
+        assert_eq!(
+            "tokio_util-%5E0.7.json",
+            file_name("tokio-util", "^0.7", StdChannel::Beta)
+        );
+        assert_eq!(
+            "tokio_util-%7E0.7.json",
+            file_name("tokio-util", "~0.7", StdChannel::Beta)
+        );
+        assert_ne!(
+            file_name("tokio-util", "=0.7", StdChannel::Beta),
+            file_name("tokio-util", "^0.7", StdChannel::Beta)
+        );
+    }
+}
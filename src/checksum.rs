@@ -0,0 +1,104 @@
+//! Verification of downloaded index content against a known checksum.
+
+use std::{fmt, str::FromStr};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::ParseError;
+
+/// SHA-256 checksum of the content of a search index. A checksum that was recorded earlier, like
+/// in a lockfile of cached crates, can be passed to [`SearchIndex::checksum`](crate::SearchIndex::checksum)
+/// to detect truncated or tampered downloads.
+///
+/// The checksum is represented as lowercase hex string.
+///
+/// ```rust
+/// use docsearch::Checksum;
+///
+/// let checksum = Checksum::of("var searchIndex = {};");
+/// let recorded = checksum.to_string();
+///
+/// assert_eq!(checksum, recorded.parse().unwrap());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Checksum([u8; 32]);
+
+impl Checksum {
+    /// Calculate the checksum of the given index content.
+    #[must_use]
+    pub fn of(content: &str) -> Self {
+        Self(Sha256::digest(content).into())
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+
+impl FromStr for Checksum {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 64 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseError::InvalidChecksum);
+        }
+
+        let mut hash = [0; 32];
+        for (b, hex) in hash.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+            // The value only contains hex digits, so each chunk is valid UTF-8.
+            let hex = std::str::from_utf8(hex).map_err(|_| ParseError::InvalidChecksum)?;
+            *b = u8::from_str_radix(hex, 16).map_err(|_| ParseError::InvalidChecksum)?;
+        }
+
+        Ok(Self(hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::Error, test_util, SearchIndex};
+
+    #[test]
+    fn test_parse() {
+        let checksum = Checksum::of("");
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            checksum.to_string()
+        );
+        assert_eq!(
+            checksum,
+            "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855"
+                .parse()
+                .unwrap()
+        );
+
+        assert!("e3b0".parse::<Checksum>().is_err());
+        assert!("x".repeat(64).parse::<Checksum>().is_err());
+        assert!("+1".repeat(32).parse::<Checksum>().is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let fixture = test_util::anyhow_fixture();
+        let state = || SearchIndex::from_parts("anyhow", "1.0.72".parse().unwrap(), "");
+
+        assert!(state()
+            .checksum(Checksum::of(&fixture))
+            .transform_index(&fixture)
+            .is_ok());
+
+        let truncated = &fixture[..fixture.len() / 2];
+        let err = state()
+            .checksum(Checksum::of(&fixture))
+            .transform_index(truncated)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::ChecksumMismatch { expected, actual, .. }
+                if expected == Checksum::of(&fixture) && actual == Checksum::of(truncated)
+        ));
+    }
+}
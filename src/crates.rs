@@ -6,77 +6,324 @@ use tracing::debug;
 
 use crate::{
     error::{Error, Result},
-    Version,
+    DocHost, Version,
 };
 
-/// Base URL for the `docs.rs` docs service.
-const DOCSRS_URL: &str = "https://docs.rs";
-
-pub(crate) fn get_page_url(std: bool, name: &str, version: &Version) -> Cow<'static, str> {
+pub(crate) fn get_page_url(
+    host: &DocHost,
+    std: bool,
+    name: &str,
+    version: &Version,
+) -> Cow<'static, str> {
     if std {
-        Cow::Borrowed(STDLIB_INDEX_URL)
+        Cow::Owned(format!("{}/std/index.html", host.std))
+    } else {
+        Cow::Owned(host.page_url(name, &version.to_url_segment()))
+    }
+}
+
+/// Create the URL to the docs page of a crate, within a self-hosted documentation root that has
+/// the same layout as the output of `cargo doc`.
+pub(crate) fn get_root_page_url(root: &str, name: &str) -> Cow<'static, str> {
+    Cow::Owned(format!("{root}/{}/index.html", crate_name(name)))
+}
+
+/// Normalize a package name into the crate name as it is used in paths and the search index, by
+/// replacing any `-` with `_`.
+pub(crate) fn crate_name(name: &str) -> Cow<'_, str> {
+    if name.contains('-') {
+        Cow::Owned(name.replace('-', "_"))
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// Get the alternative spelling of a package name, as crates.io treats `-` and `_` as the same
+/// character but docs.rs only knows about the published spelling.
+pub(crate) fn alternative_name(name: &str) -> Option<String> {
+    if name.contains('-') {
+        Some(name.replace('-', "_"))
+    } else if name.contains('_') {
+        Some(name.replace('_', "-"))
     } else {
-        Cow::Owned(format!("{DOCSRS_URL}/{name}/{version}/{name}/"))
+        None
     }
 }
 
 pub(crate) fn find_index_url(
+    host: &DocHost,
     std: bool,
     name: &str,
     version: Version,
+    url: &str,
     body: &str,
 ) -> Result<(Version, String)> {
-    let index_path = find_url(body).ok_or(Error::IndexNotFound)?;
+    let index_path = find_url(body).ok_or_else(|| {
+        find_error_page(body, name, &version).unwrap_or_else(|| Error::IndexNotFound {
+            url: url.to_owned(),
+        })
+    })?;
     debug!("found index path: {index_path}");
 
+    let version = match version {
+        Version::Req(_) if !std => find_version(body).map_or(version, Version::SemVer),
+        _ => version,
+    };
+
+    get_index_url(host, std, name, version, &index_path)
+}
+
+/// Create the full URL to the search index from its path, relative to the docs root.
+///
+/// For the stdlib, the version is extracted from the index path, which has the format
+/// `search-index<version>.js`.
+pub(crate) fn get_index_url(
+    host: &DocHost,
+    std: bool,
+    name: &str,
+    version: Version,
+    index_path: &str,
+) -> Result<(Version, String)> {
     if std {
-        let version = index_path
-            .strip_prefix("search-index")
-            .and_then(|url| url.strip_suffix(".js"))
-            .ok_or_else(|| Error::InvalidVersionFormat(index_path.clone()))?
+        let version = resource_suffix(index_path)
+            .ok_or_else(|| Error::InvalidVersionFormat(index_path.to_owned()))?
             .parse()?;
 
-        Ok((version, format!("{STDLIB_URL}/{index_path}")))
+        Ok((version, format!("{}/{index_path}", host.std)))
     } else {
-        let url = format!("{DOCSRS_URL}/{name}/{version}/{index_path}");
+        let url = host.search_index_url(name, &version.to_url_segment(), index_path);
         Ok((version, url))
     }
 }
 
-/// Download the search index for a single crate from <https://docs.rs>, optionally a specific
-/// version of it.
+/// Check the HTTP status code of a docs page response, turning error codes into precise errors.
 ///
-/// ## Version extraction
+/// For a `404` status, the error page content is inspected to find out whether the crate or only
+/// the version is missing. If the body is not available or not a known error page, the crate
+/// can't be missing if the latest version was requested, as docs.rs always has a latest version
+/// for existing crates. Otherwise, only the version is assumed to be missing.
+pub(crate) fn check_status(
+    status: u16,
+    name: &str,
+    version: &Version,
+    url: &str,
+    body: Option<&str>,
+) -> Result<()> {
+    match status {
+        200..=299 => Ok(()),
+        404 => Err(body
+            .and_then(|body| find_error_page(body, name, version))
+            .unwrap_or_else(|| {
+                if *version == Version::Latest {
+                    Error::CrateNotFound(name.to_owned())
+                } else {
+                    Error::VersionNotFound {
+                        name: name.to_owned(),
+                        version: version.to_string(),
+                    }
+                }
+            })),
+        429 => Err(Error::RateLimited {
+            url: url.to_owned(),
+        }),
+        _ => Err(Error::UnexpectedStatus {
+            status,
+            url: url.to_owned(),
+        }),
+    }
+}
+
+/// Inspect the content of a docs.rs error page, to find out whether the crate doesn't exist at all,
+/// only the requested version is missing or the docs failed to build. Returns [`None`] if the
+/// content isn't one of these error pages.
+fn find_error_page(body: &str, name: &str, version: &Version) -> Option<Error> {
+    if let Some(version) = find_build_failure(body) {
+        Some(Error::DocsBuildFailed {
+            name: name.to_owned(),
+            version,
+        })
+    } else if body.contains("The requested crate does not exist") {
+        Some(Error::CrateNotFound(name.to_owned()))
+    } else if body.contains("The requested version does not exist") {
+        Some(Error::VersionNotFound {
+            name: name.to_owned(),
+            version: version.to_string(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Extract the concrete version from a docs.rs URL, which is either absolute or just the path.
 ///
-/// If a specific version was passed as argument no further extraction is done as it is already
-/// known, but in case it wasn't given it is extracted from the returned URL after sending a web
-/// request to the service.
+/// The URL's path is in the format `<crate>/<version>/<crate>` for docs.rs, but can differ for
+/// other hosts. Therefore, the path segment at the given position is taken and converted into a
+/// semver.
+pub(crate) fn find_version_in_url(url: &str, position: usize) -> Option<semver::Version> {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').map_or("", |(_, path)| path),
+        None => url.trim_start_matches('/'),
+    };
+
+    path.split('/').nth(position)?.parse().ok()
+}
+
+/// Try to find the concrete version of the crate in the docs.rs page content. The docs.rs header
+/// contains a link to the crate's sources with the title `Browse source of <name>-<version>`, which
+/// is used to extract the version.
+fn find_version(body: &str) -> Option<semver::Version> {
+    body.split_once("title=\"Browse source of ")
+        .and_then(|(_, start)| start.split_once('\"'))
+        .and_then(|(value, _)| split_version(value))
+}
+
+/// Detect the docs.rs page for crates, whose docs failed to build, and extract the failing version
+/// from it. The page contains a warning in the form of `docs.rs failed to build <name>-<version>`.
+fn find_build_failure(body: &str) -> Option<semver::Version> {
+    body.split_once("docs.rs failed to build ")
+        .and_then(|(_, start)| start.split_once(|c: char| c == '<' || c.is_whitespace()))
+        .and_then(|(value, _)| split_version(value))
+}
+
+/// Find the version of `rustdoc` that generated the docs page. Recent pages contain it in the
+/// `data-rustdoc-version` attribute, while older ones only encode it in the resource suffix of the
+/// search index file name.
+pub(crate) fn find_rustdoc_version(body: &str) -> Option<String> {
+    body.split_once("data-rustdoc-version=\"")
+        .and_then(|(_, start)| start.split_once('"'))
+        .map(|(version, _)| version.to_owned())
+        .or_else(|| {
+            find_url(body)
+                .as_deref()
+                .and_then(resource_suffix)
+                .and_then(rustdoc_version_from_suffix)
+        })
+}
+
+/// Extract the resource suffix from the path or URL of a search index file, which has the format
+/// `search-index<suffix>.js`.
+pub(crate) fn resource_suffix(index_url: &str) -> Option<&str> {
+    index_url
+        .rsplit('/')
+        .next()?
+        .strip_prefix("search-index")?
+        .strip_suffix(".js")
+}
+
+/// Decode the `rustdoc` version from a resource suffix, which is either in the format
+/// `-<date>-<version>-<channel>-<hash>` or only the version for the stdlib docs.
 ///
-/// The URL's path is currently in the format `<crate>/<version>/<crate>`. Therefore, the path
-/// segment at index `1` is taken and converted into a semver.
+/// The result has the same format as the output of `rustdoc --version`, like
+/// `1.73.0-nightly (ad963232d 2023-07-14)`.
+pub(crate) fn rustdoc_version_from_suffix(suffix: &str) -> Option<String> {
+    let suffix = suffix.trim_start_matches('-');
+    let parts = suffix.split('-').collect::<Vec<_>>();
+
+    match parts.as_slice() {
+        [date, version, channel, hash] if date.len() == 8 => {
+            let channel = if *channel == "stable" {
+                String::new()
+            } else {
+                format!("-{channel}")
+            };
+
+            Some(format!(
+                "{version}{channel} ({hash} {}-{}-{})",
+                date.get(..4)?,
+                date.get(4..6)?,
+                date.get(6..)?
+            ))
+        }
+        [version] => version
+            .parse::<semver::Version>()
+            .ok()
+            .map(|_| (*version).to_owned()),
+        _ => None,
+    }
+}
 
-/// URL for the index page of the stdlib std crate.
-pub const STDLIB_INDEX_URL: &str = "https://doc.rust-lang.org/nightly/std/index.html";
-/// Base URL for the stdlib docs.
-const STDLIB_URL: &str = "https://doc.rust-lang.org/nightly";
+/// Extract the version from a `<name>-<version>` combination.
+///
+/// As crate names can contain `-` as well as pre-release versions, each possible split point is
+/// tried until the remainder is a valid version.
+fn split_version(value: &str) -> Option<semver::Version> {
+    value
+        .match_indices('-')
+        .find_map(|(pos, _)| value[pos + 1..].parse().ok())
+}
 
-/// Download the latest stdbib search index.
+/// Try to find the URL for the search index from a crate's main page.
 ///
-/// ## Version extraction
+/// With the `html` feature enabled, the page is parsed as HTML first, falling back to the simple
+/// string extraction of [`find_url_plain`] if that doesn't yield a result.
+pub(crate) fn find_url(body: &str) -> Option<String> {
+    #[cfg(feature = "html")]
+    if let Some(url) = find_url_html(body) {
+        return Some(url);
+    }
+
+    find_url_plain(body)
+}
+
+/// Find the URL for the search index with the help of a real HTML parser. This is more robust
+/// than the string extraction of [`find_url_plain`], as it doesn't depend on the order of
+/// attributes or their quoting style.
 ///
-/// The version of the stdlib is always extracted as part of retrieving the index file and can not
-/// be set by the caller. In contrast to [`get_docsrs`], the version is not extracted from the URL
-/// but from the index's name. The file name has the format `search-index<version>.js`.
+/// The same attributes are searched for, with the same priority, but on any element of the page.
+/// Only the `src` attribute is restricted to `script` tags.
+#[cfg(feature = "html")]
+fn find_url_html(body: &str) -> Option<String> {
+    use lol_html::{element, RewriteStrSettings};
 
-/// Try to find the URL for the search index from a crate's main page. This is currently a `div` tag
-/// with the id `rustdoc-vars` and an attribute `data-search-js` (or `data-search-index-js` for the
-/// stdlib docs) that contains the wanted URL.
+    let (mut v1, mut v2, mut v3) = (None, None, None);
+
+    lol_html::rewrite_str(
+        body,
+        RewriteStrSettings {
+            element_content_handlers: vec![
+                element!("script[src]", |el| {
+                    if let Some(src) = el
+                        .get_attribute("src")
+                        .and_then(|src| src.strip_prefix("../").map(ToOwned::to_owned))
+                        .filter(|src| src.starts_with("search-index-"))
+                    {
+                        v1 = Some(src);
+                    }
+                    Ok(())
+                }),
+                element!("[data-search-index-js]", |el| {
+                    if let Some(url) = el
+                        .get_attribute("data-search-index-js")
+                        .and_then(|url| url.strip_prefix("../").map(ToOwned::to_owned))
+                    {
+                        v2 = Some(url);
+                    }
+                    Ok(())
+                }),
+                element!("[data-resource-suffix]", |el| {
+                    v3 = el
+                        .get_attribute("data-resource-suffix")
+                        .map(|suffix| format!("search-index{suffix}.js"));
+                    Ok(())
+                }),
+            ],
+            ..RewriteStrSettings::default()
+        },
+    )
+    .ok()?;
+
+    v3.or(v2).or(v1)
+}
+
+/// Try to find the URL for the search index from a crate's main page, with simple string
+/// extraction. This is currently a `div` tag with the id `rustdoc-vars` and an attribute
+/// `data-search-js` (or `data-search-index-js` for the stdlib docs) that contains the wanted URL.
 ///
 /// As the URL is currently unique, it's relatively safe to assume that there will be only one
 /// string in the whole page that starts with `".../search-index` and ends with `.js"`. Therefore
 /// a simple string extraction is sufficient and we don't have to pull in big dependencies to parse
 /// the HTML content first.
-fn find_url(body: &str) -> Option<String> {
+fn find_url_plain(body: &str) -> Option<String> {
     let v1 = body
         .rfind("src=\"../search-index-")
         .and_then(|pos| body[pos..].split_once("src=\"../"))
@@ -103,6 +350,7 @@ mod tests {
     use insta::glob;
 
     use super::*;
+    use crate::error::ErrorKind;
 
     #[test]
     fn test_find_index_path() {
@@ -112,4 +360,145 @@ mod tests {
             insta::assert_yaml_snapshot!(data);
         });
     }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn test_find_url_html() {
+        glob!("fixtures/*.html", |path| {
+            let input = fs::read_to_string(path).unwrap();
+            assert_eq!(find_url_plain(&input), find_url_html(&input));
+        });
+
+        let body = "<div data-resource-suffix='-20230714' id=\"rustdoc-vars\"></div>";
+        assert_eq!(
+            Some("search-index-20230714.js"),
+            find_url_html(body).as_deref()
+        );
+    }
+
+    #[test]
+    fn test_find_version() {
+        glob!("fixtures/*.html", |path| {
+            let input = fs::read_to_string(path).unwrap();
+            let data = find_version(&input).unwrap().to_string();
+            insta::assert_yaml_snapshot!(data);
+        });
+    }
+
+    #[test]
+    fn test_find_rustdoc_version() {
+        glob!("fixtures/*.html", |path| {
+            let input = fs::read_to_string(path).unwrap();
+            let data = find_rustdoc_version(&input);
+            insta::assert_yaml_snapshot!(data);
+        });
+    }
+
+    #[test]
+    fn test_resource_suffix() {
+        assert_eq!(Some("1.70.0"), resource_suffix("search-index1.70.0.js"));
+        assert_eq!(
+            Some("-20230714-1.73.0-nightly-ad963232d"),
+            resource_suffix(
+                "https://docs.rs/anyhow/1.0.72/search-index-20230714-1.73.0-nightly-ad963232d.js"
+            )
+        );
+        assert_eq!(Some(""), resource_suffix("target/doc/search-index.js"));
+        assert_eq!(
+            None,
+            resource_suffix("https://docs.rs/anyhow/1.0.72/anyhow/")
+        );
+    }
+
+    #[test]
+    fn test_rustdoc_version_from_suffix() {
+        assert_eq!(
+            Some("1.39.0-nightly (66bf391c3 2019-09-23)"),
+            rustdoc_version_from_suffix("-20190923-1.39.0-nightly-66bf391c3").as_deref()
+        );
+        assert_eq!(
+            Some("1.70.0 (90c541806 2023-05-31)"),
+            rustdoc_version_from_suffix("-20230531-1.70.0-stable-90c541806").as_deref()
+        );
+        assert_eq!(
+            Some("1.70.0"),
+            rustdoc_version_from_suffix("1.70.0").as_deref()
+        );
+        assert_eq!(None, rustdoc_version_from_suffix(""));
+    }
+
+    #[test]
+    fn test_find_version_in_url() {
+        let expect = Some(semver::Version::new(1, 0, 72));
+
+        assert_eq!(expect, find_version_in_url("/anyhow/1.0.72/anyhow/", 1));
+        assert_eq!(expect, find_version_in_url("anyhow/1.0.72/anyhow/", 1));
+        assert_eq!(
+            expect,
+            find_version_in_url("https://docs.rs/anyhow/1.0.72/anyhow/", 1)
+        );
+        assert_eq!(
+            None,
+            find_version_in_url("https://docs.rs/anyhow/latest/anyhow/", 1)
+        );
+        assert_eq!(None, find_version_in_url("https://docs.rs", 1));
+    }
+
+    #[test]
+    fn test_find_error_page() {
+        let version = Version::Latest;
+
+        let body = "<h1>The requested crate does not exist</h1><p>no such crate</p>";
+        assert!(matches!(
+            find_error_page(body, "nope", &version),
+            Some(Error::CrateNotFound(name)) if name == "nope"
+        ));
+
+        let body = "<h1>The requested version does not exist</h1><p>no such version</p>";
+        assert!(matches!(
+            find_error_page(body, "anyhow", &version),
+            Some(Error::VersionNotFound { name, .. }) if name == "anyhow"
+        ));
+
+        let body = "<div class=\"warning\">docs.rs failed to build serde-yaml-0.9.0-rc.1<br>";
+        assert!(matches!(
+            find_error_page(body, "serde-yaml", &version),
+            Some(Error::DocsBuildFailed { name, version })
+                if name == "serde-yaml" && version.to_string() == "0.9.0-rc.1"
+        ));
+
+        assert!(find_error_page("<h1>Anything else</h1>", "anyhow", &version).is_none());
+    }
+
+    #[test]
+    fn test_check_status() {
+        let version = Version::Latest;
+        let url = "https://docs.rs/anyhow/latest/anyhow/";
+
+        assert!(check_status(200, "anyhow", &version, url, None).is_ok());
+        assert!(matches!(
+            check_status(429, "anyhow", &version, url, None),
+            Err(Error::RateLimited { url: u }) if u == url
+        ));
+
+        let err = check_status(500, "anyhow", &version, url, None).unwrap_err();
+        assert_eq!(ErrorKind::Http, err.kind());
+        assert_eq!(
+            "received unexpected HTTP status code 500 from `https://docs.rs/anyhow/latest/anyhow/`",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_alternative_name() {
+        assert_eq!(
+            Some("serde_yaml"),
+            alternative_name("serde-yaml").as_deref()
+        );
+        assert_eq!(
+            Some("serde-yaml"),
+            alternative_name("serde_yaml").as_deref()
+        );
+        assert_eq!(None, alternative_name("anyhow"));
+    }
 }
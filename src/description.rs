@@ -0,0 +1,243 @@
+//! Sanitization of item descriptions, which are HTML snippets in the search index.
+
+/// Output format of [`sanitize_description`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DescriptionFormat {
+    /// Plain text, with all tags removed and entities decoded.
+    #[default]
+    PlainText,
+    /// Markdown, which keeps inline code, emphasis and links, and escapes all other text.
+    Markdown,
+}
+
+/// Convert the description of an item, like the [`RawCrateData::d`](crate::RawCrateData::d)
+/// values of an index, into plain text or Markdown that can be rendered safely.
+///
+/// The descriptions are short HTML snippets generated by `rustdoc`, with tags like `<code>` and
+/// entities like `&lt;`. Unknown tags are removed, and links are only kept for `http`, `https` or
+/// relative URLs.
+///
+/// ```rust
+/// use docsearch::{sanitize_description, DescriptionFormat};
+///
+/// let desc = "Parse a <code>Vec&lt;T&gt;</code> that is <em>not</em> empty.";
+///
+/// assert_eq!(
+///     "Parse a Vec<T> that is not empty.",
+///     sanitize_description(desc, DescriptionFormat::PlainText)
+/// );
+/// assert_eq!(
+///     "Parse a `Vec<T>` that is *not* empty.",
+///     sanitize_description(desc, DescriptionFormat::Markdown)
+/// );
+/// ```
+#[must_use]
+pub fn sanitize_description(desc: &str, format: DescriptionFormat) -> String {
+    let markdown = format == DescriptionFormat::Markdown;
+    let mut out = String::with_capacity(desc.len());
+    let mut links = Vec::new();
+    let mut in_code = false;
+    let mut rest = desc;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            if let Some(end) = rest.find('>') {
+                let tag = Tag::parse(&rest[1..end]);
+                rest = &rest[end + 1..];
+
+                if markdown {
+                    push_tag(&mut out, &tag, &mut links, &mut in_code);
+                }
+                continue;
+            }
+        }
+
+        let (c, len) = if c == '&' {
+            decode_entity(rest).unwrap_or(('&', 1))
+        } else {
+            (c, c.len_utf8())
+        };
+        rest = &rest[len..];
+
+        if markdown && !in_code && is_markdown_special(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+
+    out.trim().to_owned()
+}
+
+/// Single opening or closing HTML tag, like `<a href="...">` or `</code>`.
+struct Tag {
+    name: String,
+    closing: bool,
+    href: Option<String>,
+}
+
+impl Tag {
+    fn parse(content: &str) -> Self {
+        let (closing, inner) = match content.strip_prefix('/') {
+            Some(inner) => (true, inner),
+            None => (false, content),
+        };
+        let name = inner
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        Self {
+            name,
+            closing,
+            href: attribute(inner, "href"),
+        }
+    }
+}
+
+/// Write the Markdown equivalent of a tag, if any. Link targets are kept on a stack, until the
+/// closing tag is reached.
+fn push_tag(out: &mut String, tag: &Tag, links: &mut Vec<Option<String>>, in_code: &mut bool) {
+    match (tag.name.as_str(), tag.closing) {
+        ("code", _) => {
+            out.push('`');
+            *in_code = !tag.closing;
+        }
+        ("em" | "i", _) => out.push('*'),
+        ("strong" | "b", _) => out.push_str("**"),
+        ("del" | "s", _) => out.push_str("~~"),
+        ("a", false) => {
+            let href = tag.href.as_deref().filter(|href| is_safe_url(href));
+            if href.is_some() {
+                out.push('[');
+            }
+            links.push(href.map(|href| href.replace(' ', "%20").replace(')', "%29")));
+        }
+        ("a", true) => {
+            if let Some(Some(href)) = links.pop() {
+                out.push_str("](");
+                out.push_str(&href);
+                out.push(')');
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Extract the value of the attribute with the given name from the content of a tag.
+fn attribute(content: &str, name: &str) -> Option<String> {
+    let start = content.find(&format!("{name}="))? + name.len() + 1;
+    let value = &content[start..];
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    let value = &value[..value.find(quote)?];
+
+    Some(sanitize_description(value, DescriptionFormat::PlainText))
+}
+
+/// Only allow links with a web scheme or relative links, to avoid schemes like `javascript:`.
+fn is_safe_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("http://")
+        || lower.starts_with("https://")
+        || url.find(':').map_or(true, |colon| {
+            url.find(['/', '?', '#']).map_or(false, |pos| pos < colon)
+        })
+}
+
+/// Decode a single HTML entity at the start of the value, returning the character and the byte
+/// length of the entity.
+fn decode_entity(value: &str) -> Option<(char, usize)> {
+    let end = value.get(..12).unwrap_or(value).find(';')?;
+    let name = &value[1..end];
+
+    let c = match name {
+        "lt" => '<',
+        "gt" => '>',
+        "amp" => '&',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code).unwrap_or('\u{FFFD}')
+        }
+    };
+
+    Some((c, end + 1))
+}
+
+/// Characters that have a special meaning in Markdown and must be escaped in plain text.
+fn is_markdown_special(c: char) -> bool {
+    matches!(
+        c,
+        '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~' | '|'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(desc: &str) -> String {
+        sanitize_description(desc, DescriptionFormat::PlainText)
+    }
+
+    fn markdown(desc: &str) -> String {
+        sanitize_description(desc, DescriptionFormat::Markdown)
+    }
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(
+            "The <'a, T> in std::slice::Iter<'a, T>.",
+            plain("The <code>&lt;&#39;a, T&gt;</code> in <code>std::slice::Iter&lt;&#39;a, T&gt;</code>.")
+        );
+        assert_eq!(
+            "Parse a pattern that does not involve | at the top level.",
+            plain(
+                "Parse a pattern that does <em>not</em> involve <code>|</code> at the top level."
+            )
+        );
+        assert_eq!(
+            "See the docs.",
+            plain("See <a href=\"https://docs.rs\">the docs</a>.")
+        );
+        assert_eq!("a < b & c", plain("a < b &amp; c"));
+        assert_eq!("&unknown; &#xZZ;", plain("&unknown; &#xZZ;"));
+        assert_eq!("x", plain("<script>x</script>"));
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            "The `<'a, T>` in `std::slice::Iter<'a, T>`.",
+            markdown("The <code>&lt;&#39;a, T&gt;</code> in <code>std::slice::Iter&lt;&#39;a, T&gt;</code>.")
+        );
+        assert_eq!(
+            "Does *not* involve **any** `|`.",
+            markdown("Does <em>not</em> involve <strong>any</strong> <code>|</code>.")
+        );
+        assert_eq!(
+            "See [the docs](https://docs.rs/a?b=1&c=2).",
+            markdown("See <a href=\"https://docs.rs/a?b=1&amp;c=2\">the docs</a>.")
+        );
+        assert_eq!(
+            "See [the docs](../index.html).",
+            markdown("See <a href='../index.html'>the docs</a>.")
+        );
+        assert_eq!(
+            "Click me.",
+            markdown("<a href=\"javascript:alert(1)\">Click me</a>.")
+        );
+        assert_eq!(
+            "Not \\*bold\\* or \\[a link\\](x) or \\<b\\>.",
+            markdown("Not *bold* or [a link](x) or &lt;b&gt;.")
+        );
+    }
+}
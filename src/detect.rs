@@ -0,0 +1,241 @@
+//! Detection of the documentation root from any page generated by `rustdoc`, and discovery of the
+//! crates it contains, to run searches against hosts other than docs.rs, like GitHub Pages.
+
+use crate::{
+    crates,
+    error::{Error, Result},
+    Request,
+};
+
+/// Information about a `rustdoc` documentation root, as detected by [`detect_docs`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocsRoot {
+    /// Absolute URL of the documentation root, without a trailing slash. The docs of each crate
+    /// are located in sub-directories of it, like `{root}/anyhow/index.html`.
+    pub root: String,
+    /// Name of the crate that the inspected page belongs to.
+    pub crate_name: Option<String>,
+    /// Resource suffix of the static files, which is part of the search index file name in the
+    /// form of `search-index{suffix}.js`.
+    pub resource_suffix: Option<String>,
+}
+
+/// Detect the documentation root and resource suffix from any page generated by `rustdoc`, given
+/// the page's URL and its content.
+///
+/// Pages contain the path to the root relative to themselves, which is resolved against the page
+/// URL to get the absolute root.
+///
+/// ```rust
+/// let body = r#"<meta name="generator" content="rustdoc">
+///     <div id="rustdoc-vars" data-root-path="../" data-current-crate="anyhow"
+///         data-resource-suffix="-1.70.0"></div>"#;
+/// let docs = docsearch::detect_docs("https://me.github.io/anyhow/anyhow/struct.Error.html", body)
+///     .unwrap();
+///
+/// assert_eq!("https://me.github.io/anyhow", docs.root);
+/// assert_eq!(Some("anyhow"), docs.crate_name.as_deref());
+/// assert_eq!(Some("-1.70.0"), docs.resource_suffix.as_deref());
+/// ```
+pub fn detect_docs(page_url: &str, body: &str) -> Result<DocsRoot> {
+    if !body.contains("content=\"rustdoc\"") {
+        return Err(Error::NotRustdoc {
+            url: page_url.to_owned(),
+        });
+    }
+
+    let root_path = find_attribute(body, "data-root-path")
+        .or_else(|| find_variable(body, "rootPath"))
+        .unwrap_or("./");
+    let crate_name = find_attribute(body, "data-current-crate")
+        .or_else(|| find_variable(body, "currentCrate"))
+        .map(ToOwned::to_owned);
+    let resource_suffix = find_attribute(body, "data-resource-suffix")
+        .map(ToOwned::to_owned)
+        .or_else(|| {
+            crates::find_url(body)
+                .as_deref()
+                .and_then(crates::resource_suffix)
+                .map(ToOwned::to_owned)
+        });
+
+    Ok(DocsRoot {
+        root: resolve_root(page_url, root_path),
+        crate_name,
+        resource_suffix,
+    })
+}
+
+/// List all crates that are documented in a documentation root, like a `cargo doc` output of a
+/// workspace. The list is retrieved from the `crates.js` file, that `rustdoc` generates for the
+/// crate selector in the sidebar.
+///
+/// This allows to discover every crate before fetching the shared search index, for example with
+/// [`start_search_at`](crate::start_search_at).
+///
+/// ```rust
+/// let state = docsearch::list_crates("https://docs.example.com/");
+/// assert_eq!("https://docs.example.com/crates.js", state.url());
+///
+/// let crates = state.parse(r#"window.ALL_CRATES = ["anyhow","serde"];"#).unwrap();
+/// assert_eq!(vec!["anyhow", "serde"], crates);
+/// ```
+#[must_use]
+pub fn list_crates(base_url: &str) -> CrateList {
+    CrateList {
+        url: format!("{}/crates.js", base_url.trim_end_matches('/')),
+    }
+}
+
+/// Single state to retrieve the list of crates in a documentation root. Use the [`Self::url`]
+/// function to get the URL of the `crates.js` file to download. Its content must then be passed to
+/// [`Self::parse`].
+pub struct CrateList {
+    url: String,
+}
+
+impl CrateList {
+    /// URL to the crate list that should be retrieved and passed to [`Self::parse`].
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Full description of the request to retrieve the content for [`Self::parse`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        Request::get(&self.url)
+    }
+
+    /// Parse the crate list downloaded from [`Self::url`], which contains a JavaScript array of
+    /// crate names in the form of `window.ALL_CRATES = ["anyhow","serde"];`.
+    pub fn parse(self, body: &str) -> Result<Vec<String>> {
+        parse_crates(body).map_err(|e| e.with_url(&self.url))
+    }
+}
+
+/// Extract the JSON array of crate names from the `crates.js` content.
+fn parse_crates(body: &str) -> Result<Vec<String>> {
+    let list = body
+        .split_once("ALL_CRATES")
+        .and_then(|(_, rest)| rest.split_once('['))
+        .and_then(|(_, rest)| rest.split_once(']'))
+        .map(|(list, _)| format!("[{list}]"))
+        .ok_or(Error::CrateListNotFound)?;
+
+    serde_json::from_str(&list).map_err(Into::into)
+}
+
+/// Extract the value of an HTML attribute in the form of `name="value"`.
+fn find_attribute<'a>(body: &'a str, name: &str) -> Option<&'a str> {
+    body.split_once(&format!("{name}=\""))
+        .and_then(|(_, start)| start.split_once('"'))
+        .map(|(value, _)| value)
+}
+
+/// Extract the value of a JavaScript variable in the form of `window.name = "value";`, which is
+/// how older `rustdoc` versions stored the page information.
+fn find_variable<'a>(body: &'a str, name: &str) -> Option<&'a str> {
+    body.split_once(&format!("{name} = \""))
+        .and_then(|(_, start)| start.split_once('"'))
+        .map(|(value, _)| value)
+}
+
+/// Resolve the relative root path (like `../../`) against the URL of the page it was found on.
+/// The root can't go above the host part of the URL.
+fn resolve_root(page_url: &str, root_path: &str) -> String {
+    let (origin, path) = match page_url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+            (format!("{scheme}://{host}"), path)
+        }
+        None => (String::new(), page_url),
+    };
+
+    // Drop the file name of the page, or an empty segment for directory URLs.
+    let mut segments = path.split('/').collect::<Vec<_>>();
+    segments.pop();
+
+    for segment in root_path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    segments.retain(|s| !s.is_empty());
+    let path = segments.join("/");
+
+    match (origin.is_empty(), path.is_empty()) {
+        (true, _) => path,
+        (false, true) => origin,
+        (false, false) => format!("{origin}/{path}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_docs() {
+        let body = fs::read_to_string("src/fixtures/anyhow-1.0.72.html").unwrap();
+        let docs = detect_docs("https://docs.rs/anyhow/1.0.72/anyhow/", &body).unwrap();
+        assert_eq!(
+            DocsRoot {
+                root: "https://docs.rs/anyhow/1.0.72".to_owned(),
+                crate_name: Some("anyhow".to_owned()),
+                resource_suffix: Some("-20230714-1.73.0-nightly-ad963232d".to_owned()),
+            },
+            docs
+        );
+
+        let body = fs::read_to_string("src/fixtures/anyhow-1.0.0.html").unwrap();
+        let docs = detect_docs("https://docs.rs/anyhow/1.0.0/anyhow/index.html", &body).unwrap();
+        assert_eq!(
+            DocsRoot {
+                root: "https://docs.rs/anyhow/1.0.0".to_owned(),
+                crate_name: Some("anyhow".to_owned()),
+                resource_suffix: Some("-20190923-1.39.0-nightly-66bf391c3".to_owned()),
+            },
+            docs
+        );
+
+        assert!(matches!(
+            detect_docs("https://example.com", "<html></html>"),
+            Err(Error::NotRustdoc { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_crates() {
+        let body = "window.ALL_CRATES = [\"alloc\",\"core\",\"std\"];\n//{\"start\":21}\n";
+        assert_eq!(vec!["alloc", "core", "std"], parse_crates(body).unwrap());
+
+        let body = "window.ALL_CRATES = [];";
+        assert!(parse_crates(body).unwrap().is_empty());
+
+        assert!(matches!(
+            parse_crates("var x = 5;"),
+            Err(Error::CrateListNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_root() {
+        assert_eq!(
+            "https://me.github.io/docs",
+            resolve_root("https://me.github.io/docs/anyhow/index.html", "../")
+        );
+        assert_eq!(
+            "https://me.github.io",
+            resolve_root("https://me.github.io/anyhow/", "../../../")
+        );
+        assert_eq!("target/doc", resolve_root("target/doc/anyhow/", ".././"));
+    }
+}
@@ -0,0 +1,180 @@
+//! Comparison of two [`Index`]es, like different versions of the same crate.
+
+use std::collections::HashMap;
+
+use crate::Index;
+
+/// Differences between two [`Index`]es, as returned by [`Index::diff`]. All lists are sorted by
+/// the item paths.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexDiff {
+    /// Paths of items that only exist in the newer index.
+    pub added: Vec<String>,
+    /// Paths of items that only exist in the older index.
+    pub removed: Vec<String>,
+    /// Items that exist in both indexes, but either their path or their URL changed.
+    pub moved: Vec<MovedItem>,
+}
+
+impl IndexDiff {
+    /// Whether both indexes contain the exact same items.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+/// Single item that changed its location between two indexes.
+///
+/// An item is considered the same, if either its path stayed the same (but its URL changed), or
+/// its URL stayed the same (but its path changed). The latter happens when an item is moved to
+/// another module, but re-exported at its old location in the docs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MovedItem {
+    /// Path of the item in the older index.
+    pub old_path: String,
+    /// Path of the item in the newer index.
+    pub new_path: String,
+    /// URL path of the item in the older index.
+    pub old_url: String,
+    /// URL path of the item in the newer index.
+    pub new_url: String,
+}
+
+pub(crate) fn diff(old: &Index, new: &Index) -> IndexDiff {
+    let mut diff = IndexDiff::default();
+
+    // Candidates for path changes, keyed by their URL. Several new paths can share the same URL,
+    // like re-exports of an item, so all of them are kept in order.
+    let mut added = HashMap::<_, Vec<_>>::new();
+    for (path, url) in &new.mapping {
+        if !old.mapping.contains_key(path) {
+            added.entry(url.as_str()).or_default().push(path.as_str());
+        }
+    }
+
+    for (path, url) in &old.mapping {
+        match new.mapping.get(path) {
+            Some(new_url) if new_url == url => {}
+            Some(new_url) => diff.moved.push(MovedItem {
+                old_path: path.clone(),
+                new_path: path.clone(),
+                old_url: url.clone(),
+                new_url: new_url.clone(),
+            }),
+            None => match added
+                .get_mut(url.as_str())
+                .filter(|paths| !paths.is_empty())
+            {
+                Some(paths) => diff.moved.push(MovedItem {
+                    old_path: path.clone(),
+                    new_path: paths.remove(0).to_owned(),
+                    old_url: url.clone(),
+                    new_url: url.clone(),
+                }),
+                None => diff.removed.push(path.clone()),
+            },
+        }
+    }
+
+    diff.added = added
+        .into_values()
+        .flatten()
+        .map(ToOwned::to_owned)
+        .collect();
+    diff.added.sort();
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::SearchIndex;
+
+    fn load(version: &str) -> Index {
+        let content =
+            fs::read_to_string(format!("src/index/fixtures/anyhow-{version}.js")).unwrap();
+        SearchIndex::from_parts("anyhow", version.parse().unwrap(), "")
+            .transform_index(&content)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = load("1.0.70");
+        let new = load("1.0.72");
+
+        assert!(diff(&old, &old).is_empty());
+
+        let result = diff(&old, &new);
+        assert_eq!(
+            IndexDiff {
+                added: vec![],
+                removed: vec![],
+                moved: vec![MovedItem {
+                    old_path: "anyhow::format_err".to_owned(),
+                    new_path: "anyhow::format_err".to_owned(),
+                    old_url: "anyhow/macro.format_err.html".to_owned(),
+                    new_url: "anyhow/import.format_err.html".to_owned(),
+                }],
+            },
+            result
+        );
+
+        let mut new = load("1.0.72");
+        let url = new.mapping.remove("anyhow::Chain").unwrap();
+        new.mapping
+            .insert("anyhow::error::Chain".to_owned(), url.clone());
+        new.mapping.remove("anyhow::Ok");
+        new.mapping.insert(
+            "anyhow::Context2".to_owned(),
+            "anyhow/trait.Context2.html".to_owned(),
+        );
+
+        let result = diff(&load("1.0.72"), &new);
+        assert_eq!(vec!["anyhow::Context2"], result.added);
+        assert_eq!(vec!["anyhow::Ok"], result.removed);
+        assert_eq!(
+            vec![MovedItem {
+                old_path: "anyhow::Chain".to_owned(),
+                new_path: "anyhow::error::Chain".to_owned(),
+                old_url: url.clone(),
+                new_url: url,
+            }],
+            result.moved
+        );
+    }
+
+    #[test]
+    fn test_diff_shared_url() {
+        let old = load("1.0.72");
+        let mut new = load("1.0.72");
+        let url = new.mapping.remove("anyhow::Chain").unwrap();
+        for path in ["anyhow::error::Chain", "anyhow::iter::Chain"] {
+            new.mapping.insert(path.to_owned(), url.clone());
+        }
+        for path in ["anyhow::a::New", "anyhow::b::New"] {
+            new.mapping
+                .insert(path.to_owned(), "anyhow/struct.New.html".to_owned());
+        }
+
+        let result = diff(&old, &new);
+        assert_eq!(
+            vec!["anyhow::a::New", "anyhow::b::New", "anyhow::iter::Chain"],
+            result.added
+        );
+        assert!(result.removed.is_empty());
+        assert_eq!(
+            vec![MovedItem {
+                old_path: "anyhow::Chain".to_owned(),
+                new_path: "anyhow::error::Chain".to_owned(),
+                old_url: url.clone(),
+                new_url: url,
+            }],
+            result.moved
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Parsing of documentation URLs into search queries.
+
+use std::str::FromStr;
+
+use crate::{crates, error::ParseError, SimplePath, Version};
+
+/// Search query extracted from the URL of a documentation page, like
+/// `https://docs.rs/anyhow/1.0/anyhow/type.Result.html`. It contains everything needed to run a
+/// fresh search and generate a canonical link for the item.
+///
+/// Supported are docs.rs URLs (including just their path) and URLs of the stdlib docs at
+/// `doc.rust-lang.org`.
+///
+/// ```rust
+/// use docsearch::{DocsUrl, Version};
+///
+/// let url = "https://docs.rs/anyhow/1.0/anyhow/struct.Error.html#method.context"
+///     .parse::<DocsUrl>()
+///     .unwrap();
+///
+/// assert_eq!("anyhow", url.name);
+/// assert_eq!("1.0".parse::<Version>().unwrap(), url.version);
+/// assert_eq!("anyhow::Error::context", url.path.as_ref());
+///
+/// let state = docsearch::start_search(&url.name, url.version);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocsUrl {
+    /// Name of the crate as published, which can differ from the crate name in the path.
+    pub name: String,
+    /// Version of the crate, as given in the URL.
+    pub version: Version,
+    /// Simple path of the item, that the URL points to.
+    pub path: SimplePath,
+}
+
+impl FromStr for DocsUrl {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, path) = match s.split_once("://") {
+            Some((_, rest)) => rest.split_once('/').unwrap_or((rest, "")),
+            None => ("docs.rs", s.trim_start_matches('/')),
+        };
+        let (path, fragment) = match path.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (path, None),
+        };
+        let path = path.split_once('?').map_or(path, |(path, _)| path);
+        let mut segments = path.split('/');
+
+        let (name, version) = if host == "doc.rust-lang.org" {
+            let version = match segments.next() {
+                Some("stable" | "beta" | "nightly") => Version::Latest,
+                Some(version) => version.parse().map_err(|_| ParseError::InvalidUrl)?,
+                None => return Err(ParseError::InvalidUrl),
+            };
+            (None, version)
+        } else {
+            let mut name = segments.next().filter(|name| !name.is_empty());
+            if name == Some("crate") {
+                name = segments.next();
+            }
+            let version = segments
+                .next()
+                .filter(|version| !version.is_empty())
+                .map_or(Ok(Version::Latest), str::parse)
+                .map_err(|_| ParseError::InvalidUrl)?;
+            (name, version)
+        };
+
+        // Crate overview pages like `/crate/<name>/<version>` don't point to any item.
+        let segments = if path.starts_with("crate/") {
+            Vec::new()
+        } else {
+            segments.collect::<Vec<_>>()
+        };
+        let (crate_name, segments) = match segments.split_first() {
+            Some((crate_name, segments)) if !crate_name.is_empty() => (Some(*crate_name), segments),
+            _ => (None, &[][..]),
+        };
+
+        let crate_name = crate_name.or(name).ok_or(ParseError::InvalidUrl)?;
+        let name = name.unwrap_or(crate_name);
+
+        let mut parts = vec![crates::crate_name(crate_name).into_owned()];
+
+        if let Some((page, modules)) = segments.split_last() {
+            parts.extend(modules.iter().map(|&module| module.to_owned()));
+
+            // Item pages are named `<kind>.<name>.html`, while modules only have an `index.html`.
+            if let Some(item) = page
+                .strip_suffix(".html")
+                .and_then(|page| page.split_once('.'))
+                .map(|(_, item)| item)
+            {
+                parts.push(item.to_owned());
+
+                // Fragments of members are in the form of `<kind>.<name>`, other anchors like
+                // `impl-Clone-for-Error` are ignored.
+                if let Some((_, member)) = fragment.and_then(|f| f.split_once('.')) {
+                    parts.push(member.to_owned());
+                }
+            }
+        }
+
+        Ok(Self {
+            name: name.to_owned(),
+            version,
+            path: SimplePath::from_index_path(&parts.join("::"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> (String, String, String) {
+        let url = url.parse::<DocsUrl>().unwrap();
+        (url.name, url.version.to_string(), url.path.into_inner())
+    }
+
+    #[test]
+    fn test_parse() {
+        let expect = |name: &str, version: &str, path: &str| {
+            (name.to_owned(), version.to_owned(), path.to_owned())
+        };
+
+        assert_eq!(
+            expect("anyhow", "^1.0", "anyhow::Result"),
+            parse("https://docs.rs/anyhow/1.0/anyhow/type.Result.html")
+        );
+        assert_eq!(
+            expect("serde-yaml", "0.9.0", "serde_yaml::value::Value::as_str"),
+            parse("/serde-yaml/0.9.0/serde_yaml/value/enum.Value.html#method.as_str")
+        );
+        assert_eq!(
+            expect("tokio", "latest", "tokio::sync"),
+            parse("https://docs.rs/tokio/latest/tokio/sync/index.html")
+        );
+        assert_eq!(
+            expect("tokio", "latest", "tokio"),
+            parse("https://docs.rs/tokio")
+        );
+        assert_eq!(
+            expect("serde-yaml", "0.9.0", "serde_yaml"),
+            parse("https://docs.rs/crate/serde-yaml/0.9.0/source/")
+        );
+        assert_eq!(
+            expect("std", "latest", "std::vec::Vec"),
+            parse("https://doc.rust-lang.org/stable/std/vec/struct.Vec.html#impl-Clone-for-Vec")
+        );
+        assert_eq!(
+            expect("core", "1.70.0", "core::option::Option::map"),
+            parse("https://doc.rust-lang.org/1.70.0/core/option/enum.Option.html#method.map")
+        );
+
+        assert!("https://docs.rs/anyhow/not-a-version/"
+            .parse::<DocsUrl>()
+            .is_err());
+        assert!("https://docs.rs/".parse::<DocsUrl>().is_err());
+    }
+}
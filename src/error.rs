@@ -8,23 +8,164 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum Error {
-    #[error("failed deserializing JSON")]
-    Json(#[from] serde_json::Error),
+    #[error("an I/O operation failed")]
+    Io(#[from] std::io::Error),
+    #[error("running `cargo metadata` failed: {0}")]
+    CargoMetadata(String),
+    #[error("the lockfile contains a package without name or version")]
+    InvalidLockfile,
+    #[error(
+        "failed deserializing JSON{}",
+        .url.as_ref().map_or_else(String::new, |url| format!(" from `{url}`"))
+    )]
+    Json {
+        url: Option<String>,
+        #[source]
+        source: serde_json::Error,
+    },
     #[error("invalid semantic version string")]
     SemVer(#[from] semver::Error),
-    #[error("the version part was missing in `{0}`")]
-    MissingVersion(String),
-    #[error("couldn't find the index path in a response body")]
-    IndexNotFound,
-    #[error("index didn't contain information for the requested crate")]
-    CrateDataMissing,
+    #[error("the version part of the crate `{name}` was missing in `{url}`")]
+    MissingVersion { name: String, url: String },
+    #[error("the crate `{0}` doesn't exist")]
+    CrateNotFound(String),
+    #[error("no published version of the crate `{name}` matching `{version}` was found")]
+    VersionNotFound { name: String, version: String },
+    #[error("the requested version `{version}` of the crate `{name}` was yanked")]
+    VersionYanked {
+        name: String,
+        version: semver::Version,
+    },
+    #[error("too many requests were sent to `{url}` and the service is rate limiting")]
+    RateLimited { url: String },
+    #[error("received unexpected HTTP status code {status} from `{url}`")]
+    UnexpectedStatus { status: u16, url: String },
+    #[error("failed fetching `{url}`")]
+    Fetch {
+        url: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("the docs of the crate `{name}` for version `{version}` failed to build")]
+    DocsBuildFailed {
+        name: String,
+        version: semver::Version,
+    },
+    #[error("no version of the crate `{0}` was built successfully")]
+    NoSuccessfulBuild(String),
+    #[error("the page `{url}` wasn't generated by rustdoc")]
+    NotRustdoc { url: String },
+    #[error("couldn't find the list of crates in the `crates.js` file")]
+    CrateListNotFound,
+    #[error("couldn't find the search index in `{url}`")]
+    IndexNotFound { url: String },
+    #[error("the index at `{url}` didn't contain information for the crate `{name}`")]
+    CrateDataMissing { name: String, url: String },
     #[error("version was not in the expected `search-index<X.X.X>.js` format but `{0}`")]
     InvalidVersionFormat(String),
+    #[error("the item `{item}` of the crate `{name}` refers to the missing parent {parent}")]
+    InvalidParentIndex {
+        name: String,
+        item: String,
+        parent: usize,
+    },
+    #[error("the item data of the crate `{name}` has lists of different lengths")]
+    MismatchedItemData { name: String },
+    #[error("the {limit} of {value} exceeds the limit of {max}")]
+    LimitExceeded {
+        limit: crate::Limit,
+        value: usize,
+        max: usize,
+    },
+    #[cfg(feature = "checksum")]
+    #[error("the index at `{url}` has the checksum {actual}, but {expected} was expected")]
+    ChecksumMismatch {
+        expected: crate::Checksum,
+        actual: crate::Checksum,
+        url: String,
+    },
     #[error("the used index version is currently not supported")]
     UnsupportedIndexVersion,
     #[cfg(feature = "index-v1")]
     #[error("failed to parse the V1 index")]
     InvalidV1Index(#[from] IndexV1Error),
+    #[cfg(feature = "url")]
+    #[error("the link isn't a valid absolute URL")]
+    InvalidLinkUrl(#[from] url::ParseError),
+}
+
+impl Error {
+    /// Category of this error, which allows to handle groups of errors the same way without
+    /// matching each variant, like retrying later for [`ErrorKind::RateLimited`].
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Io(_) | Self::CargoMetadata(_) => ErrorKind::Io,
+            Self::InvalidLockfile
+            | Self::Json { .. }
+            | Self::SemVer(_)
+            | Self::MissingVersion { .. }
+            | Self::CrateListNotFound
+            | Self::IndexNotFound { .. }
+            | Self::InvalidVersionFormat(_)
+            | Self::InvalidParentIndex { .. }
+            | Self::MismatchedItemData { .. } => ErrorKind::Parse,
+            #[cfg(feature = "index-v1")]
+            Self::InvalidV1Index(_) => ErrorKind::Parse,
+            #[cfg(feature = "url")]
+            Self::InvalidLinkUrl(_) => ErrorKind::Parse,
+            #[cfg(feature = "checksum")]
+            Self::ChecksumMismatch { .. } => ErrorKind::Parse,
+            Self::CrateNotFound(_)
+            | Self::VersionNotFound { .. }
+            | Self::VersionYanked { .. }
+            | Self::CrateDataMissing { .. } => ErrorKind::NotFound,
+            Self::DocsBuildFailed { .. } | Self::NoSuccessfulBuild(_) => ErrorKind::BuildFailed,
+            Self::RateLimited { .. } => ErrorKind::RateLimited,
+            Self::UnexpectedStatus { .. } | Self::Fetch { .. } => ErrorKind::Http,
+            Self::NotRustdoc { .. } | Self::UnsupportedIndexVersion => ErrorKind::Unsupported,
+            Self::LimitExceeded { .. } => ErrorKind::LimitExceeded,
+        }
+    }
+
+    /// Attach the URL of the parsed content to JSON errors, that don't have one yet.
+    pub(crate) fn with_url(self, url: &str) -> Self {
+        match self {
+            Self::Json { url: None, source } => Self::Json {
+                url: Some(url.to_owned()),
+                source,
+            },
+            e => e,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(source: serde_json::Error) -> Self {
+        Self::Json { url: None, source }
+    }
+}
+
+/// Category of an [`Error`], as returned by [`Error::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The crate, version or item doesn't exist.
+    NotFound,
+    /// The docs of the crate failed to build, so there is no index to search.
+    BuildFailed,
+    /// The docs or index are in a format that isn't supported.
+    Unsupported,
+    /// Some content couldn't be parsed or didn't contain the expected data.
+    Parse,
+    /// The service is rate limiting and the request should be retried later.
+    RateLimited,
+    /// The service responded with an unexpected HTTP status.
+    Http,
+    /// Reading local files or running an external command failed.
+    Io,
+    /// The index exceeded one of the configured [`ParseLimits`](crate::ParseLimits).
+    LimitExceeded,
 }
 
 /// Errors that can happen when parsing the old V1 index.
@@ -44,6 +185,7 @@ pub enum IndexV1Error {
 
 /// Errors that can happen when parsing a [`SimplePath`](crate::SimplePath).
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum ParseError {
     /// The value is too short to represent a simple path.
     #[error("The value is too short")]
@@ -51,4 +193,17 @@ pub enum ParseError {
     /// One (and possibly more) of the segments aren't valid identifiers.
     #[error("One or more segments aren't valid identifiers")]
     InvalidIdentifier,
+    /// The value isn't a URL to a documentation page.
+    #[error("The value isn't a valid documentation URL")]
+    InvalidUrl,
+    /// The value isn't a path in the fully-qualified syntax like `<Type as Trait>::item`.
+    #[error("The value isn't a valid fully-qualified path")]
+    InvalidQualifiedPath,
+    /// The disambiguator prefix like `struct@` is unknown or conflicts with a trailing `!`.
+    #[error("The disambiguator is unknown or conflicting")]
+    InvalidDisambiguator,
+    /// The value isn't a SHA-256 checksum in hex format.
+    #[cfg(feature = "checksum")]
+    #[error("The value isn't a valid checksum")]
+    InvalidChecksum,
 }
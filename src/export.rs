@@ -0,0 +1,336 @@
+//! Export of an [`Index`] into formats that can be consumed by other tools.
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Write as _},
+    io::Write,
+};
+
+use serde::Serialize;
+
+use crate::{
+    error::Result, link::escape_html, tree, Index, ItemType, LinkOptions, LinkStyle, ModuleTree,
+};
+
+/// Identifier of the first version of the JSON export schema.
+const JSON_SCHEMA_V1: &str = "docsearch/index/v1";
+
+/// Root object of the V1 JSON export.
+#[derive(Serialize)]
+struct JsonIndexV1<'a> {
+    schema: &'static str,
+    name: &'a str,
+    version: String,
+    requested: String,
+    std: bool,
+    crate_url: String,
+    rustdoc_version: Option<&'a str>,
+    items: Vec<JsonItemV1<'a>>,
+}
+
+/// Single item of the V1 JSON export.
+#[derive(Serialize)]
+struct JsonItemV1<'a> {
+    path: &'a str,
+    kind: Option<&'static str>,
+    url: String,
+}
+
+pub(crate) fn to_json_schema_v1(index: &Index) -> Result<String> {
+    let options = LinkOptions::default();
+    let data = JsonIndexV1 {
+        schema: JSON_SCHEMA_V1,
+        name: &index.name,
+        version: index.version.to_string(),
+        requested: index.requested.to_string(),
+        std: index.std,
+        crate_url: index.crate_url_with(&options),
+        rustdoc_version: index.rustdoc_version.as_deref(),
+        items: index
+            .mapping
+            .iter()
+            .map(|(path, link)| JsonItemV1 {
+                path,
+                kind: ItemType::from_link(link).map(ItemType::as_str),
+                url: index.format_link(link, &options),
+            })
+            .collect(),
+    };
+
+    serde_json::to_string(&data).map_err(Into::into)
+}
+
+pub(crate) fn write_delimited(
+    index: &Index,
+    mut writer: impl Write,
+    delimiter: char,
+) -> Result<()> {
+    let options = LinkOptions::default();
+
+    writeln!(writer, "path{delimiter}kind{delimiter}url")?;
+
+    for (path, link) in &index.mapping {
+        writeln!(
+            writer,
+            "{}{delimiter}{}{delimiter}{}",
+            escape(path, delimiter),
+            ItemType::from_link(link).map_or("", ItemType::as_str),
+            escape(&index.format_link(link, &options), delimiter),
+        )?;
+    }
+
+    writer.flush().map_err(Into::into)
+}
+
+/// Quote the value if it contains the delimiter or any other character that would break up the
+/// row, doubling all contained quotes.
+fn escape(value: &str, delimiter: char) -> Cow<'_, str> {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// Output format of the link directory, as generated by [`Index::link_directory`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DirectoryFormat {
+    /// Markdown document with headings and bullet lists.
+    Markdown,
+    /// HTML fragment with headings and unordered lists.
+    Html,
+}
+
+/// Item types that can appear directly in a module, with their group title in the directory. The
+/// order follows the one of the module pages generated by rustdoc.
+const DIRECTORY_GROUPS: &[(ItemType, &str)] = &[
+    (ItemType::ExternCrate, "Crates"),
+    (ItemType::Import, "Re-exports"),
+    (ItemType::Primitive, "Primitive Types"),
+    (ItemType::Macro, "Macros"),
+    (ItemType::Struct, "Structs"),
+    (ItemType::Enum, "Enums"),
+    (ItemType::Constant, "Constants"),
+    (ItemType::Static, "Statics"),
+    (ItemType::Trait, "Traits"),
+    (ItemType::Function, "Functions"),
+    (ItemType::Typedef, "Type Aliases"),
+    (ItemType::Union, "Unions"),
+    (ItemType::ForeignType, "Foreign Types"),
+    (ItemType::OpaqueTy, "Opaque Types"),
+    (ItemType::Keyword, "Keywords"),
+    (ItemType::ProcAttribute, "Attribute Macros"),
+    (ItemType::ProcDerive, "Derive Macros"),
+    (ItemType::TraitAlias, "Trait Aliases"),
+];
+
+pub(crate) fn link_directory(index: &Index, format: DirectoryFormat) -> String {
+    let mut out = String::new();
+    let tree = tree::build(index);
+
+    // Writing into a string never fails.
+    write_directory(&mut out, index, &tree, format).ok();
+
+    out
+}
+
+fn write_directory(
+    out: &mut String,
+    index: &Index,
+    tree: &ModuleTree,
+    format: DirectoryFormat,
+) -> fmt::Result {
+    let title = format!("{} {}", index.name, index.version);
+
+    match format {
+        DirectoryFormat::Markdown => writeln!(out, "# {title}")?,
+        DirectoryFormat::Html => writeln!(out, "<h1>{}</h1>", escape_html(&title))?,
+    }
+
+    write_module(out, index, tree, format)
+}
+
+/// Write a section for the module with all its items grouped by type, followed by the sections
+/// of all sub-modules.
+fn write_module(
+    out: &mut String,
+    index: &Index,
+    module: &ModuleTree,
+    format: DirectoryFormat,
+) -> fmt::Result {
+    let options = LinkOptions::default();
+    let module_url = |module: &ModuleTree| match module.path.split_once("::") {
+        Some(_) => index.format_link(
+            &format!("{}/index.html", module.path.replace("::", "/")),
+            &options,
+        ),
+        None => index.crate_url_with(&options),
+    };
+
+    write_heading(out, format, 2, &module.path, &module_url(module))?;
+
+    if !module.modules.is_empty() {
+        write_heading(out, format, 3, "Modules", "")?;
+        write_list(
+            out,
+            format,
+            module
+                .modules
+                .iter()
+                .map(|m| (m.name.as_str(), module_url(m))),
+        )?;
+    }
+
+    for (kind, title) in DIRECTORY_GROUPS {
+        let mut items = module
+            .items
+            .iter()
+            .filter(|item| item.kind == *kind)
+            .filter_map(|item| {
+                let link = index.mapping.get(&item.path)?;
+                Some((item.name.as_str(), index.format_link(link, &options)))
+            })
+            .peekable();
+
+        if items.peek().is_none() {
+            continue;
+        }
+
+        write_heading(out, format, 3, title, "")?;
+        write_list(out, format, items)?;
+    }
+
+    for sub in &module.modules {
+        write_module(out, index, sub, format)?;
+    }
+
+    Ok(())
+}
+
+/// Write a heading of the given level, that is formatted as code and linked, if a URL is given.
+fn write_heading(
+    out: &mut String,
+    format: DirectoryFormat,
+    level: usize,
+    text: &str,
+    url: &str,
+) -> fmt::Result {
+    match (format, url.is_empty()) {
+        (DirectoryFormat::Markdown, true) => {
+            writeln!(out, "\n{} {text}", "#".repeat(level))
+        }
+        (DirectoryFormat::Markdown, false) => {
+            writeln!(out, "\n{} [`{text}`]({url})", "#".repeat(level))
+        }
+        (DirectoryFormat::Html, true) => {
+            writeln!(out, "<h{level}>{}</h{level}>", escape_html(text))
+        }
+        (DirectoryFormat::Html, false) => writeln!(
+            out,
+            "<h{level}><a href=\"{}\"><code>{}</code></a></h{level}>",
+            escape_html(url),
+            escape_html(text)
+        ),
+    }
+}
+
+/// Write a list of linked names, formatted as code.
+fn write_list<'a>(
+    out: &mut String,
+    format: DirectoryFormat,
+    items: impl Iterator<Item = (&'a str, String)>,
+) -> fmt::Result {
+    match format {
+        DirectoryFormat::Markdown => {
+            writeln!(out)?;
+            for (name, url) in items {
+                writeln!(out, "- {}", LinkStyle::Markdown.render(name, &url))?;
+            }
+        }
+        DirectoryFormat::Html => {
+            writeln!(out, "<ul>")?;
+            for (name, url) in items {
+                writeln!(out, "<li>{}</li>", LinkStyle::Html.render(name, &url))?;
+            }
+            writeln!(out, "</ul>")?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn test_to_json_schema_v1() {
+        let index = test_util::anyhow_index();
+        let json = to_json_schema_v1(&index).unwrap();
+        let value = serde_json::from_str::<Value>(&json).unwrap();
+
+        assert_eq!("docsearch/index/v1", value["schema"]);
+        assert_eq!("anyhow", value["name"]);
+        assert_eq!("1.0.72", value["version"]);
+        assert_eq!("https://docs.rs/anyhow/1.0.72/anyhow", value["crate_url"]);
+        assert_eq!(
+            index.mapping.len(),
+            value["items"].as_array().unwrap().len()
+        );
+
+        let item = value["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["path"] == "anyhow::Error")
+            .unwrap();
+        assert_eq!("struct", item["kind"]);
+        assert_eq!(
+            "https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html",
+            item["url"]
+        );
+    }
+
+    #[test]
+    fn test_write_delimited() {
+        let index = test_util::anyhow_index();
+
+        let mut csv = Vec::new();
+        write_delimited(&index, &mut csv, ',').unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+
+        assert_eq!(Some("path,kind,url"), lines.next());
+        assert_eq!(index.mapping.len(), lines.count());
+        assert!(csv.contains(
+            "\nanyhow::Error,struct,https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html\n"
+        ));
+
+        let mut tsv = Vec::new();
+        write_delimited(&index, &mut tsv, '\t').unwrap();
+        let tsv = String::from_utf8(tsv).unwrap();
+
+        assert!(tsv.starts_with("path\tkind\turl\n"));
+        assert!(tsv.contains(
+            "\nanyhow::Error\tstruct\thttps://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html\n"
+        ));
+    }
+
+    #[test]
+    fn test_link_directory() {
+        let index = test_util::anyhow_index();
+
+        insta::assert_snapshot!(
+            "link_directory_markdown",
+            link_directory(&index, DirectoryFormat::Markdown)
+        );
+        insta::assert_snapshot!(
+            "link_directory_html",
+            link_directory(&index, DirectoryFormat::Html)
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Abstraction over HTTP clients, to send the requests of a search.
+
+use std::{future::Future, pin::Pin};
+
+use crate::{error::Result, Request, Response};
+
+/// Future that is returned by [`AsyncHttpFetch::fetch`].
+pub type FetchFuture<'a> = Pin<Box<dyn Future<Output = Result<Response>> + Send + 'a>>;
+
+/// Blocking HTTP client, that can send the requests of a search with [`Search::run`].
+///
+/// Integrations can be written once against this trait, instead of driving the search states
+/// themselves. An implementation for [`ureq::Agent`] is available with the `blocking` feature.
+///
+/// [`Search::run`]: crate::Search::run
+pub trait HttpFetch {
+    /// Send the request, including its headers, and return the response. Error statuses must be
+    /// returned as regular response, only failures to send the request or read the response are
+    /// errors, usually [`Error::Fetch`](crate::error::Error::Fetch).
+    fn fetch(&self, request: &Request) -> Result<Response>;
+}
+
+/// Async HTTP client, that can send the requests of a search with [`Search::run_async`], like
+/// [`HttpFetch`]. An implementation for [`reqwest::Client`] is available with the `reqwest`
+/// feature.
+///
+/// [`Search::run_async`]: crate::Search::run_async
+pub trait AsyncHttpFetch {
+    /// Send the request, including its headers, and return the response, like
+    /// [`HttpFetch::fetch`].
+    fn fetch<'a>(&'a self, request: &'a Request) -> FetchFuture<'a>;
+}
+
+impl<T: HttpFetch + ?Sized> HttpFetch for &T {
+    fn fetch(&self, request: &Request) -> Result<Response> {
+        (**self).fetch(request)
+    }
+}
+
+impl<T: AsyncHttpFetch + ?Sized> AsyncHttpFetch for &T {
+    fn fetch<'a>(&'a self, request: &'a Request) -> FetchFuture<'a> {
+        (**self).fetch(request)
+    }
+}
+
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+impl AsyncHttpFetch for reqwest::Client {
+    fn fetch<'a>(&'a self, request: &'a Request) -> FetchFuture<'a> {
+        Box::pin(async move {
+            let fetch_error = |e: reqwest::Error| crate::error::Error::Fetch {
+                url: request.url.clone(),
+                source: e.into(),
+            };
+            let method = match request.method {
+                crate::Method::Get => reqwest::Method::GET,
+                crate::Method::Head => reqwest::Method::HEAD,
+            };
+
+            let response = request
+                .headers
+                .iter()
+                .fold(
+                    self.request(method, &request.url),
+                    |builder, (name, value)| builder.header(*name, *value),
+                )
+                .send()
+                .await
+                .map_err(fetch_error)?;
+
+            let status = response.status().as_u16();
+            let final_url = response.url().to_string();
+            let body = response.text().await.map_err(fetch_error)?;
+
+            Ok(Response::new(status, body).final_url(final_url))
+        })
+    }
+}
+
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+impl HttpFetch for ureq::Agent {
+    fn fetch(&self, request: &Request) -> Result<Response> {
+        use std::io::Read;
+
+        let fetch_error =
+            |e: Box<dyn std::error::Error + Send + Sync>| crate::error::Error::Fetch {
+                url: request.url.clone(),
+                source: e,
+            };
+
+        let response = match request
+            .headers
+            .iter()
+            .fold(
+                self.request(request.method.as_str(), &request.url),
+                |builder, (name, value)| builder.set(name, value),
+            )
+            .call()
+        {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(fetch_error(Box::new(e))),
+        };
+
+        let status = response.status();
+        let final_url = response.get_url().to_owned();
+        let mut body = String::new();
+        response
+            .into_reader()
+            .read_to_string(&mut body)
+            .map_err(|e| fetch_error(Box::new(e)))?;
+
+        Ok(Response::new(status, body).final_url(final_url))
+    }
+}
@@ -0,0 +1,379 @@
+//! Configuration of the hosts that serve the crate and stdlib documentation.
+
+use std::borrow::Cow;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::crates;
+
+/// Base URL for the `docs.rs` docs service.
+pub(crate) const DOCSRS_URL: &str = "https://docs.rs";
+/// Base URL for the static file host of `docs.rs`, which serves the same files with long cache
+/// lifetimes.
+const STATIC_DOCSRS_URL: &str = "https://static.docs.rs";
+/// Base URL for the stdlib docs.
+const STDLIB_URL: &str = "https://doc.rust-lang.org/nightly";
+
+/// Release channel of the stdlib docs on `doc.rust-lang.org`, that links point to.
+///
+/// ```rust
+/// use docsearch::{LinkOptions, StdChannel};
+///
+/// let options = LinkOptions::new().std_channel(StdChannel::Stable);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StdChannel {
+    /// The nightly docs, like `https://doc.rust-lang.org/nightly/std/...`. These contain the
+    /// newest items, which is why they're used by default.
+    #[default]
+    Nightly,
+    /// The beta docs, like `https://doc.rust-lang.org/beta/std/...`.
+    Beta,
+    /// The stable docs, like `https://doc.rust-lang.org/stable/std/...`.
+    Stable,
+    /// The docs without an explicit channel, like `https://doc.rust-lang.org/std/...`, which
+    /// point to the latest stable release as well.
+    Unversioned,
+}
+
+impl StdChannel {
+    /// Base URL of the stdlib docs for this channel.
+    #[must_use]
+    pub const fn url(self) -> &'static str {
+        match self {
+            Self::Nightly => STDLIB_URL,
+            Self::Beta => "https://doc.rust-lang.org/beta",
+            Self::Stable => "https://doc.rust-lang.org/stable",
+            Self::Unversioned => "https://doc.rust-lang.org",
+        }
+    }
+}
+
+/// Hosts that documentation is retrieved from and links are generated for. By default, these are
+/// `docs.rs` for crates and `doc.rust-lang.org` for the stdlib.
+///
+/// Custom hosts use the same URL layout as docs.rs by default, which allows to point the search at
+/// an internal mirror of the service. Other layouts, like the ones of alternative registries, can
+/// be described with a [`HostProfile`].
+///
+/// ```rust
+/// use docsearch::{DocHost, Version};
+///
+/// let host = DocHost::new("https://docs.example.com").std_url("https://std.example.com/nightly");
+/// let state = docsearch::start_search("anyhow", Version::Latest).with_host(host);
+///
+/// assert_eq!("https://docs.example.com/anyhow/latest/anyhow/", state.url());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DocHost {
+    pub(crate) docs: Cow<'static, str>,
+    pub(crate) std: Cow<'static, str>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) profile: HostProfile,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) index: Option<Cow<'static, str>>,
+}
+
+impl Default for DocHost {
+    fn default() -> Self {
+        Self {
+            docs: Cow::Borrowed(DOCSRS_URL),
+            std: Cow::Borrowed(STDLIB_URL),
+            profile: HostProfile::default(),
+            index: None,
+        }
+    }
+}
+
+impl DocHost {
+    /// Create a new host configuration with a custom base URL for crate docs, in place of
+    /// `https://docs.rs`. The stdlib docs stay at their default location.
+    #[must_use]
+    pub fn new(docs_url: impl Into<String>) -> Self {
+        Self {
+            docs: Cow::Owned(trim_url(docs_url.into())),
+            ..Self::default()
+        }
+    }
+
+    /// Set a custom base URL for the stdlib docs, in place of `https://doc.rust-lang.org/nightly`.
+    #[must_use]
+    pub fn std_url(mut self, url: impl Into<String>) -> Self {
+        self.std = Cow::Owned(trim_url(url.into()));
+        self
+    }
+
+    /// Use the stdlib docs of the given release channel on `doc.rust-lang.org`, in place of the
+    /// nightly docs.
+    #[must_use]
+    pub fn std_channel(mut self, channel: StdChannel) -> Self {
+        self.std = Cow::Borrowed(channel.url());
+        self
+    }
+
+    /// Set the URL layout of the crate docs, in case it differs from the one of docs.rs.
+    #[must_use]
+    pub fn profile(mut self, profile: HostProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Set a separate base URL that search index files are retrieved from, instead of the base URL
+    /// for crate docs. The index files must be located at the same paths as on the main host.
+    #[must_use]
+    pub fn index_url(mut self, url: impl Into<String>) -> Self {
+        self.index = Some(Cow::Owned(trim_url(url.into())));
+        self
+    }
+
+    /// Retrieve search index files from `static.docs.rs`, which serves them with long cache
+    /// lifetimes. This reduces load and latency for high-volume users of docs.rs.
+    ///
+    /// ```rust
+    /// use docsearch::DocHost;
+    ///
+    /// let state = docsearch::start_search("anyhow", "1.0.72".parse().unwrap())
+    ///     .with_host(DocHost::default().static_index())
+    ///     .with_resource_suffix("-20230714-1.73.0-nightly-ad963232d")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     "https://static.docs.rs/anyhow/1.0.72/search-index-20230714-1.73.0-nightly-ad963232d.js",
+    ///     state.url()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn static_index(mut self) -> Self {
+        self.index = Some(Cow::Borrowed(STATIC_DOCSRS_URL));
+        self
+    }
+
+    /// Base URL for crate docs.
+    #[must_use]
+    pub fn docs(&self) -> &str {
+        &self.docs
+    }
+
+    /// Base URL for the stdlib docs.
+    #[must_use]
+    pub fn std(&self) -> &str {
+        &self.std
+    }
+}
+
+impl DocHost {
+    /// Create the URL to the docs page of a crate.
+    pub(crate) fn page_url(&self, name: &str, version: &str) -> String {
+        Self::render(&self.docs, &self.profile.page, name, version, "")
+    }
+
+    /// Create the URL to the search index of a crate, from the index file name.
+    pub(crate) fn search_index_url(&self, name: &str, version: &str, file: &str) -> String {
+        let base = self.index.as_ref().unwrap_or(&self.docs);
+        Self::render(base, &self.profile.index, name, version, file)
+    }
+
+    /// Create the URL to an item of a crate, from the item's path as found in the index mapping.
+    pub(crate) fn link_url(&self, name: &str, version: &str, path: &str) -> String {
+        Self::render(&self.docs, &self.profile.link, name, version, path)
+    }
+
+    /// Position of the version in the path segments of page URLs, including the segments of the
+    /// base URL itself. This allows to extract the version from redirects.
+    pub(crate) fn version_position(&self) -> Option<usize> {
+        let base = self
+            .docs
+            .split_once("://")
+            .and_then(|(_, rest)| rest.split_once('/'))
+            .map_or(0, |(_, path)| {
+                path.split('/').filter(|s| !s.is_empty()).count()
+            });
+
+        self.profile
+            .page
+            .split('/')
+            .position(|segment| segment.contains("{version}"))
+            .map(|pos| base + pos)
+    }
+
+    fn render(base: &str, template: &str, name: &str, version: &str, value: &str) -> String {
+        let path = template
+            .replace("{name}", name)
+            .replace("{crate}", &crates::crate_name(name))
+            .replace("{version}", version)
+            .replace("{file}", value)
+            .replace("{path}", value);
+
+        format!("{base}/{path}")
+    }
+}
+
+/// URL layout of a documentation host, described as path templates relative to its base URL.
+///
+/// The templates can contain the following placeholders:
+///
+/// - `{name}` for the name of the crate as published, like `serde-yaml`.
+/// - `{crate}` for the name of the crate as used in code, like `serde_yaml`.
+/// - `{version}` for the version of the crate.
+/// - `{file}` for the file name of the search index (only in the index template).
+/// - `{path}` for the path of an item, relative to the docs root (only in the link template).
+///
+/// ```rust
+/// use docsearch::{DocHost, HostProfile, Version};
+///
+/// let host = DocHost::new("https://registry.example.com/docs").profile(HostProfile::new(
+///     "{name}/{version}/doc/{crate}/index.html",
+///     "{name}/{version}/doc/{file}",
+///     "{name}/{version}/doc/{path}",
+/// ));
+/// let state = docsearch::start_search("my-crate", "1.2.0".parse().unwrap()).with_host(host);
+///
+/// assert_eq!(
+///     "https://registry.example.com/docs/my-crate/1.2.0/doc/my_crate/index.html",
+///     state.url()
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HostProfile {
+    page: Cow<'static, str>,
+    index: Cow<'static, str>,
+    link: Cow<'static, str>,
+}
+
+impl Default for HostProfile {
+    fn default() -> Self {
+        Self::DOCS_RS
+    }
+}
+
+impl HostProfile {
+    /// The URL layout of docs.rs.
+    pub const DOCS_RS: Self = Self {
+        page: Cow::Borrowed("{name}/{version}/{crate}/"),
+        index: Cow::Borrowed("{name}/{version}/{file}"),
+        link: Cow::Borrowed("{name}/{version}/{path}"),
+    };
+
+    /// Create a new profile from the templates for the docs page of a crate, its search index and
+    /// the links to its items.
+    #[must_use]
+    pub fn new(page: impl Into<String>, index: impl Into<String>, link: impl Into<String>) -> Self {
+        let template = |value: String| Cow::Owned(value.trim_start_matches('/').to_owned());
+
+        Self {
+            page: template(page.into()),
+            index: template(index.into()),
+            link: template(link.into()),
+        }
+    }
+}
+
+/// Create the URL to an item of a crate from a custom template of the whole URL, with the given
+/// base URL of the host as `{host}`.
+pub(crate) fn template_url(
+    template: &str,
+    host: &str,
+    name: &str,
+    version: &str,
+    path: &str,
+) -> String {
+    template
+        .replace("{host}", host)
+        .replace("{name}", name)
+        .replace("{crate}", &crates::crate_name(name))
+        .replace("{version}", version)
+        .replace("{path}", path)
+}
+
+/// Remove any trailing slashes from the URL, as paths are appended with a leading slash.
+fn trim_url(mut url: String) -> String {
+    url.truncate(url.trim_end_matches('/').len());
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doc_host() {
+        let host = DocHost::new("https://docs.example.com/").std_url("https://std.example.com//");
+        assert_eq!("https://docs.example.com", host.docs());
+        assert_eq!("https://std.example.com", host.std());
+
+        let host = DocHost::default();
+        assert_eq!("https://docs.rs", host.docs());
+        assert_eq!("https://doc.rust-lang.org/nightly", host.std());
+    }
+
+    #[test]
+    fn test_host_profile() {
+        let host = DocHost::default();
+        assert_eq!(
+            "https://docs.rs/serde-yaml/0.9.0/serde_yaml/",
+            host.page_url("serde-yaml", "0.9.0")
+        );
+        assert_eq!(Some(1), host.version_position());
+
+        let host = DocHost::new("https://registry.example.com/docs/").profile(HostProfile::new(
+            "/{name}/{version}/doc/{crate}/index.html",
+            "{name}/{version}/doc/{file}",
+            "{name}/{version}/doc/{path}",
+        ));
+        assert_eq!(
+            "https://registry.example.com/docs/serde-yaml/0.9.0/doc/search-index.js",
+            host.search_index_url("serde-yaml", "0.9.0", "search-index.js")
+        );
+        assert_eq!(
+            "https://registry.example.com/docs/serde-yaml/0.9.0/doc/serde_yaml/struct.Value.html",
+            host.link_url("serde-yaml", "0.9.0", "serde_yaml/struct.Value.html")
+        );
+        assert_eq!(Some(2), host.version_position());
+    }
+
+    #[test]
+    fn test_template_url() {
+        assert_eq!(
+            "https://proxy.example.com/serde_yaml@0.9.0/serde_yaml/struct.Value.html",
+            template_url(
+                "https://proxy.example.com/{crate}@{version}/{path}",
+                "https://docs.rs",
+                "serde-yaml",
+                "0.9.0",
+                "serde_yaml/struct.Value.html"
+            )
+        );
+        assert_eq!(
+            "https://doc.rust-lang.org/nightly/std/vec/struct.Vec.html",
+            template_url(
+                "{host}/{path}",
+                STDLIB_URL,
+                "std",
+                "",
+                "std/vec/struct.Vec.html"
+            )
+        );
+    }
+
+    #[test]
+    fn test_std_channel() {
+        assert_eq!(
+            "https://doc.rust-lang.org/nightly",
+            DocHost::default().std()
+        );
+        assert_eq!(
+            "https://doc.rust-lang.org/stable",
+            DocHost::default().std_channel(StdChannel::Stable).std()
+        );
+        assert_eq!(
+            "https://doc.rust-lang.org",
+            DocHost::default()
+                .std_channel(StdChannel::Unversioned)
+                .std()
+        );
+    }
+}
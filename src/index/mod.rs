@@ -6,20 +6,27 @@ use std::{
     fmt,
 };
 
+#[cfg(feature = "serde")]
+use serde::Serialize;
 use serde::{
-    de::{SeqAccess, Visitor},
+    de::{DeserializeOwned, SeqAccess, Visitor},
     Deserialize, Deserializer,
 };
+use serde_json::value::RawValue;
 use serde_repr::Deserialize_repr;
 
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    ParseLimits,
+};
 
 #[cfg(feature = "index-v1")]
 mod v1;
 #[cfg(feature = "index-v2")]
 mod v2;
 
-#[cfg_attr(test, derive(Clone, Copy, Eq, PartialEq, serde::Serialize))]
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(test, derive(serde::Serialize))]
 enum Version {
     #[cfg(feature = "index-v1")]
     V1,
@@ -28,6 +35,18 @@ enum Version {
     V3,
 }
 
+impl From<Version> for IndexFormat {
+    fn from(version: Version) -> Self {
+        match version {
+            #[cfg(feature = "index-v1")]
+            Version::V1 => Self::V1,
+            #[cfg(feature = "index-v2")]
+            Version::V2 => Self::V2,
+            Version::V3 => Self::V3,
+        }
+    }
+}
+
 impl Version {
     fn detect(index: &str) -> Option<Self> {
         #[cfg(feature = "index-v1")]
@@ -52,53 +71,204 @@ impl Version {
     }
 }
 
-/// Whole index data after transformation.
-#[cfg_attr(test, derive(PartialEq, Eq, serde::Serialize))]
-struct IndexData {
+/// Mappings from simple paths to URL paths, for each crate contained in an index.
+pub type Mappings = HashMap<String, CrateMapping>;
+
+/// Mapping from simple paths to URL paths for a single crate.
+#[derive(Debug, Default)]
+pub struct CrateMapping {
+    /// URL path for each simple path. If multiple items share the same path, like the module
+    /// `std::vec` and the macro `vec!`, only the last one is kept here.
+    pub links: BTreeMap<String, String>,
+    /// URL paths of further items, that share their path with the item in [`Self::links`].
+    pub shadowed: BTreeMap<String, Vec<String>>,
+}
+
+/// Problem in a search index, that was skipped when parsing it in lenient mode with
+/// [`SearchIndex::transform_index_lenient`](crate::SearchIndex::transform_index_lenient).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IndexWarning {
+    /// The data of a crate couldn't be parsed, so the crate was dropped as a whole.
+    InvalidCrate {
+        /// Name of the crate.
+        name: String,
+        /// Description of the parsing error.
+        reason: String,
+    },
+    /// An item referred to a parent that doesn't exist, so the item was dropped.
+    InvalidParent {
+        /// Name of the crate.
+        name: String,
+        /// Path of the item, without the parent.
+        item: String,
+        /// Position of the missing parent.
+        parent: usize,
+    },
+    /// The data of an item was incomplete or malformed, so the item was dropped.
+    InvalidItem {
+        /// Name of the crate.
+        name: String,
+        /// Position of the item in the crate's data.
+        position: usize,
+        /// Description of the problem.
+        reason: String,
+    },
+}
+
+impl fmt::Display for IndexWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCrate { name, reason } => {
+                write!(f, "dropped the crate `{name}` with invalid data: {reason}")
+            }
+            Self::InvalidParent { name, item, parent } => write!(
+                f,
+                "dropped the item `{item}` of the crate `{name}` with the missing parent {parent}"
+            ),
+            Self::InvalidItem {
+                name,
+                position,
+                reason,
+            } => write!(
+                f,
+                "dropped the item at position {position} of the crate `{name}`: {reason}"
+            ),
+        }
+    }
+}
+
+/// Format of the search index, that an [`Index`](crate::Index) was parsed from.
+///
+/// Newer releases of this crate might extract more information from the same format, or support
+/// new formats. Caches can use this to invalidate entries that were parsed from older formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum IndexFormat {
+    /// The oldest format, only supported with the `index-v1` feature.
+    V1,
+    /// The outdated format, only supported with the `index-v2` feature.
+    V2,
+    /// The current format.
+    V3,
+    /// Any format that was parsed by a custom [`IndexParser`].
+    Custom,
+}
+
+/// Parser for a specific format of the search index, that transforms the raw index content into
+/// the common [`RawIndexData`] structure.
+///
+/// The built-in formats are implemented by [`IndexV1Parser`], [`IndexV2Parser`] and
+/// [`IndexV3Parser`]. When `rustdoc` changes its format again, a custom parser can be passed to
+/// [`SearchIndex::transform_index_with`](crate::SearchIndex::transform_index_with) until support
+/// for the new format lands in this crate.
+pub trait IndexParser {
+    /// Check whether the index content is in the format supported by this parser.
+    fn detect(&self, index: &str) -> bool;
+
+    /// Parse the index content into its raw data.
+    fn parse(&self, index: &str) -> Result<RawIndexData>;
+}
+
+/// Parser for the oldest index format, that is a JavaScript object literal with references into a
+/// shared list of strings.
+#[cfg(feature = "index-v1")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IndexV1Parser;
+
+#[cfg(feature = "index-v1")]
+impl IndexParser for IndexV1Parser {
+    fn detect(&self, index: &str) -> bool {
+        Version::detect(index) == Some(Version::V1)
+    }
+
+    fn parse(&self, index: &str) -> Result<RawIndexData> {
+        v1::load_raw(index, None).map_err(Into::into)
+    }
+}
+
+/// Parser for the outdated index format, that contains a list of entries per crate.
+#[cfg(feature = "index-v2")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IndexV2Parser;
+
+#[cfg(feature = "index-v2")]
+impl IndexParser for IndexV2Parser {
+    fn detect(&self, index: &str) -> bool {
+        Version::detect(index) == Some(Version::V2)
+    }
+
+    fn parse(&self, index: &str) -> Result<RawIndexData> {
+        v2::load_raw(index, None)
+    }
+}
+
+/// Parser for the current index format, that contains separate lists for each field of an entry.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IndexV3Parser;
+
+impl IndexParser for IndexV3Parser {
+    fn detect(&self, index: &str) -> bool {
+        Version::detect(index) == Some(Version::V3)
+    }
+
+    fn parse(&self, index: &str) -> Result<RawIndexData> {
+        load_raw(index, None)
+    }
+}
+
+/// Whole index data after transformation, as created by [`raw::transform`](crate::raw::transform).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct IndexData {
     /// Mapping from crate name to data.
-    crates: HashMap<String, CrateData>,
+    pub crates: HashMap<String, CrateData>,
 }
 
 /// Crate data after transformation.
-#[cfg_attr(test, derive(PartialEq, Eq, serde::Serialize))]
-struct CrateData {
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct CrateData {
     /// Doc string of the crate.
-    #[allow(dead_code)]
-    doc: String,
+    pub doc: String,
     /// Data for each individual item of the crate.
-    items: Vec<IndexItem>,
-    /// Parent paths that help to construct full paths and URLs from item information.
-    paths: Vec<(ItemType, String)>,
+    pub items: Vec<IndexItem>,
+    /// Parent paths that help to construct full paths and URLs from item information. These are
+    /// referenced by [`IndexItem::parent_idx`].
+    pub paths: Vec<(ItemType, String)>,
     // aliases
 }
 
 /// Index data for a single item after transformation.
 ///
 /// Taken from: <https://github.com/rust-lang/rust/blob/eba3228b2a9875d268ff3990903d04e19f6cdb0c/src/librustdoc/html/render/mod.rs#L84>.
-#[cfg_attr(test, derive(PartialEq, Eq, serde::Serialize))]
-struct IndexItem {
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct IndexItem {
     /// The type of item.
-    ty: ItemType,
+    pub ty: ItemType,
     /// Simple name without path.
-    name: String,
+    pub name: String,
     /// Resolved, full path.
-    path: String,
+    pub path: String,
     /// Short, one line description. Can contain HTML tags and is likely truncated with the `…`
     /// character.
-    #[allow(dead_code)]
-    desc: String,
-    /// Index to the parent item, if it belongs to another item.
-    parent_idx: Option<usize>,
+    pub desc: String,
+    /// Index to the parent item in [`CrateData::paths`], if it belongs to another item. The index
+    /// comes from untrusted input and might be out of bounds.
+    pub parent_idx: Option<usize>,
     // search_type
 }
 
 /// Different item types that can appear in the rust docs to identify the kind of item.
 ///
 /// Taken from: <https://github.com/rust-lang/rust/blob/eba3228b2a9875d268ff3990903d04e19f6cdb0c/src/librustdoc/formats/item_type.rs>.
-#[derive(Clone, Copy, Debug, Deserialize_repr)]
-#[cfg_attr(test, derive(PartialEq, Eq, serde::Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize_repr)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[non_exhaustive]
 #[repr(u8)]
-enum ItemType {
+pub enum ItemType {
     Module = 0,
     ExternCrate = 1,
     Import = 2,
@@ -128,7 +298,7 @@ enum ItemType {
 }
 
 impl ItemType {
-    const fn as_str(self) -> &'static str {
+    pub(crate) const fn as_str(self) -> &'static str {
         match self {
             Self::Module => "mod",
             Self::ExternCrate => "externcrate",
@@ -159,6 +329,80 @@ impl ItemType {
         }
     }
 
+    /// Prefix to disambiguate intra-doc links to items of this type, like `macro` for
+    /// `macro@std::vec`.
+    pub(crate) const fn disambiguator(self) -> &'static str {
+        match self {
+            Self::Module => "mod",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Union => "union",
+            Self::Trait => "trait",
+            Self::Function => "fn",
+            Self::Constant | Self::AssocConst => "const",
+            Self::Static => "static",
+            Self::Macro | Self::ProcAttribute => "macro",
+            Self::ProcDerive => "derive",
+            Self::Primitive => "prim",
+            Self::TyMethod | Self::Method => "method",
+            Self::StructField => "field",
+            Self::Variant => "variant",
+            Self::ExternCrate
+            | Self::Import
+            | Self::Typedef
+            | Self::Impl
+            | Self::AssocType
+            | Self::ForeignType
+            | Self::Keyword
+            | Self::OpaqueTy
+            | Self::TraitAlias => "type",
+        }
+    }
+
+    fn from_str_name(value: &str) -> Option<Self> {
+        Some(match value {
+            "mod" => Self::Module,
+            "externcrate" => Self::ExternCrate,
+            "import" => Self::Import,
+            "struct" => Self::Struct,
+            "union" => Self::Union,
+            "enum" => Self::Enum,
+            "fn" => Self::Function,
+            "type" => Self::Typedef,
+            "static" => Self::Static,
+            "trait" => Self::Trait,
+            "impl" => Self::Impl,
+            "tymethod" => Self::TyMethod,
+            "method" => Self::Method,
+            "structfield" => Self::StructField,
+            "variant" => Self::Variant,
+            "macro" => Self::Macro,
+            "primitive" => Self::Primitive,
+            "associatedtype" => Self::AssocType,
+            "constant" => Self::Constant,
+            "associatedconstant" => Self::AssocConst,
+            "foreigntype" => Self::ForeignType,
+            "keyword" => Self::Keyword,
+            "opaque" => Self::OpaqueTy,
+            "attr" => Self::ProcAttribute,
+            "derive" => Self::ProcDerive,
+            "traitalias" => Self::TraitAlias,
+            _ => return None,
+        })
+    }
+
+    /// Determine the item type from a URL path of the index mapping. Members of other items are
+    /// identified by their fragment, like `#method.context`, all other items by their page name,
+    /// like `struct.Error.html`.
+    pub(crate) fn from_link(link: &str) -> Option<Self> {
+        let name = match link.split_once('#') {
+            Some((_, fragment)) => fragment,
+            None => link.rsplit('/').next()?,
+        };
+
+        Self::from_str_name(name.split_once('.')?.0)
+    }
+
     const fn from_raw(value: u8) -> Option<Self> {
         Some(match value {
             0 => Self::Module,
@@ -195,40 +439,40 @@ impl ItemType {
 /// The whole index data for a crate. It usually contains only one entry for the crate it was
 /// generated for. The stdlib index is a special case where multiple crates like `std` and `alloc`
 /// are included.
-#[derive(Debug, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Eq, serde::Serialize))]
-struct RawIndexData {
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RawIndexData {
     /// Mapping from crate name to raw index data.
     #[serde(flatten)]
-    crates: HashMap<String, RawCrateData>,
+    pub crates: HashMap<String, RawCrateData>,
 }
 
 /// Crate index data in its raw form. All elements are vectors and the same index over all of them
 /// contain the information for a single item.
 ///
 /// Taken from: <https://github.com/rust-lang/rust/blob/eba3228b2a9875d268ff3990903d04e19f6cdb0c/src/librustdoc/html/render/cache.rs#L121>.
-#[derive(Debug, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Eq, serde::Serialize))]
-struct RawCrateData {
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RawCrateData {
     /// Doc string for the crate. Seems to always be `github\u{2002}crates-io\u{2002}docs-rs`.
-    doc: String,
+    pub doc: String,
     /// Type of item.
     #[serde(deserialize_with = "t")]
-    t: Vec<ItemType>,
+    pub t: Vec<ItemType>,
     /// Simple name without the path.
-    n: Vec<String>,
+    pub n: Vec<String>,
     /// Module path of the item. This uses previous items as reference and an empty value means to
     /// use the value of the previous item. Similar to being still in the same _directory_.
     #[serde(deserialize_with = "q")]
-    q: BTreeMap<usize, String>,
+    pub q: BTreeMap<usize, String>,
     /// Short, one line description of the item. Maybe contain HTML tags and is likely truncated.
-    d: Vec<String>,
+    pub d: Vec<String>,
     /// Index of the parent item. For example if the item is a method, it references the index of
     /// the struct/enum/... it belongs to.
     ///
     /// A value of `0` means that no parent exists. Therefore, indexes start at `1` and need to be
     /// adjusted to access the right item in the other vectors.
-    i: Vec<usize>,
+    pub i: Vec<usize>,
     // f: search type
     /// Further information about the parent item that helps in constructing the full path of an
     /// item with parent.
@@ -237,7 +481,7 @@ struct RawCrateData {
     /// the basic path `foo` as the [`Self::q`] value only describes module paths. This field
     /// contains the parent name `Bar` (and its item type) so that the full path `foo::Bar::baz` can
     /// be constructed.
-    p: Vec<(ItemType, String)>,
+    pub p: Vec<(ItemType, String)>,
     // a: aliases
 }
 
@@ -246,17 +490,56 @@ struct RawCrateData {
 ///
 /// This is the combination of the internal functions [`load_raw`], [`transform`] and
 /// [`generate_mapping`].
-pub fn load(index: &str) -> Result<HashMap<String, BTreeMap<String, String>>> {
-    let raw = match Version::detect(index) {
-        Some(Version::V3) => load_raw(index)?,
+pub fn load(index: &str) -> Result<(IndexFormat, Mappings)> {
+    load_with(index, None, None, &ParseLimits::new())
+}
+
+/// Parse and transform a raw index file like [`load`], but try the given custom parser before
+/// the built-in ones.
+///
+/// If a list of warnings is given, the index is parsed in lenient mode. Crates with invalid data,
+/// as well as incomplete items and items that refer to missing parents are skipped and reported
+/// as warnings, instead of failing the whole index.
+///
+/// The index and each crate in it must stay within the given limits.
+pub fn load_with(
+    index: &str,
+    parser: Option<&dyn IndexParser>,
+    mut warnings: Option<&mut Vec<IndexWarning>>,
+    limits: &ParseLimits,
+) -> Result<(IndexFormat, Mappings)> {
+    limits.check_index(index)?;
+
+    if let Some(parser) = parser.filter(|parser| parser.detect(index)) {
+        let raw = check_lengths(parser.parse(index)?, warnings.as_deref_mut())?;
+        let raw = limits.check_crates(raw, warnings.as_deref_mut())?;
+        let mapping = generate_mapping(transform(raw), warnings)?;
+        return Ok((IndexFormat::Custom, mapping));
+    }
+
+    let (format, raw) = load_any(index, warnings.as_deref_mut())?;
+    let raw = check_lengths(raw, warnings.as_deref_mut())?;
+    let raw = limits.check_crates(raw, warnings.as_deref_mut())?;
+
+    Ok((format, generate_mapping(transform(raw), warnings)?))
+}
+
+/// Detect the format of the index and parse it with the matching built-in parser, without any
+/// further transformation.
+pub(crate) fn load_any(
+    index: &str,
+    warnings: Option<&mut Vec<IndexWarning>>,
+) -> Result<(IndexFormat, RawIndexData)> {
+    let version = Version::detect(index).ok_or(Error::UnsupportedIndexVersion)?;
+    let raw = match version {
+        Version::V3 => load_raw(index, warnings)?,
         #[cfg(feature = "index-v2")]
-        Some(Version::V2) => v2::load_raw(index)?,
+        Version::V2 => v2::load_raw(index, warnings)?,
         #[cfg(feature = "index-v1")]
-        Some(Version::V1) => v1::load_raw(index)?,
-        None => return Err(Error::UnsupportedIndexVersion),
+        Version::V1 => v1::load_raw(index, warnings)?,
     };
 
-    Ok(generate_mapping(transform(raw)))
+    Ok((version.into(), raw))
 }
 
 /// Extract the JSON content from the index data and run it through [`serde`] to transform it into
@@ -277,7 +560,7 @@ pub fn load(index: &str) -> Result<HashMap<String, BTreeMap<String, String>>> {
 ///
 /// For further explanation of the individual fields of a single crate entry, looks at the docs of
 /// [`RawIndexData`] and [`RawCrateData`].
-fn load_raw(index: &str) -> Result<RawIndexData> {
+fn load_raw(index: &str, warnings: Option<&mut Vec<IndexWarning>>) -> Result<RawIndexData> {
     let json = {
         let mut json = index
             .lines()
@@ -301,7 +584,231 @@ fn load_raw(index: &str) -> Result<RawIndexData> {
             .replace(r"\\", r"\")
     };
 
-    serde_json::from_str(&json).map_err(Into::into)
+    parse_crates::<RawCrateData>(&json, warnings)
+}
+
+/// Raw format of the crate data in a specific index version, that converts into the common
+/// [`RawCrateData`].
+pub(super) trait CrateFormat: DeserializeOwned + Into<RawCrateData> {
+    /// Variant of the format for lenient mode, that keeps malformed items as errors instead of
+    /// failing the whole crate.
+    type Lenient: DeserializeOwned + Into<LenientCrateData>;
+}
+
+impl CrateFormat for RawCrateData {
+    type Lenient = LenientRawCrateData;
+}
+
+/// Crate data of any index version in lenient mode, where each item was deserialized on its own.
+pub(super) struct LenientCrateData {
+    pub doc: String,
+    /// Data of each item, or the reason why it couldn't be deserialized.
+    pub items: Vec<Result<RawItem, String>>,
+    /// Module paths, by the position of the item in [`Self::items`].
+    pub q: BTreeMap<usize, String>,
+    pub p: Vec<(ItemType, String)>,
+}
+
+/// Data of a single item in its raw form, like in [`RawCrateData`].
+pub(super) struct RawItem {
+    pub t: ItemType,
+    pub n: String,
+    pub d: String,
+    pub i: usize,
+}
+
+impl LenientCrateData {
+    /// Drop all malformed items, and report each of them as warning.
+    ///
+    /// Module paths only appear on the first item of each module. The path of a dropped item is
+    /// therefore moved to the next item, unless that one defines its own path.
+    pub(super) fn into_raw(self, name: &str, warnings: &mut Vec<IndexWarning>) -> RawCrateData {
+        let mut q = self.q;
+        let mut data = RawCrateData {
+            doc: self.doc,
+            p: self.p,
+            ..RawCrateData::default()
+        };
+        let mut path = None;
+
+        for (position, item) in self.items.into_iter().enumerate() {
+            let item_path = q.remove(&position);
+
+            match item {
+                Ok(item) => {
+                    if let Some(path) = item_path.or(path.take()) {
+                        data.q.insert(data.t.len(), path);
+                    }
+
+                    data.t.push(item.t);
+                    data.n.push(item.n);
+                    data.d.push(item.d);
+                    data.i.push(item.i);
+                }
+                Err(reason) => {
+                    if item_path.is_some() {
+                        path = item_path;
+                    }
+
+                    warnings.push(IndexWarning::InvalidItem {
+                        name: name.to_owned(),
+                        position,
+                        reason,
+                    });
+                }
+            }
+        }
+
+        data
+    }
+}
+
+/// Element of a list that doesn't fail the whole list if it's malformed, but keeps the reason
+/// instead.
+pub(super) struct Lenient<T>(pub Result<T, String>);
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Lenient<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Ok(Self(T::deserialize(value).map_err(|e| e.to_string())))
+    }
+}
+
+/// Crate data of the current index format in lenient mode, with each element of the item lists
+/// deserialized on its own.
+#[derive(Deserialize)]
+pub(super) struct LenientRawCrateData {
+    doc: String,
+    #[serde(deserialize_with = "t_lenient")]
+    t: Vec<Lenient<ItemType>>,
+    n: Vec<Lenient<String>>,
+    #[serde(deserialize_with = "q")]
+    q: BTreeMap<usize, String>,
+    d: Vec<Lenient<String>>,
+    i: Vec<Lenient<usize>>,
+    p: Vec<(ItemType, String)>,
+}
+
+impl From<LenientRawCrateData> for LenientCrateData {
+    fn from(raw: LenientRawCrateData) -> Self {
+        let len = [raw.t.len(), raw.n.len(), raw.d.len(), raw.i.len()]
+            .into_iter()
+            .max()
+            .unwrap_or_default();
+        let mut t = raw.t.into_iter();
+        let mut n = raw.n.into_iter();
+        let mut d = raw.d.into_iter();
+        let mut i = raw.i.into_iter();
+
+        let items = (0..len)
+            .map(|_| match (t.next(), n.next(), d.next(), i.next()) {
+                (Some(Lenient(t)), Some(Lenient(n)), Some(Lenient(d)), Some(Lenient(i))) => {
+                    Ok(RawItem {
+                        t: t?,
+                        n: n?,
+                        d: d?,
+                        i: i?,
+                    })
+                }
+                _ => Err("the item data is incomplete".to_owned()),
+            })
+            .collect();
+
+        Self {
+            doc: raw.doc,
+            items,
+            q: raw.q,
+            p: raw.p,
+        }
+    }
+}
+
+/// Deserialize the JSON object that contains the data of each crate, in the raw format `T` of the
+/// index version.
+///
+/// In lenient mode, when a list of warnings is given, the crates are deserialized one by one. Any
+/// crate with invalid data is skipped, as well as any malformed item within the remaining crates,
+/// and reported as warning.
+fn parse_crates<T>(json: &str, warnings: Option<&mut Vec<IndexWarning>>) -> Result<RawIndexData>
+where
+    T: CrateFormat,
+{
+    let warnings = if let Some(warnings) = warnings {
+        warnings
+    } else {
+        let crates = serde_json::from_str::<HashMap<String, T>>(json)?;
+        return Ok(RawIndexData {
+            crates: crates
+                .into_iter()
+                .map(|(name, raw)| (name, raw.into()))
+                .collect(),
+        });
+    };
+
+    let crates = serde_json::from_str::<HashMap<String, &RawValue>>(json)?
+        .into_iter()
+        .filter_map(
+            |(name, raw)| match serde_json::from_str::<T::Lenient>(raw.get()) {
+                Ok(raw) => {
+                    let raw = raw.into().into_raw(&name, warnings);
+                    Some((name, raw))
+                }
+                Err(e) => {
+                    warnings.push(IndexWarning::InvalidCrate {
+                        name,
+                        reason: e.to_string(),
+                    });
+                    None
+                }
+            },
+        )
+        .collect();
+
+    Ok(RawIndexData { crates })
+}
+
+/// Ensure that the lists of item data in each crate have the same length, as [`transform`] would
+/// silently drop the items that are only partially present otherwise.
+///
+/// In lenient mode, when a list of warnings is given, the lists are cut to the shortest one and
+/// each dropped item is reported as warning. Otherwise, any mismatch fails with
+/// [`Error::MismatchedItemData`].
+fn check_lengths(
+    mut raw: RawIndexData,
+    mut warnings: Option<&mut Vec<IndexWarning>>,
+) -> Result<RawIndexData> {
+    for (name, data) in &mut raw.crates {
+        let lengths = [data.t.len(), data.n.len(), data.d.len(), data.i.len()];
+        let (min, max) = (
+            lengths.iter().min().copied().unwrap_or_default(),
+            lengths.iter().max().copied().unwrap_or_default(),
+        );
+
+        if min == max {
+            continue;
+        }
+
+        let warnings = warnings
+            .as_deref_mut()
+            .ok_or_else(|| Error::MismatchedItemData { name: name.clone() })?;
+
+        data.t.truncate(min);
+        data.n.truncate(min);
+        data.d.truncate(min);
+        data.i.truncate(min);
+        data.q.retain(|&position, _| position < min);
+
+        warnings.extend((min..max).map(|position| IndexWarning::InvalidItem {
+            name: name.clone(),
+            position,
+            reason: "the item data is incomplete".to_owned(),
+        }));
+    }
+
+    Ok(raw)
 }
 
 /// Convert from the index data into a more usable data structure that contains one full data set
@@ -316,9 +823,9 @@ fn load_raw(index: &str) -> Result<RawIndexData> {
 ///
 /// ## Implementation
 ///
-/// The separate elements of each item are combined back together with the [`Iterator::zip`] method.
-/// A nice side effect is that we don't have to cope for differences in vector sizes (which should
-/// not exist but can theoretically) as it stops as soon as one of the iterators returns [`None`].
+/// The separate elements of each item are combined back together with the [`Iterator::zip`] method,
+/// which stops as soon as one of the iterators returns [`None`]. Differences in vector sizes are
+/// caught by [`check_lengths`] before, when loading an index.
 ///
 /// The path field is only present if it changes compared to the previous item to reduce index size.
 /// The previous path is kept around thanks to the [`Iterator::fold`] method and only updated if the
@@ -328,7 +835,7 @@ fn load_raw(index: &str) -> Result<RawIndexData> {
 ///
 /// Parent indexes are transformed from a `usize` into an `Option<usize>` to erase the special
 /// handling of the `0` value and indexes are reduced by `1` to allow proper indexing.
-fn transform(raw: RawIndexData) -> IndexData {
+pub(crate) fn transform(raw: RawIndexData) -> IndexData {
     IndexData {
         crates: raw
             .crates
@@ -372,10 +879,16 @@ fn transform(raw: RawIndexData) -> IndexData {
 
 /// Generate a mapping from the transformed index data. This simply calls [`generate_crate_mapping`]
 /// for each crate in the index to do the actual transformation of item data.
-fn generate_mapping(data: IndexData) -> HashMap<String, BTreeMap<String, String>> {
+fn generate_mapping(
+    data: IndexData,
+    mut warnings: Option<&mut Vec<IndexWarning>>,
+) -> Result<Mappings> {
     data.crates
         .into_iter()
-        .map(|(name, data)| (name, generate_crate_mapping(data)))
+        .map(|(name, data)| {
+            let mapping = generate_crate_mapping(&name, data, warnings.as_deref_mut())?;
+            Ok((name, mapping))
+        })
         .collect()
 }
 
@@ -396,19 +909,46 @@ fn generate_mapping(data: IndexData) -> HashMap<String, BTreeMap<String, String>
 /// The original type/item combination is replaced with the parent information and the actual item
 /// part is moved into a path fragment to become an anchor. That is, because an item with parent
 /// doesn't have its own page but is a part of the parents page.
-fn generate_crate_mapping(data: CrateData) -> BTreeMap<String, String> {
+///
+/// Items of different namespaces can share the same path, like a function and module of the same
+/// name. The last one is kept as main link and all others are collected as shadowed links.
+///
+/// The index is untrusted input, so a parent index that is out of bounds fails with
+/// [`Error::InvalidParentIndex`], or skips the item with a warning in lenient mode.
+fn generate_crate_mapping(
+    name: &str,
+    data: CrateData,
+    mut warnings: Option<&mut Vec<IndexWarning>>,
+) -> Result<CrateMapping> {
     let paths = data.paths;
+    let mut mapping = CrateMapping::default();
 
-    data.items
-        .into_iter()
-        .map(|item| {
-            let full_path = if let Some(idx) = item.parent_idx {
-                format!("{}::{}::{}", item.path, paths[idx].1, item.name)
-            } else {
-                format!("{}::{}", item.path, item.name)
-            };
+    for item in data.items {
+        let parent = match item.parent_idx {
+            Some(idx) => match (paths.get(idx), warnings.as_deref_mut()) {
+                (Some(parent), _) => Some(parent),
+                (None, Some(warnings)) => {
+                    warnings.push(IndexWarning::InvalidParent {
+                        name: name.to_owned(),
+                        item: format!("{}::{}", item.path, item.name),
+                        parent: idx,
+                    });
+                    continue;
+                }
+                (None, None) => {
+                    return Err(Error::InvalidParentIndex {
+                        name: name.to_owned(),
+                        item: format!("{}::{}", item.path, item.name),
+                        parent: idx,
+                    });
+                }
+            },
+            None => None,
+        };
 
-            let url = if let Some(parent) = item.parent_idx.map(|i| &paths[i]) {
+        let (full_path, url) = if let Some(parent) = parent {
+            (
+                format!("{}::{}::{}", item.path, parent.1, item.name),
                 format!(
                     "{}/{}.{}.html#{}.{}",
                     item.path.replace("::", "/"),
@@ -416,19 +956,32 @@ fn generate_crate_mapping(data: CrateData) -> BTreeMap<String, String> {
                     parent.1,
                     item.ty.as_str(),
                     item.name
-                )
-            } else {
+                ),
+            )
+        } else {
+            (
+                format!("{}::{}", item.path, item.name),
                 format!(
                     "{}/{}.{}.html",
                     item.path.replace("::", "/"),
                     item.ty.as_str(),
                     item.name
-                )
-            };
+                ),
+            )
+        };
 
-            (full_path, url)
-        })
-        .collect()
+        match mapping.links.insert(full_path.clone(), url) {
+            Some(previous) if previous != mapping.links[&full_path] => {
+                let shadowed = mapping.shadowed.entry(full_path).or_default();
+                if !shadowed.contains(&previous) {
+                    shadowed.push(previous);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(mapping)
 }
 
 fn t<'de, D>(deserializer: D) -> Result<Vec<ItemType>, D::Error>
@@ -478,6 +1031,53 @@ impl<'de> Visitor<'de> for VecItemTypeVisitor {
     }
 }
 
+fn t_lenient<'de, D>(deserializer: D) -> Result<Vec<Lenient<ItemType>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VecLenientItemTypeVisitor)
+}
+
+struct VecLenientItemTypeVisitor;
+
+impl<'de> Visitor<'de> for VecLenientItemTypeVisitor {
+    type Value = Vec<Lenient<ItemType>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        VecItemTypeVisitor.expecting(formatter)
+    }
+
+    fn visit_str<E>(self, v: &str) -> std::result::Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.bytes()
+            .map(|ascii| {
+                Lenient(
+                    ascii
+                        .is_ascii_uppercase()
+                        .then(|| ItemType::from_raw(ascii - b'A'))
+                        .flatten()
+                        .ok_or_else(|| format!("invalid ASCII character `{}`", ascii as char)),
+                )
+            })
+            .collect())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut list = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+        while let Some(element) = seq.next_element()? {
+            list.push(element);
+        }
+
+        Ok(list)
+    }
+}
+
 fn q<'de, D>(deserializer: D) -> Result<BTreeMap<usize, String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -553,10 +1153,10 @@ mod tests {
             let input = fs::read_to_string(path).unwrap();
             let data = Version::detect(&input).and_then(|v| match v {
                 #[cfg(feature = "index-v1")]
-                Version::V1 => Some(v1::load_raw(&input).unwrap()),
+                Version::V1 => Some(v1::load_raw(&input, None).unwrap()),
                 #[cfg(feature = "index-v2")]
-                Version::V2 => Some(v2::load_raw(&input).unwrap()),
-                Version::V3 => Some(load_raw(&input).unwrap()),
+                Version::V2 => Some(v2::load_raw(&input, None).unwrap()),
+                Version::V3 => Some(load_raw(&input, None).unwrap()),
             });
             insta::assert_yaml_snapshot!(data);
         });
@@ -570,10 +1170,10 @@ mod tests {
             let data = Version::detect(&input)
                 .and_then(|v| match v {
                     #[cfg(feature = "index-v1")]
-                    Version::V1 => Some(v1::load_raw(&input).unwrap()),
+                    Version::V1 => Some(v1::load_raw(&input, None).unwrap()),
                     #[cfg(feature = "index-v2")]
-                    Version::V2 => Some(v2::load_raw(&input).unwrap()),
-                    Version::V3 => Some(load_raw(&input).unwrap()),
+                    Version::V2 => Some(v2::load_raw(&input, None).unwrap()),
+                    Version::V3 => Some(load_raw(&input, None).unwrap()),
                 })
                 .map(transform);
             insta::assert_yaml_snapshot!(data);
@@ -588,17 +1188,243 @@ mod tests {
             let data = Version::detect(&input)
                 .and_then(|v| match v {
                     #[cfg(feature = "index-v1")]
-                    Version::V1 => Some(v1::load_raw(&input).unwrap()),
+                    Version::V1 => Some(v1::load_raw(&input, None).unwrap()),
                     #[cfg(feature = "index-v2")]
-                    Version::V2 => Some(v2::load_raw(&input).unwrap()),
-                    Version::V3 => Some(load_raw(&input).unwrap()),
+                    Version::V2 => Some(v2::load_raw(&input, None).unwrap()),
+                    Version::V3 => Some(load_raw(&input, None).unwrap()),
                 })
                 .map(transform)
-                .map(generate_mapping);
+                .map(|data| {
+                    generate_mapping(data, None)
+                        .unwrap()
+                        .into_iter()
+                        .map(|(name, mapping)| {
+                            let mapping = serde_json::json!({
+                                "links": mapping.links,
+                                "shadowed": mapping.shadowed,
+                            });
+                            (name, mapping)
+                        })
+                        .collect::<BTreeMap<_, _>>()
+                });
             insta::assert_yaml_snapshot!(data);
         });
     }
 
+    #[test]
+    fn test_load_with() {
+        struct CustomParser;
+
+        impl IndexParser for CustomParser {
+            fn detect(&self, index: &str) -> bool {
+                index.starts_with("custom:")
+            }
+
+            fn parse(&self, index: &str) -> Result<RawIndexData> {
+                let name = index.trim_start_matches("custom:");
+                Ok(RawIndexData {
+                    crates: [(
+                        name.to_owned(),
+                        RawCrateData {
+                            t: vec![ItemType::Struct],
+                            n: vec!["Error".to_owned()],
+                            q: [(0, name.to_owned())].into(),
+                            d: vec![String::new()],
+                            i: vec![0],
+                            ..RawCrateData::default()
+                        },
+                    )]
+                    .into(),
+                })
+            }
+        }
+
+        let (format, mappings) = load_with(
+            "custom:anyhow",
+            Some(&CustomParser),
+            None,
+            &ParseLimits::new(),
+        )
+        .unwrap();
+        assert_eq!(IndexFormat::Custom, format);
+        assert_eq!(
+            Some("anyhow/struct.Error.html"),
+            mappings["anyhow"]
+                .links
+                .get("anyhow::Error")
+                .map(String::as_str)
+        );
+
+        let err = load("custom:anyhow").unwrap_err();
+        assert!(matches!(err, Error::UnsupportedIndexVersion));
+    }
+
+    #[test]
+    fn test_load_lenient() {
+        let v3 = fs::read_to_string("src/index/fixtures/anyhow-1.0.72.js")
+            .unwrap()
+            .replacen("{\\\n", "{\\\n\"broken\":{\"doc\":1},\\\n", 1);
+        #[cfg(feature = "index-v1")]
+        let v1 = fs::read_to_string("src/index/fixtures/anyhow-1.0.0.js")
+            .unwrap()
+            .replacen(
+                "\ninitSearch",
+                "\nsearchIndex[\"broken\"]={\"doc\":1};\ninitSearch",
+                1,
+            );
+
+        #[cfg(feature = "index-v1")]
+        let inputs = [v3, v1];
+        #[cfg(not(feature = "index-v1"))]
+        let inputs = [v3];
+
+        for input in inputs {
+            assert!(load(&input).is_err());
+
+            let mut warnings = Vec::new();
+            let (_, mappings) =
+                load_with(&input, None, Some(&mut warnings), &ParseLimits::new()).unwrap();
+
+            assert!(mappings.contains_key("anyhow"));
+            assert!(!mappings.contains_key("broken"));
+            assert!(matches!(
+                warnings.as_slice(),
+                [IndexWarning::InvalidCrate { name, .. }] if name == "broken"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_crates_lenient() {
+        let v3 = r#"{"demo":{"doc":"","t":"DaFF","n":["Error","broken","bail",1],"q":["demo","demo::inner"],"d":["","","",""],"i":[0,0,0,0],"p":[]}}"#;
+        assert!(parse_crates::<RawCrateData>(v3, None).is_err());
+
+        let mut warnings = Vec::new();
+        let raw = parse_crates::<RawCrateData>(v3, Some(&mut warnings)).unwrap();
+        let data = &raw.crates["demo"];
+        assert_eq!(vec!["Error", "bail"], data.n);
+        assert_eq!(
+            BTreeMap::from([(0, "demo".to_owned()), (1, "demo::inner".to_owned())]),
+            data.q
+        );
+        assert!(matches!(
+            warnings.as_slice(),
+            [
+                IndexWarning::InvalidItem { position: 1, .. },
+                IndexWarning::InvalidItem { position: 3, .. },
+            ]
+        ));
+
+        #[cfg(feature = "index-v2")]
+        {
+            let v2 = r#"{"demo":{"doc":"","i":[[3,"Error","demo","",null,null],["x","broken","","",null,null],[5,"bail","","",null,null]],"p":[]}}"#;
+            assert!(parse_crates::<v2::RawCrate>(v2, None).is_err());
+
+            let mut warnings = Vec::new();
+            let raw = parse_crates::<v2::RawCrate>(v2, Some(&mut warnings)).unwrap();
+            assert_eq!(vec!["Error", "bail"], raw.crates["demo"].n);
+            assert!(matches!(
+                warnings.as_slice(),
+                [IndexWarning::InvalidItem { name, position: 1, .. }] if name == "demo"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_mismatched_lengths() {
+        let raw = || RawIndexData {
+            crates: HashMap::from([(
+                "anyhow".to_owned(),
+                RawCrateData {
+                    t: vec![ItemType::Struct, ItemType::Function],
+                    n: vec!["Error".to_owned(), "format_err".to_owned()],
+                    q: BTreeMap::from([(0, "anyhow".to_owned())]),
+                    d: vec![String::new()],
+                    i: vec![0, 0],
+                    ..RawCrateData::default()
+                },
+            )]),
+        };
+
+        let err = check_lengths(raw(), None).unwrap_err();
+        assert!(matches!(err, Error::MismatchedItemData { ref name } if name == "anyhow"));
+
+        let mut warnings = Vec::new();
+        let data = check_lengths(raw(), Some(&mut warnings)).unwrap();
+        assert_eq!(vec!["Error"], data.crates["anyhow"].n);
+        assert_eq!(1, data.crates["anyhow"].t.len());
+        assert_eq!(
+            vec![IndexWarning::InvalidItem {
+                name: "anyhow".to_owned(),
+                position: 1,
+                reason: "the item data is incomplete".to_owned(),
+            }],
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_invalid_parent() {
+        let data = || CrateData {
+            doc: String::new(),
+            items: vec![
+                IndexItem {
+                    ty: ItemType::Struct,
+                    name: "Error".to_owned(),
+                    path: "anyhow".to_owned(),
+                    desc: String::new(),
+                    parent_idx: None,
+                },
+                IndexItem {
+                    ty: ItemType::Method,
+                    name: "msg".to_owned(),
+                    path: "anyhow".to_owned(),
+                    desc: String::new(),
+                    parent_idx: Some(1),
+                },
+            ],
+            paths: vec![(ItemType::Struct, "Error".to_owned())],
+        };
+
+        let err = generate_crate_mapping("anyhow", data(), None).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidParentIndex { ref item, parent: 1, .. } if item == "anyhow::msg"
+        ));
+
+        let mut warnings = Vec::new();
+        let mapping = generate_crate_mapping("anyhow", data(), Some(&mut warnings)).unwrap();
+        assert_eq!(
+            vec!["anyhow::Error"],
+            mapping.links.keys().map(String::as_str).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![IndexWarning::InvalidParent {
+                name: "anyhow".to_owned(),
+                item: "anyhow::msg".to_owned(),
+                parent: 1,
+            }],
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_item_type_from_link() {
+        assert_eq!(
+            Some(ItemType::Struct),
+            ItemType::from_link("anyhow/struct.Error.html")
+        );
+        assert_eq!(
+            Some(ItemType::Method),
+            ItemType::from_link("anyhow/struct.Error.html#method.context")
+        );
+        assert_eq!(
+            Some(ItemType::Module),
+            ItemType::from_link("tokio/mod.sync.html")
+        );
+        assert_eq!(None, ItemType::from_link("anyhow/index.html"));
+    }
+
     #[test]
     fn test_t() {
         #[derive(Debug, PartialEq, Deserialize)]
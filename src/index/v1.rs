@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use winnow::{
     ascii::dec_uint,
     combinator::{
-        cut_err, delimited, fail, fold_repeat, peek, preceded, separated, separated_pair, success,
+        cut_err, delimited, empty, fail, peek, preceded, repeat, separated, separated_pair,
         terminated,
     },
     dispatch,
@@ -15,10 +15,16 @@ use winnow::{
     PResult, Parser, Stateful,
 };
 
-use super::{v2::RawCrate, RawIndexData};
+use super::{
+    v2::{LenientRawCrate, RawCrate},
+    IndexWarning, LenientCrateData, RawCrateData, RawIndexData,
+};
 use crate::error::IndexV1Error as Error;
 
-pub(super) fn load_raw(index: &str) -> Result<RawIndexData, Error> {
+pub(super) fn load_raw(
+    index: &str,
+    mut warnings: Option<&mut Vec<IndexWarning>>,
+) -> Result<RawIndexData, Error> {
     let r = {
         let r = index
             .lines()
@@ -34,29 +40,53 @@ pub(super) fn load_raw(index: &str) -> Result<RawIndexData, Error> {
             .and_then(|line| line.split_once("\"]="))
     });
 
-    let crates = entries
-        .map(|(name, index)| {
-            let json = match json.parse(Stateful {
-                input: index,
-                state: r.as_slice(),
-            }) {
-                Ok(json) => json,
-                Err(err) => {
-                    return Err(Error::InvalidIndexJavaScript(format!("Parse error: {err}")));
-                }
-            };
-            let json = serde_json::Value::try_from(json).map_err(Error::InvalidIndexJavaScript)?;
-            let data = serde_json::from_value::<RawCrate>(json)
-                .map_err(Error::InvalidIndexJson)?
-                .into();
-
-            Ok((name.to_owned(), data))
-        })
-        .collect::<Result<_, _>>()?;
+    let mut crates = HashMap::new();
+    for (name, index) in entries {
+        match (
+            parse_crate(name, index, &r, warnings.as_deref_mut()),
+            warnings.as_deref_mut(),
+        ) {
+            (Ok(data), _) => {
+                crates.insert(name.to_owned(), data);
+            }
+            (Err(e), Some(warnings)) => warnings.push(IndexWarning::InvalidCrate {
+                name: name.to_owned(),
+                reason: e.to_string(),
+            }),
+            (Err(e), None) => return Err(e),
+        }
+    }
 
     Ok(RawIndexData { crates })
 }
 
+/// Parse the data of a single crate, with the list of references to resolve. In lenient mode,
+/// malformed items are skipped and reported as warnings.
+fn parse_crate(
+    name: &str,
+    index: &str,
+    r: &[String],
+    warnings: Option<&mut Vec<IndexWarning>>,
+) -> Result<RawCrateData, Error> {
+    let json = match json.parse(Stateful {
+        input: index,
+        state: r,
+    }) {
+        Ok(json) => json,
+        Err(err) => {
+            return Err(Error::InvalidIndexJavaScript(format!("Parse error: {err}")));
+        }
+    };
+    let json = serde_json::Value::try_from(json).map_err(Error::InvalidIndexJavaScript)?;
+
+    match warnings {
+        Some(warnings) => serde_json::from_value::<LenientRawCrate>(json)
+            .map(|raw| LenientCrateData::from(raw).into_raw(name, warnings)),
+        None => serde_json::from_value::<RawCrate>(json).map(Into::into),
+    }
+    .map_err(Error::InvalidIndexJson)
+}
+
 #[derive(Clone, Debug)]
 enum JsJson {
     Null,
@@ -130,7 +160,7 @@ fn string(input: &mut Stream<'_>) -> PResult<String> {
     preceded(
         '\"',
         cut_err(terminated(
-            fold_repeat(0.., character, String::new, |mut string, c| {
+            repeat(0.., character).fold(String::new, |mut string, c| {
                 string.push(c);
                 string
             }),
@@ -146,14 +176,14 @@ fn character(input: &mut Stream<'_>) -> PResult<char> {
     if c == '\\' {
         dispatch!(
             any;
-            '"' => success('"'),
-            '\\' => success('\\'),
-            '/'  => success('/'),
-            'b' => success('\x08'),
-            'f' => success('\x0C'),
-            'n' => success('\n'),
-            'r' => success('\r'),
-            't' => success('\t'),
+            '"' => empty.value('"'),
+            '\\' => empty.value('\\'),
+            '/'  => empty.value('/'),
+            'b' => empty.value('\x08'),
+            'f' => empty.value('\x0C'),
+            'n' => empty.value('\n'),
+            'r' => empty.value('\r'),
+            't' => empty.value('\t'),
             'u' => unicode_escape,
             _ => fail,
         )
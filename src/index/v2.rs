@@ -1,16 +1,13 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use serde::Deserialize;
 use serde_tuple::Deserialize_tuple;
 
-use super::{ItemType, RawCrateData, RawIndexData};
-use crate::error::{Error, Result};
-
-#[derive(Deserialize)]
-struct RawIndex {
-    #[serde(flatten)]
-    crates: HashMap<String, RawCrate>,
-}
+use super::{
+    CrateFormat, IndexWarning, ItemType, Lenient, LenientCrateData, RawCrateData, RawIndexData,
+    RawItem,
+};
+use crate::error::Result;
 
 #[derive(Deserialize)]
 pub(super) struct RawCrate {
@@ -53,6 +50,50 @@ impl From<RawCrate> for RawCrateData {
     }
 }
 
+impl CrateFormat for RawCrate {
+    type Lenient = LenientRawCrate;
+}
+
+/// Crate data in lenient mode, with each entry deserialized on its own.
+#[derive(Deserialize)]
+pub(super) struct LenientRawCrate {
+    doc: String,
+    i: Vec<Lenient<Entry>>,
+    p: Vec<(ItemType, String)>,
+}
+
+impl From<LenientRawCrate> for LenientCrateData {
+    fn from(raw: LenientRawCrate) -> Self {
+        let mut q = BTreeMap::new();
+        let items = raw
+            .i
+            .into_iter()
+            .enumerate()
+            .map(|(position, Lenient(entry))| {
+                entry.map(|mut entry| {
+                    if let Some(path) = entry.q.take().filter(|path| !path.is_empty()) {
+                        q.insert(position, path);
+                    }
+
+                    RawItem {
+                        t: entry.t,
+                        n: entry.n.unwrap_or_default(),
+                        d: entry.d.unwrap_or_default(),
+                        i: entry.i.unwrap_or_default(),
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            doc: raw.doc,
+            items,
+            q,
+            p: raw.p,
+        }
+    }
+}
+
 #[derive(Deserialize_tuple)]
 struct Entry {
     t: ItemType,
@@ -64,7 +105,10 @@ struct Entry {
     f: Option<Vec<serde_json::Value>>,
 }
 
-pub(super) fn load_raw(index: &str) -> Result<RawIndexData> {
+pub(super) fn load_raw(
+    index: &str,
+    warnings: Option<&mut Vec<IndexWarning>>,
+) -> Result<RawIndexData> {
     let json = {
         let mut json = index
             .lines()
@@ -88,13 +132,5 @@ pub(super) fn load_raw(index: &str) -> Result<RawIndexData> {
             .replace(r"\\", r"\")
     };
 
-    let raw = serde_json::from_str::<RawIndex>(&json).map_err(Error::from)?;
-
-    Ok(RawIndexData {
-        crates: raw
-            .crates
-            .into_iter()
-            .map(|(name, raw)| (name, raw.into()))
-            .collect(),
-    })
+    super::parse_crates::<RawCrate>(&json, warnings)
 }
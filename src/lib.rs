@@ -16,9 +16,51 @@
 //! The features listed are **enabled by default**.
 //!
 //! - `index-v2` enables support to parse the slightly outdated index format. This is needed if
-//! parsing of older crates that haven't be update in a while is required.
+//!   parsing of older crates that haven't be update in a while is required.
 //! - `index-v1` enables support for the even older index format. Nowadays it's rarely found and
-//! this is only needed to parse very old crates that haven't been updated in a long while.
+//!   this is only needed to parse very old crates that haven't been updated in a long while.
+//!
+//! Another feature, that is **enabled by default** as well:
+//!
+//! - `serde` implements [`Serialize`](serde::Serialize) and [`Deserialize`](serde::Deserialize)
+//!   for the public types like [`Index`] and [`Version`], for example to cache indexes. Minimal
+//!   builds, like WebAssembly bundles, can disable it to save on code size, as it drops these
+//!   implementations as well as the `serde` support of [`semver`]. The `serde` crate itself is
+//!   still needed, because the index parser is built on it.
+//!
+//! The following features are **optional**:
+//!
+//! - `html` parses the docs pages with a real HTML parser to locate the search index, which is
+//!   more robust against changes in the generated HTML than the default string extraction.
+//! - `mdbook` enables the [`mdbook`] module, which implements the mdBook preprocessor protocol to
+//!   turn references like ``[`anyhow::Result`]`` in a book into links.
+//! - `url` adds variants of the link generating functions like [`Index::find_url`], that return
+//!   a [`url::Url`] instead of a plain string.
+//! - `checksum` allows to verify the downloaded index content against a known [`Checksum`] with
+//!   [`SearchIndex::checksum`].
+//! - `reqwest` adds the [`search`] function, that runs a whole search with a built-in
+//!   [`reqwest`] client.
+//! - `blocking` adds the [`search_blocking`] function, that runs a whole search with a blocking
+//!   [`ureq`] client, without the need for an async runtime.
+//! - `http` allows to convert a [`Request`] into an `http::Request<()>` and an
+//!   `http::Response<String>` into a [`Response`], for clients like `hyper` that are built on the
+//!   types of the `http` crate.
+//! - `cli` builds the `docsearch` command line tool, that prints the link for an item path like
+//!   `docsearch anyhow::Result`. Retrieved indexes are cached, and `docsearch cache` manages
+//!   the cache. It also builds the `cargo docsearch` subcommand, that links to the exact versions
+//!   of the current project's dependencies, as resolved in its `Cargo.lock`. Install both with
+//!   `cargo install docsearch --features cli`.
+//! - `testing` enables the [`testing`] module, with helpers to run searches against canned docs
+//!   pages and indexes in tests, without network access.
+//! - `tower` adds the [`TowerFetch`] adapter, that sends the requests of a search through any
+//!   `tower::Service`.
+//!
+//! # WebAssembly
+//!
+//! The crate doesn't do any I/O by itself, so it compiles to `wasm32-unknown-unknown` and can run
+//! in browser extensions or Cloudflare Workers. The requests of a search can be sent with the
+//! `fetch` API of the host, for example through [`search_with`], as shown in the `wasm` example of
+//! the repository. The `reqwest`, `blocking` and `cli` features have no effect on this target.
 #![forbid(unsafe_code)]
 #![deny(
     rust_2018_idioms,
@@ -29,53 +71,868 @@
 )]
 #![allow(clippy::missing_errors_doc)]
 
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{borrow::Cow, collections::BTreeMap, io::Write, ops::Bound, path::Path};
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::error::{Error, Result};
-pub use crate::{simple_path::SimplePath, version::Version};
+#[cfg(feature = "checksum")]
+pub use crate::checksum::Checksum;
+#[cfg(feature = "index-v1")]
+pub use crate::index::IndexV1Parser;
+#[cfg(feature = "index-v2")]
+pub use crate::index::IndexV2Parser;
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+pub use crate::search::search;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub use crate::search::search_blocking;
+#[cfg(feature = "tower")]
+pub use crate::service::TowerFetch;
+pub use crate::{
+    bisect::{bisect_introduction, Bisect, BisectStep},
+    builds::{
+        list_versions, start_build_fallback, BuildCheck, BuildFallback, BuildStep, DocsStatus,
+        Release, ReleaseBuildStep, ReleaseBuilds, ReleaseStatus, VersionList,
+    },
+    cache::CacheKey,
+    description::{sanitize_description, DescriptionFormat},
+    detect::{detect_docs, list_crates, CrateList, DocsRoot},
+    diff::{IndexDiff, MovedItem},
+    docs_url::DocsUrl,
+    export::DirectoryFormat,
+    fetch::{AsyncHttpFetch, FetchFuture, HttpFetch},
+    host::{DocHost, HostProfile, StdChannel},
+    index::{
+        IndexFormat, IndexParser, IndexV3Parser, IndexWarning, ItemType, RawCrateData, RawIndexData,
+    },
+    limits::{Limit, ParseLimits},
+    link::{Link, LinkOptions, LinkStyle, VersionStyle},
+    locator::{DocsRsLocator, IndexLocation, IndexLocator, LocatorContext},
+    markdown::{markdown_references, rewrite_markdown},
+    metadata::{suggest_crates, CrateMetadata, CrateSuggestions},
+    methods::{MethodIndex, MethodLink, MethodSource},
+    progress::Progress,
+    qualified::QualifiedPath,
+    query::Query,
+    refresh::{refresh, Refresh, RefreshStep},
+    request::{Method, Request, Response},
+    reverse::ReverseIndex,
+    search::{search_with, Search, Step},
+    simple_path::{Disambiguator, SimplePath},
+    tree::{ModuleTree, TreeItem},
+    version::Version,
+};
+use crate::{
+    error::{Error, Result},
+    progress::Observer,
+};
 
+mod bisect;
+mod builds;
+mod cache;
+#[cfg(feature = "checksum")]
+mod checksum;
 mod crates;
+mod description;
+mod detect;
+mod diff;
+mod docs_url;
 pub mod error;
+mod export;
+mod fetch;
+mod host;
 mod index;
+mod limits;
+mod link;
+mod local;
+mod locator;
+mod markdown;
+#[cfg(feature = "mdbook")]
+pub mod mdbook;
+mod metadata;
+mod methods;
+mod progress;
+mod qualified;
+mod query;
+pub mod raw;
+mod refresh;
+mod registry;
+mod request;
+mod reverse;
+mod search;
+#[cfg(feature = "tower")]
+mod service;
 mod simple_path;
+#[cfg(test)]
+mod test_util;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod tree;
 mod version;
+pub mod workspace;
 
 /// List of crates in the stdlib index.
 pub(crate) const STD_CRATES: &[&str] = &["alloc", "core", "proc_macro", "std", "test"];
 
+/// Whether the crate is part of the stdlib, like `std` or `core`. These crates are documented on
+/// `doc.rust-lang.org` instead of docs.rs.
+///
+/// ```rust
+/// assert!(docsearch::is_std_crate("alloc"));
+/// assert!(!docsearch::is_std_crate("tokio-util"));
+/// ```
+#[must_use]
+pub fn is_std_crate(name: &str) -> bool {
+    STD_CRATES.contains(&name)
+}
+
 /// Parsed crate index that contains the mappings from [`SimplePath`]s to their URL for direct
 /// linking.
-#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Index {
     /// Name of the crate.
     pub name: String,
-    /// Version of the crate.
+    /// Version of the crate. This is the concrete version if the requested one could be resolved.
     pub version: Version,
+    /// The version that was originally requested for the search, like a [`Version::Req`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub requested: Version,
     /// Mapping from simple paths to URL paths.
     pub mapping: BTreeMap<String, String>,
+    /// URL paths of items that share their path with another item in [`Self::mapping`], like the
+    /// macro `vec!` and the module `std::vec`. These are considered when looking up paths that
+    /// are restricted to a specific kind of item, like `std::vec!`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub shadowed: BTreeMap<String, Vec<String>>,
     /// Whether this index is for the standard library.
     pub std: bool,
+    /// Hosts that the index was retrieved from, which links are generated for.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub host: DocHost,
+    /// Custom documentation root that links are generated against, like a local `target/doc`
+    /// directory or the one given to [`start_search_at`]. If not set, links point to the
+    /// [`Self::host`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub root: Option<String>,
+    /// Version of `rustdoc` that generated the docs, like `1.73.0-nightly (ad963232d 2023-07-14)`,
+    /// if it could be detected.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rustdoc_version: Option<String>,
+    /// Format of the search index that this index was parsed from. This is [`None`] for indexes
+    /// that were serialized before the format was recorded.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub format: Option<IndexFormat>,
+    /// Resource suffix of the search index file, which changes whenever the docs are rebuilt. It
+    /// can be compared with the suffix of a newer docs page to detect changes, or passed to
+    /// [`SearchPage::with_resource_suffix`] to skip the page download.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resource_suffix: Option<String>,
+    /// Additional crate information from crates.io, if it was attached with
+    /// [`Self::attach_metadata`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metadata: Option<CrateMetadata>,
 }
 
 impl Index {
+    /// Create an index directly from a mapping of simple paths to URL paths, like
+    /// `anyhow::Error` to `anyhow/struct.Error.html`, instead of parsing a search index. This is
+    /// useful for tests, or to feed data from other sources than `rustdoc` into tools that work
+    /// with indexes.
+    ///
+    /// Indexes for the crates of the stdlib link to its docs. All other fields keep their
+    /// defaults and can be adjusted afterwards.
+    ///
+    /// ```rust
+    /// use docsearch::{Index, Version};
+    ///
+    /// let index = Index::from_mapping(
+    ///     "anyhow",
+    ///     "1.0.72".parse().unwrap(),
+    ///     [("anyhow::Error".to_owned(), "anyhow/struct.Error.html".to_owned())].into(),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     Some("https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html".to_owned()),
+    ///     index.find_link(&"anyhow::Error".parse().unwrap())
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_mapping(
+        name: impl Into<String>,
+        version: Version,
+        mapping: BTreeMap<String, String>,
+    ) -> Self {
+        let name = name.into();
+
+        Self {
+            std: STD_CRATES.contains(&name.as_str()),
+            name,
+            requested: version.clone(),
+            version,
+            mapping,
+            shadowed: BTreeMap::new(),
+            host: DocHost::default(),
+            root: None,
+            rustdoc_version: None,
+            format: None,
+            resource_suffix: None,
+            metadata: None,
+        }
+    }
+
+    /// Find the link to the docs of the given path, if it exists in this index.
     #[must_use]
     pub fn find_link(&self, path: &SimplePath) -> Option<String> {
+        self.find_link_with(path, &LinkOptions::default())
+    }
+
+    /// Find the links to the docs of all items with the given path, together with their item
+    /// type. Unlike [`Self::find_link`], which picks a single item, this returns every item that
+    /// shares the path, like the function `syn::parse` and the module of the same name, so
+    /// callers can choose one themselves. The [disambiguator](SimplePath::disambiguator) of the
+    /// path is respected.
+    ///
+    /// ```no_run
+    /// # let index: docsearch::Index = unimplemented!();
+    /// let path = "syn::parse".parse().unwrap();
+    ///
+    /// for (kind, link) in index.find_links_all(&path) {
+    ///     println!("{kind:?}: {link}");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn find_links_all(&self, path: &SimplePath) -> Vec<(ItemType, String)> {
+        self.find_links_all_with(path, &LinkOptions::default())
+    }
+
+    /// Find the links to the docs of all items with the given path like [`Self::find_links_all`],
+    /// but customize the generated links with the given options.
+    #[must_use]
+    pub fn find_links_all_with(
+        &self,
+        path: &SimplePath,
+        options: &LinkOptions,
+    ) -> Vec<(ItemType, String)> {
+        let formatter = self.link_formatter(options);
+        let render = |link: &str| options.render(path.as_ref(), &formatter.format(link));
+
+        if path.is_crate_only() {
+            return vec![(
+                ItemType::Module,
+                render(&self.crate_link_path(path.crate_name(), options)),
+            )];
+        }
+
+        let key = path.index_path();
+
+        self.mapping
+            .get(key.as_ref())
+            .into_iter()
+            .chain(self.shadowed.get(key.as_ref()).into_iter().flatten())
+            .filter_map(|link| {
+                let kind = ItemType::from_link(link)?;
+                let matches = path
+                    .disambiguator()
+                    .map_or(true, |disambiguator| disambiguator.matches(kind));
+
+                matches.then(|| (kind, render(link)))
+            })
+            .collect()
+    }
+
+    /// Find the candidates for a method like `anyhow::Error::context`, for both the inherent
+    /// method of the type and the methods of the same name in traits. Each link is labeled with
+    /// its [source](MethodSource), so callers can tell them apart instead of getting whichever
+    /// item the index happens to map the path to. The method of the type itself comes first.
+    ///
+    /// This is a heuristic, as the search index doesn't record which traits a type implements.
+    /// Only types that have a method of that name are considered, but then all traits of the
+    /// crate with a method of the same name are included, even if the type doesn't implement
+    /// them.
+    ///
+    /// For looking up many methods, see [`Self::method_index`].
+    ///
+    /// ```no_run
+    /// use docsearch::MethodSource;
+    ///
+    /// # let index: docsearch::Index = unimplemented!();
+    /// let path = "anyhow::Error::context".parse().unwrap();
+    ///
+    /// for method in index.find_method_candidates(&path) {
+    ///     match method.source {
+    ///         MethodSource::Inherent => println!("inherent: {}", method.url),
+    ///         MethodSource::Trait(name) => println!("maybe from {name}: {}", method.url),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn find_method_candidates(&self, path: &SimplePath) -> Vec<MethodLink> {
+        self.method_index().find_candidates(path)
+    }
+
+    /// Find the candidates for a method like [`Self::find_method_candidates`], but customize the
+    /// generated links with the given options. The [`LinkOptions::style`] doesn't apply, as the
+    /// links are plain URLs.
+    #[must_use]
+    pub fn find_method_candidates_with(
+        &self,
+        path: &SimplePath,
+        options: &LinkOptions,
+    ) -> Vec<MethodLink> {
+        self.method_index().find_candidates_with(path, options)
+    }
+
+    /// Create a [`MethodIndex`] to look up the candidates of many methods.
+    #[must_use]
+    pub fn method_index(&self) -> MethodIndex<'_> {
+        MethodIndex::new(self)
+    }
+
+    /// Find the links to the docs of many paths at once. The result contains the link for each
+    /// path at the same position, or [`None`] if the path doesn't exist in this index.
+    ///
+    /// The parts of the links that don't depend on the item, like the host and version, are only
+    /// resolved once for the whole batch.
+    ///
+    /// ```no_run
+    /// use docsearch::SimplePath;
+    ///
+    /// # let index: docsearch::Index = unimplemented!();
+    /// let paths = ["anyhow::Result", "anyhow::Error", "anyhow::Missing"]
+    ///     .iter()
+    ///     .map(|path| path.parse::<SimplePath>().unwrap())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// for (path, link) in paths.iter().zip(index.find_links(&paths)) {
+    ///     println!("{path}: {}", link.as_deref().unwrap_or("not found"));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn find_links(&self, paths: &[SimplePath]) -> Vec<Option<String>> {
+        self.find_links_with(paths, &LinkOptions::default())
+    }
+
+    /// Find the links to the docs of many paths at once like [`Self::find_links`], but customize
+    /// the generated links with the given options.
+    #[must_use]
+    pub fn find_links_with(
+        &self,
+        paths: &[SimplePath],
+        options: &LinkOptions,
+    ) -> Vec<Option<String>> {
+        let formatter = self.link_formatter(options);
+        paths.iter().map(|path| formatter.find(path)).collect()
+    }
+
+    /// Find the link to the docs of an item given in the fully-qualified syntax, like
+    /// `<anyhow::Error as std::error::Error>::source`. The [candidates](QualifiedPath::candidates)
+    /// are tried in order and the first one that exists in this index is returned, together with
+    /// the link.
+    ///
+    /// The trait and type can be part of different crates, so it may be necessary to try the
+    /// indexes of both crates.
+    #[must_use]
+    pub fn find_qualified(&self, path: &QualifiedPath) -> Option<(SimplePath, String)> {
+        self.find_qualified_with(path, &LinkOptions::default())
+    }
+
+    /// Find the link to the docs of an item given in the fully-qualified syntax like
+    /// [`Self::find_qualified`], but customize the generated link with the given options.
+    #[must_use]
+    pub fn find_qualified_with(
+        &self,
+        path: &QualifiedPath,
+        options: &LinkOptions,
+    ) -> Option<(SimplePath, String)> {
+        let formatter = self.link_formatter(options);
+
+        path.candidates().into_iter().find_map(|candidate| {
+            let link = formatter.find(&candidate)?;
+            Some((candidate, link))
+        })
+    }
+
+    /// Find the link to the docs of an item with a prepared [`Query`]. Each of the query's paths
+    /// is tried in order, before falling back to a case-insensitive match (if enabled). The path
+    /// of the found item is returned together with the link.
+    ///
+    /// Only paths into the crate of this index are considered, so indexes of other crates are
+    /// skipped right away. The case-insensitive match compares against all paths of the index.
+    ///
+    /// ```no_run
+    /// use docsearch::Query;
+    ///
+    /// # let indexes: Vec<docsearch::Index> = unimplemented!();
+    /// let query = "anyhow::error".parse::<Query>().unwrap().case_insensitive(true);
+    ///
+    /// for index in &indexes {
+    ///     if let Some((path, link)) = index.find_query(&query) {
+    ///         println!("{path}: {link}");
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn find_query(&self, query: &Query) -> Option<(SimplePath, String)> {
+        self.find_query_with(query, &LinkOptions::default())
+    }
+
+    /// Find the link to the docs of an item with a prepared [`Query`] like [`Self::find_query`],
+    /// but customize the generated link with the given options.
+    #[must_use]
+    pub fn find_query_with(
+        &self,
+        query: &Query,
+        options: &LinkOptions,
+    ) -> Option<(SimplePath, String)> {
+        query::find(self, query, options)
+    }
+
+    /// Find the link to the docs of the given path like [`Self::find_link`], but render it as
+    /// Markdown link with the path as inline code, like
+    /// ``[`anyhow::Result`](https://docs.rs/anyhow/1.0.72/anyhow/type.Result.html)``.
+    #[must_use]
+    pub fn find_markdown_link(&self, path: &SimplePath) -> Option<String> {
+        self.find_link_with(path, &LinkOptions::new().style(LinkStyle::Markdown))
+    }
+
+    /// Find the link to the docs of the given path like [`Self::find_link`], but render it as
+    /// HTML anchor with the path as inline code, like
+    /// `<a href="https://docs.rs/..."><code>anyhow::Result</code></a>`. The URL and path are
+    /// escaped, so the result can be embedded into any HTML document as is.
+    #[must_use]
+    pub fn find_html_link(&self, path: &SimplePath) -> Option<String> {
+        self.find_link_with(path, &LinkOptions::new().style(LinkStyle::Html))
+    }
+
+    /// Create the destination of an intra-doc link to the given path, like `anyhow::Error` for
+    /// ``[`anyhow::Error`]`` or ``[`Error`](anyhow::Error)``. This allows to migrate from hard
+    /// links to intra-doc links.
+    ///
+    /// If the index shows that another item has the same path, the link is disambiguated with
+    /// the item type, like `macro@std::vec` for the `vec!` macro, which shares its path with the
+    /// `std::vec` module.
+    #[must_use]
+    pub fn intra_doc_link(&self, path: &SimplePath) -> Option<String> {
+        markdown::intra_doc_link(self, path)
+    }
+
+    /// Check whether the given path exists in this index, without generating its link. This is
+    /// `true` whenever [`Self::find_link`] would return a link.
+    #[must_use]
+    pub fn contains(&self, path: &SimplePath) -> bool {
+        path.is_crate_only() || self.lookup(path).is_some()
+    }
+
+    /// Check whether any path in this index starts with the given prefix, like `anyhow::Er`. This
+    /// is useful to validate partial input, for example while the user is still typing.
+    ///
+    /// ```no_run
+    /// # let index: docsearch::Index = unimplemented!();
+    /// if index.contains_prefix("anyhow::Er") {
+    ///     println!("keep typing");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn contains_prefix(&self, prefix: &str) -> bool {
+        self.mapping
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .next()
+            .map_or(false, |(path, _)| path.starts_with(prefix))
+    }
+
+    /// Create a new index, that only contains the given module and all its items, like
+    /// `tokio::sync`. All other information, like the version and host, is kept as is, so the
+    /// subset generates the same links as this index.
+    #[must_use]
+    pub fn subset(&self, module: &str) -> Self {
+        Self {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            requested: self.requested.clone(),
+            mapping: self
+                .module_items(module)
+                .map(|(path, link)| (path.clone(), link.clone()))
+                .collect(),
+            shadowed: self
+                .shadowed
+                .iter()
+                .filter(|(path, _)| is_in_module(path, module))
+                .map(|(path, links)| (path.clone(), links.clone()))
+                .collect(),
+            std: self.std,
+            host: self.host.clone(),
+            root: self.root.clone(),
+            rustdoc_version: self.rustdoc_version.clone(),
+            format: self.format,
+            resource_suffix: self.resource_suffix.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Search for items within the given module (including its sub-modules), whose name contains
+    /// the query, ignoring case. The returned paths are sorted.
+    ///
+    /// ```no_run
+    /// # let index: docsearch::Index = unimplemented!();
+    /// for path in index.search_in("tokio::sync", "mutex") {
+    ///     println!("{path}");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn search_in<'a>(&'a self, module: &str, query: &str) -> Vec<&'a str> {
+        let query = query.to_lowercase();
+
+        self.module_items(module)
+            .map(|(path, _)| path.as_str())
+            .filter(|path| {
+                path.rsplit("::")
+                    .next()
+                    .map_or(false, |name| name.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// Iterate over the paths of all items of the given type, like all traits or macros of the
+    /// crate. The paths are returned in sorted order.
+    ///
+    /// ```no_run
+    /// use docsearch::ItemType;
+    ///
+    /// # let index: docsearch::Index = unimplemented!();
+    /// for path in index.iter_kind(ItemType::Macro) {
+    ///     println!("{path}!");
+    /// }
+    /// ```
+    pub fn iter_kind(&self, kind: ItemType) -> impl Iterator<Item = &str> {
+        self.mapping
+            .iter()
+            .filter(move |(_, link)| ItemType::from_link(link) == Some(kind))
+            .map(|(path, _)| path.as_str())
+    }
+
+    /// Iterate over the module itself and all items within it, which share the module's path as
+    /// common prefix.
+    fn module_items<'a: 'b, 'b>(
+        &'a self,
+        module: &'b str,
+    ) -> impl Iterator<Item = (&'a String, &'a String)> + 'b {
+        self.mapping
+            .range::<str, _>((Bound::Included(module), Bound::Unbounded))
+            .take_while(move |(path, _)| path.starts_with(module))
+            .filter(move |(path, _)| is_in_module(path, module))
+    }
+
+    /// Find the simple path of the item, that the given docs URL points to. This is the reverse
+    /// operation of [`Self::find_link`] and allows to normalize links pasted by users.
+    ///
+    /// The URL can be a full docs.rs URL (with any version), just its path or only the part
+    /// relative to the docs root, like `anyhow/struct.Error.html#method.context`. If the fragment
+    /// doesn't point to an item in the index, the path of the page itself is returned.
+    ///
+    /// For resolving many URLs, see [`Self::reverse_index`].
+    #[must_use]
+    pub fn find_path(&self, url: &str) -> Option<SimplePath> {
+        self.reverse_index().find_path(url)
+    }
+
+    /// Create a [`ReverseIndex`] to resolve many URLs.
+    #[must_use]
+    pub fn reverse_index(&self) -> ReverseIndex<'_> {
+        ReverseIndex::new(self)
+    }
+
+    /// Compare this index with another one, usually a newer version of the same crate, and report
+    /// which items were added, removed or moved.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> IndexDiff {
+        diff::diff(self, other)
+    }
+
+    /// Build a tree of all modules of this crate, with the items that they contain.
+    #[must_use]
+    pub fn module_tree(&self) -> ModuleTree {
+        tree::build(self)
+    }
+
+    /// Export this index as JSON in the documented and versioned schema `docsearch/index/v1`.
+    /// Unlike the [`Serialize`](serde::Serialize) implementation, which follows the internal
+    /// structure of this type, the schema stays stable across releases and is meant to be consumed
+    /// by other tools.
+    ///
+    /// The schema consists of a single object with the following fields:
+    ///
+    /// - `schema`: Always the string `docsearch/index/v1`.
+    /// - `name`: Name of the crate.
+    /// - `version`: Resolved version of the crate, or `latest`.
+    /// - `requested`: Originally requested version of the crate.
+    /// - `std`: Whether the index is for the standard library.
+    /// - `crate_url`: Full URL to the crate's root page.
+    /// - `rustdoc_version`: Version of `rustdoc` that generated the docs, or `null`.
+    /// - `items`: List of all items, sorted by path, each an object with the fields:
+    ///   - `path`: Simple path of the item, like `anyhow::Error`.
+    ///   - `kind`: Type of the item as used in rustdoc URLs, like `struct` or `fn`, or `null`.
+    ///   - `url`: Full URL to the item's docs.
+    ///
+    /// New fields may be added in the future, but existing fields are never changed or removed
+    /// without introducing a new schema version.
+    pub fn to_json_schema_v1(&self) -> Result<String> {
+        export::to_json_schema_v1(self)
+    }
+
+    /// Write all items of this index as CSV, with one `path,kind,url` row per item and a header
+    /// row. The kind is the item type as used in rustdoc URLs, like `struct` or `fn`.
+    ///
+    /// ```no_run
+    /// # let index: docsearch::Index = unimplemented!();
+    /// let file = std::fs::File::create("anyhow.csv")?;
+    /// index.write_csv(std::io::BufWriter::new(file))?;
+    /// # Ok::<(), docsearch::error::Error>(())
+    /// ```
+    pub fn write_csv(&self, writer: impl Write) -> Result<()> {
+        export::write_delimited(self, writer, ',')
+    }
+
+    /// Write all items of this index as TSV, in the same layout as [`Self::write_csv`] but
+    /// separated by tabs.
+    pub fn write_tsv(&self, writer: impl Write) -> Result<()> {
+        export::write_delimited(self, writer, '\t')
+    }
+
+    /// Generate a directory of links to all items of this crate, grouped by module and item type,
+    /// like the module pages of rustdoc but for the whole crate on a single page.
+    #[must_use]
+    pub fn link_directory(&self, format: DirectoryFormat) -> String {
+        export::link_directory(self, format)
+    }
+
+    /// URL to the crates.io API for this crate, whose content can be passed to
+    /// [`Self::attach_metadata`]. Returns [`None`] for the stdlib, which isn't published on
+    /// crates.io.
+    #[must_use]
+    pub fn metadata_url(&self) -> Option<String> {
+        (!self.std).then(|| metadata::get_url(&self.name))
+    }
+
+    /// Full description of the request to retrieve the content for [`Self::attach_metadata`].
+    #[must_use]
+    pub fn metadata_request(&self) -> Option<Request> {
+        self.metadata_url().map(Request::get)
+    }
+
+    /// Parse the crates.io API response downloaded from [`Self::metadata_url`] and attach the
+    /// crate information like description, repository and license to this index.
+    pub fn attach_metadata(&mut self, body: &str) -> Result<()> {
+        self.metadata = Some(
+            metadata::parse(&self.version, body)
+                .map_err(|e| e.with_url(&metadata::get_url(&self.name)))?,
+        );
+        Ok(())
+    }
+
+    /// Find the link to the docs of the given path like [`Self::find_link`], but customize the
+    /// generated link with the given options. The link is rendered in the
+    /// [`LinkOptions::style`], with the path as link text.
+    #[must_use]
+    pub fn find_link_with(&self, path: &SimplePath, options: &LinkOptions) -> Option<String> {
+        self.link_formatter(options).find(path)
+    }
+
+    /// Find the link to the docs of the given path like [`Self::find_link`], but return it split
+    /// into its components, so the page or anchor can be changed independently.
+    #[must_use]
+    pub fn find_link_parts(&self, path: &SimplePath) -> Option<Link> {
+        self.find_link_parts_with(path, &LinkOptions::default())
+    }
+
+    /// Find the link to the docs of the given path split into its components like
+    /// [`Self::find_link_parts`], but customize the generated link with the given options. The
+    /// [`LinkOptions::style`] doesn't apply to the components.
+    #[must_use]
+    pub fn find_link_parts_with(&self, path: &SimplePath, options: &LinkOptions) -> Option<Link> {
+        let crate_link;
         let link = if path.is_crate_only() {
-            path.crate_name()
+            crate_link = self.crate_link_path(path.crate_name(), options);
+            &crate_link
         } else {
-            self.mapping.get(path.as_ref())?
+            self.lookup(path)?
+        };
+        let (page, fragment) = match link.split_once('#') {
+            Some((page, fragment)) => (page, Some(fragment)),
+            None => (link, None),
         };
 
-        Some(if self.std {
-            format!("https://doc.rust-lang.org/nightly/{link}")
+        Some(Link::from_url(
+            self.format_link(page, options),
+            &link::encode_path(page),
+            fragment,
+        ))
+    }
+
+    /// Find the link to the docs of the given path like [`Self::find_link`], but return it as
+    /// parsed [`url::Url`]. Links that aren't absolute URLs, like the ones relative to a custom
+    /// root, can't be represented and result in [`None`] as well.
+    #[cfg(feature = "url")]
+    #[must_use]
+    pub fn find_url(&self, path: &SimplePath) -> Option<url::Url> {
+        self.find_url_with(path, &LinkOptions::default())
+    }
+
+    /// Find the link to the docs of the given path as [`url::Url`] like [`Self::find_url`], but
+    /// customize the generated link with the given options.
+    #[cfg(feature = "url")]
+    #[must_use]
+    pub fn find_url_with(&self, path: &SimplePath, options: &LinkOptions) -> Option<url::Url> {
+        self.find_link_parts_with(path, options)?.to_url().ok()
+    }
+
+    /// Whether the index was retrieved for a concrete version. Otherwise, like for
+    /// [`Version::Latest`], the [`Self::version`] it was resolved to can change with new releases
+    /// and cached indexes should be [refreshed](refresh) from time to time.
+    #[must_use]
+    pub fn is_pinned(&self) -> bool {
+        self.requested.is_concrete()
+    }
+
+    /// Get the URL to the root documentation page of this crate, pointing to the same host and
+    /// version as the links of [`Self::find_link`].
+    #[must_use]
+    pub fn crate_url(&self) -> String {
+        self.crate_url_with(&LinkOptions::default())
+    }
+
+    /// Get the URL to the root documentation page of this crate like [`Self::crate_url`], but
+    /// customize the generated URL with the given options.
+    #[must_use]
+    pub fn crate_url_with(&self, options: &LinkOptions) -> String {
+        self.format_crate_link(&self.name, options)
+    }
+
+    /// Find the URL path of the given path in the mapping. If the path is restricted to a kind of
+    /// item, the shadowed links are considered as well.
+    fn lookup(&self, path: &SimplePath) -> Option<&str> {
+        self.lookup_key(&path.index_path(), path.disambiguator())
+    }
+
+    /// Find the URL path like [`Self::lookup`], but for a path in the form of the mapping.
+    fn lookup_key(&self, key: &str, disambiguator: Option<Disambiguator>) -> Option<&str> {
+        let link = self.mapping.get(key)?;
+
+        match disambiguator {
+            None => Some(link),
+            Some(disambiguator) => std::iter::once(link)
+                .chain(self.shadowed.get(key).into_iter().flatten())
+                .find(|link| {
+                    ItemType::from_link(link).map_or(false, |ty| disambiguator.matches(ty))
+                })
+                .map(String::as_str),
+        }
+    }
+
+    /// Create the link to the root page of a crate, which isn't part of the index mapping.
+    fn format_crate_link(&self, crate_name: &str, options: &LinkOptions) -> String {
+        self.format_link(&self.crate_link_path(crate_name, options), options)
+    }
+
+    /// Path of the root page of a crate, relative to the docs root. Custom roots follow the
+    /// directory layout of `rustdoc`, while hosts resolve the crate directory by themselves.
+    fn crate_link_path(&self, crate_name: &str, options: &LinkOptions) -> String {
+        if self.root.is_some() || options.root.is_some() {
+            format!("{crate_name}/index.html")
         } else {
-            format!("https://docs.rs/{}/{}/{link}", self.name, self.version)
-        })
+            crate_name.to_owned()
+        }
+    }
+
+    /// Create the full link from a URL path, relative to the docs root.
+    fn format_link(&self, link: &str, options: &LinkOptions) -> String {
+        self.link_formatter(options).format(link)
+    }
+
+    /// Prepare the creation of many links with the same options.
+    pub(crate) fn link_formatter<'a>(&'a self, options: &'a LinkOptions) -> LinkFormatter<'a> {
+        let host = options.host.as_ref().unwrap_or(&self.host);
+
+        LinkFormatter {
+            index: self,
+            options,
+            root: options.root.as_deref().or(self.root.as_deref()),
+            host,
+            std: options
+                .std_channel
+                .map_or(host.std(), |channel| channel.url()),
+            version: options.version_style.format(&self.version, &self.requested),
+        }
     }
 }
 
+/// Creates links of an [`Index`] with fixed options. Everything that doesn't depend on the item,
+/// like the host and the version segment, is resolved once up front.
+struct LinkFormatter<'a> {
+    index: &'a Index,
+    options: &'a LinkOptions,
+    root: Option<&'a str>,
+    host: &'a DocHost,
+    std: &'a str,
+    version: String,
+}
+
+impl LinkFormatter<'_> {
+    /// Find the link to the docs of the given path, rendered in the [`LinkOptions::style`].
+    fn find(&self, path: &SimplePath) -> Option<String> {
+        let url = if path.is_crate_only() {
+            self.format(&self.index.crate_link_path(path.crate_name(), self.options))
+        } else {
+            self.format(self.index.lookup(path)?)
+        };
+
+        Some(self.options.render(path.as_ref(), &url))
+    }
+
+    /// Create the full link from a URL path, relative to the docs root.
+    fn format(&self, link: &str) -> String {
+        let link = &*link::encode_path(link);
+
+        if let Some(root) = self.root {
+            return match root {
+                "" => link.to_owned(),
+                "/" => format!("/{link}"),
+                _ => format!("{root}/{link}"),
+            };
+        }
+
+        if let Some(template) = &self.options.template {
+            return host::template_url(
+                template,
+                if self.index.std {
+                    self.std
+                } else {
+                    self.host.docs()
+                },
+                &self.index.name,
+                &self.version,
+                link,
+            );
+        }
+
+        if self.index.std {
+            format!("{}/{link}", self.std)
+        } else {
+            self.host.link_url(&self.index.name, &self.version, link)
+        }
+    }
+}
+
+/// Check whether the path is the module itself or any item within it.
+fn is_in_module(path: &str, module: &str) -> bool {
+    path.strip_prefix(module)
+        .map_or(false, |rest| rest.is_empty() || rest.starts_with("::"))
+}
+
 /// Search for the given crate name and optionally a fixed version. This is the main entry point to
 /// retrieve an [`Index`] and further query that index for [`SimplePath`]s.
 ///
@@ -135,13 +992,145 @@ impl Index {
 #[must_use]
 pub fn start_search(name: &str, version: Version) -> SearchPage<'_> {
     let std = STD_CRATES.contains(&name);
-    let url = crates::get_page_url(std, name, &version);
+    let host = DocHost::default();
+    let url = crates::get_page_url(&host, std, name, &version);
 
     SearchPage {
-        name,
+        name: Cow::Borrowed(name),
+        requested: version.clone(),
         version,
         std,
+        host,
+        root: None,
         url,
+        observer: None,
+    }
+}
+
+/// Start a new search like [`start_search`], but against a self-hosted documentation root, like
+/// the output of `cargo doc` published on a private server. This is needed for crates that aren't
+/// available on docs.rs.
+///
+/// The root must have the same layout as `cargo doc` generates, with the docs of each crate in a
+/// sub-directory and the search index right in the root. The page, the index and the links of the
+/// resulting [`Index`] are all relative to this root.
+///
+/// ```rust
+/// use docsearch::Version;
+///
+/// let state = docsearch::start_search_at("https://docs.example.com/", "my-crate", Version::Latest);
+/// assert_eq!("https://docs.example.com/my_crate/index.html", state.url());
+///
+/// let state = state
+///     .find_index(r#"<div id="rustdoc-vars" data-resource-suffix="-1.70.0"></div>"#)
+///     .unwrap();
+/// assert_eq!("https://docs.example.com/search-index-1.70.0.js", state.url());
+/// ```
+#[must_use]
+pub fn start_search_at<'a>(base_url: &str, name: &'a str, version: Version) -> SearchPage<'a> {
+    let root = base_url.trim_end_matches('/').to_owned();
+    let url = crates::get_root_page_url(&root, name);
+
+    SearchPage {
+        name: Cow::Borrowed(name),
+        requested: version.clone(),
+        version,
+        std: STD_CRATES.contains(&name),
+        host: DocHost::default(),
+        root: Some(root),
+        url,
+        observer: None,
+    }
+}
+
+/// Load the search index from a local documentation directory, usually `target/doc` as generated
+/// by `cargo doc`. This allows fully offline usage, even for crates that aren't published.
+///
+/// The directory can contain the docs of several crates (like all dependencies of a project),
+/// therefore an [`Index`] for each of the contained crates is returned. The generated links are
+/// relative to the directory if the path is relative, or `file://` URLs if the path is absolute.
+///
+/// # Example
+///
+/// ```no_run
+/// let indexes = docsearch::from_local_docs("target/doc").unwrap();
+/// let query = "anyhow::Result".parse().unwrap();
+///
+/// if let Some(index) = indexes.iter().find(|index| index.name == "anyhow") {
+///     // Prints `target/doc/anyhow/type.Result.html`.
+///     println!("{}", index.find_link(&query).unwrap());
+/// }
+/// ```
+pub fn from_local_docs(path: impl AsRef<Path>) -> Result<Vec<Index>> {
+    local::load(path.as_ref())
+}
+
+/// Start a new search like [`start_search`], but resolve the version through the crates.io
+/// [sparse index](https://doc.rust-lang.org/cargo/reference/registry-index.html#sparse-protocol)
+/// first, instead of relying on the redirects of docs.rs.
+///
+/// This allows to detect yanked releases before fetching any docs, and normalizes the crate name to
+/// its published spelling.
+///
+/// Returns [`None`] for crates of the standard library, as these aren't published on crates.io and
+/// should be searched with [`start_search`] directly.
+#[must_use]
+pub fn start_search_with_registry(name: &str, version: Version) -> Option<SearchRegistry> {
+    if STD_CRATES.contains(&name) {
+        return None;
+    }
+
+    Some(SearchRegistry {
+        name: name.to_owned(),
+        version,
+        url: registry::get_index_url(name),
+    })
+}
+
+/// Optional state before the [`SearchPage`], that resolves the crate version through the crates.io
+/// sparse index. Use the [`Self::url`] function to get the URL of the index file to download. Its
+/// content must then be passed to [`Self::find_version`] to get to the next state.
+pub struct SearchRegistry {
+    name: String,
+    version: Version,
+    url: String,
+}
+
+impl SearchRegistry {
+    /// URL to the sparse index file that should be retrieved and passed to [`Self::find_version`].
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Full description of the request to retrieve the content for [`Self::find_version`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        Request::get(&self.url)
+    }
+
+    /// Find the requested version in the content downloaded from [`Self::url`] and continue with a
+    /// search for that exact version.
+    ///
+    /// For [`Version::Latest`], the highest version that is neither yanked nor a pre-release is
+    /// used. A specific version results in an error if it doesn't exist or was yanked.
+    pub fn find_version(self, body: &str) -> Result<SearchPage<'static>> {
+        let release = registry::find_release(&self.name, &self.version, body)
+            .map_err(|e| e.with_url(&self.url))?;
+        let version = Version::SemVer(release.vers);
+        let host = DocHost::default();
+        let url = crates::get_page_url(&host, false, &release.name, &version);
+
+        Ok(SearchPage {
+            name: Cow::Owned(release.name),
+            version,
+            requested: self.version,
+            std: false,
+            host,
+            root: None,
+            url,
+            observer: None,
+        })
     }
 }
 
@@ -149,10 +1138,14 @@ pub fn start_search(name: &str, version: Version) -> SearchPage<'_> {
 /// download content from. The web page content must then be passed to [`Self::find_index`] to get
 /// to the next state.
 pub struct SearchPage<'a> {
-    name: &'a str,
+    name: Cow<'a, str>,
     version: Version,
+    requested: Version,
     std: bool,
+    host: DocHost,
+    root: Option<String>,
     url: Cow<'static, str>,
+    observer: Option<Observer>,
 }
 
 impl<'a> SearchPage<'a> {
@@ -162,17 +1155,257 @@ impl<'a> SearchPage<'a> {
         &self.url
     }
 
+    /// Full description of the request to retrieve the content for [`Self::find_index`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        Request::get(self.url.as_ref())
+    }
+
+    /// Full description of the `HEAD` request, whose response is passed to
+    /// [`Self::resolve_from_head`].
+    #[must_use]
+    pub fn head_request(&self) -> Request {
+        Request::head(self.url.as_ref())
+    }
+
+    /// Call the observer with the [`Progress`] of this and all following states, whenever the
+    /// search moves on to the next step.
+    ///
+    /// ```rust
+    /// use docsearch::{Progress, Version};
+    ///
+    /// let state = docsearch::start_search("anyhow", Version::Latest).observe(|progress| {
+    ///     if let Progress::ItemsMapped { count } = progress {
+    ///         println!("found {count} items");
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn observe(mut self, observer: impl Fn(&Progress<'_>) + Send + Sync + 'static) -> Self {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Retrieve the documentation from the given hosts, instead of `docs.rs` and
+    /// `doc.rust-lang.org`. The resulting [`Index`] generates links for the same hosts.
+    ///
+    /// This replaces the custom documentation root of [`start_search_at`], if any.
+    #[must_use]
+    pub fn with_host(self, host: DocHost) -> Self {
+        let url = crates::get_page_url(&host, self.std, &self.name, &self.version);
+
+        Self {
+            host,
+            root: None,
+            url,
+            ..self
+        }
+    }
+
+    /// Switch to the alternative spelling of the crate name, replacing any `-` with `_` or the other
+    /// way around. Returns [`None`] if the name contains neither of them, or the search uses a
+    /// custom documentation root, where paths don't depend on the spelling of the name.
+    ///
+    /// Crate names are often used interchangeably with underscores or dashes, like `serde_yaml` vs
+    /// the published `serde-yaml`. If the download from [`Self::url`] fails with a `404` status,
+    /// the search can be retried with the alternative name instead.
+    ///
+    /// The [`Search`] driver does this automatically.
+    #[must_use]
+    pub fn with_alternative_name(mut self) -> Option<Self> {
+        self.rename().then_some(self)
+    }
+
+    /// Switch to the alternative spelling of the crate name like [`Self::with_alternative_name`],
+    /// but in place. Returns whether there is an alternative name at all.
+    pub(crate) fn rename(&mut self) -> bool {
+        let name = match crates::alternative_name(&self.name) {
+            Some(name) if self.root.is_none() => name,
+            _ => return false,
+        };
+
+        self.url = crates::get_page_url(&self.host, self.std, &name, &self.version);
+        self.name = Cow::Owned(name);
+
+        true
+    }
+
+    /// Resolve the concrete version from the `Location` header of the redirect response, that
+    /// docs.rs sends for [`Version::Latest`] and [`Version::Req`] requests to [`Self::url`]. The
+    /// header value can be either the absolute URL or just its path.
+    ///
+    /// Clients that can intercept redirects can use this to learn the concrete version without
+    /// downloading the HTML page, for example to check it against a cache. The returned state
+    /// points to the page of the concrete version.
+    ///
+    /// For the stdlib and custom documentation roots there is no such redirect and the state is
+    /// returned unchanged.
+    pub fn resolve_from_redirect(self, location: &str) -> Result<Self> {
+        if self.std || self.root.is_some() {
+            return Ok(self);
+        }
+
+        let version = self
+            .host
+            .version_position()
+            .and_then(|pos| crates::find_version_in_url(location, pos))
+            .map(Version::SemVer)
+            .ok_or_else(|| Error::MissingVersion {
+                name: self.name.to_string(),
+                url: location.to_owned(),
+            })?;
+        let url = crates::get_page_url(&self.host, false, &self.name, &version);
+
+        Ok(Self {
+            version,
+            url,
+            ..self
+        })
+    }
+
+    /// Resolve the concrete version from the final URL of the page, after all redirects were
+    /// followed. Many HTTP clients expose this URL together with the response, for example
+    /// `reqwest::Response::url`.
+    ///
+    /// The version is then used for the index URL and the resulting [`Index`], instead of the
+    /// requested one. If the URL doesn't contain a concrete version, the state is returned
+    /// unchanged.
+    #[must_use]
+    pub fn resolve_version(self, final_url: &str) -> Self {
+        if self.std || self.root.is_some() {
+            return self;
+        }
+
+        match self
+            .host
+            .version_position()
+            .and_then(|pos| crates::find_version_in_url(final_url, pos))
+        {
+            Some(version) => Self {
+                version: Version::SemVer(version),
+                ..self
+            },
+            None => self,
+        }
+    }
+
+    /// Resolve the concrete version from the response of a `HEAD` request to [`Self::url`], that
+    /// doesn't follow redirects. Only the status code and the `Location` header are needed.
+    ///
+    /// - A redirect resolves the version from the location, like [`Self::resolve_from_redirect`].
+    /// - A success status means the version was already concrete and the state stays unchanged.
+    /// - Any other status results in an error, like [`Self::find_index_with_status`] describes.
+    ///
+    /// Together with [`Self::with_resource_suffix`], this allows to skip the download of the HTML
+    /// page, reserving the full `GET` request for the index file itself.
+    pub fn resolve_from_head(self, status: u16, location: Option<&str>) -> Result<Self> {
+        match (status, location) {
+            (300..=399, Some(location)) => self.resolve_from_redirect(location),
+            _ => crates::check_status(status, &self.name, &self.version, &self.url, None)
+                .map(|()| self),
+        }
+    }
+
+    /// Skip the HTML page and continue with the index directly, if the resource suffix of the
+    /// crate's docs is already known (for example from [`Index`] data of a previous search).
+    pub fn with_resource_suffix(self, suffix: &str) -> Result<SearchIndex<'a>> {
+        let index_path = format!("search-index{suffix}.js");
+        let (version, url) = match &self.root {
+            Some(root) => (self.version, format!("{root}/{index_path}")),
+            None => {
+                crates::get_index_url(&self.host, self.std, &self.name, self.version, &index_path)?
+            }
+        };
+
+        Ok(SearchIndex {
+            name: self.name,
+            version,
+            requested: self.requested,
+            std: self.std,
+            host: self.host,
+            root: self.root,
+            rustdoc_version: crates::rustdoc_version_from_suffix(suffix),
+            url,
+            limits: ParseLimits::new(),
+            #[cfg(feature = "checksum")]
+            checksum: None,
+            observer: self.observer,
+        }
+        .located())
+    }
+
+    /// Try to find the index in the content downloaded from [`Self::url`] like [`Self::find_index`],
+    /// but check the HTTP status code of the response first. This allows to return precise errors
+    /// instead of a generic [`Error::IndexNotFound`] for error pages:
+    ///
+    /// - [`Error::CrateNotFound`] or [`Error::VersionNotFound`] for a `404` status, depending on
+    ///   the content of the docs.rs error page.
+    /// - [`Error::RateLimited`] for a `429` status.
+    /// - [`Error::UnexpectedStatus`] for any other non-success status.
+    pub fn find_index_with_status(self, status: u16, body: &str) -> Result<SearchIndex<'a>> {
+        crates::check_status(status, &self.name, &self.version, &self.url, Some(body))?;
+        self.find_index(body)
+    }
+
     /// Try to find the index in the content downloaded from [`Self::url`], effectively transferring
     /// to the next state in retrieving an `Index` instance.
     pub fn find_index(self, body: &str) -> Result<SearchIndex<'a>> {
-        let (version, url) = crates::find_index_url(self.std, self.name, self.version, body)?;
+        self.find_index_with(&DocsRsLocator, body)
+    }
 
-        Ok(SearchIndex {
+    /// Try to find the index in the content downloaded from [`Self::url`] like [`Self::find_index`],
+    /// but use a custom strategy to locate it, instead of the built-in [`DocsRsLocator`].
+    pub fn find_index_with(
+        self,
+        locator: &impl IndexLocator,
+        body: &str,
+    ) -> Result<SearchIndex<'a>> {
+        let location = self.locate(locator, body)?;
+        Ok(self.into_index(location, body))
+    }
+
+    /// Locate the index in the page content, without consuming the state, so it can be kept
+    /// after an error.
+    pub(crate) fn locate(&self, locator: &impl IndexLocator, body: &str) -> Result<IndexLocation> {
+        progress::notify(
+            self.observer.as_ref(),
+            &Progress::PageLocated { url: &self.url },
+        );
+
+        locator.locate(
+            &LocatorContext {
+                name: &self.name,
+                version: &self.version,
+                std: self.std,
+                host: &self.host,
+                root: self.root.as_deref(),
+                url: &self.url,
+            },
+            body,
+        )
+    }
+
+    /// Transfer to the next state, with the index location from [`Self::locate`].
+    pub(crate) fn into_index(
+        self,
+        IndexLocation { version, url }: IndexLocation,
+        body: &str,
+    ) -> SearchIndex<'a> {
+        SearchIndex {
             name: self.name,
             version,
+            requested: self.requested,
             std: self.std,
+            host: self.host,
+            root: self.root,
+            rustdoc_version: crates::find_rustdoc_version(body),
             url,
-        })
+            limits: ParseLimits::new(),
+            #[cfg(feature = "checksum")]
+            checksum: None,
+            observer: self.observer,
+        }
+        .located()
     }
 }
 
@@ -180,33 +1413,204 @@ impl<'a> SearchPage<'a> {
 /// search index URL to download. The index's content must be passed to [`Self::transform_index`] to
 /// create the final [`Index`] instance.
 pub struct SearchIndex<'a> {
-    name: &'a str,
+    name: Cow<'a, str>,
     version: Version,
+    requested: Version,
     std: bool,
+    host: DocHost,
+    root: Option<String>,
+    rustdoc_version: Option<String>,
     url: String,
+    limits: ParseLimits,
+    #[cfg(feature = "checksum")]
+    checksum: Option<Checksum>,
+    observer: Option<Observer>,
 }
 
 impl<'a> SearchIndex<'a> {
+    /// Create the search index state directly from already known parts, skipping the download of
+    /// the HTML page. This is useful for cached crates, where the version and resource suffix (and
+    /// therefore the index URL) are already known from a previous search.
+    ///
+    /// ```rust
+    /// use docsearch::{SearchIndex, Version};
+    ///
+    /// let state = SearchIndex::from_parts(
+    ///     "anyhow",
+    ///     "1.0.72".parse().unwrap(),
+    ///     "https://docs.rs/anyhow/1.0.72/search-index-20230714-1.73.0-nightly-ad963232d.js",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_parts(name: &'a str, version: Version, index_url: impl Into<String>) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            requested: version.clone(),
+            version,
+            std: STD_CRATES.contains(&name),
+            host: DocHost::default(),
+            root: None,
+            rustdoc_version: None,
+            url: index_url.into(),
+            limits: ParseLimits::new(),
+            #[cfg(feature = "checksum")]
+            checksum: None,
+            observer: None,
+        }
+    }
+
+    /// Call the observer with the [`Progress`] of the index transformation, like
+    /// [`SearchPage::observe`].
+    #[must_use]
+    pub fn observe(mut self, observer: impl Fn(&Progress<'_>) + Send + Sync + 'static) -> Self {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Report the found index URL to the observer, after transitioning to this state.
+    fn located(self) -> Self {
+        progress::notify(
+            self.observer.as_ref(),
+            &Progress::IndexUrlFound {
+                url: &self.url,
+                version: &self.version,
+            },
+        );
+        self
+    }
+
+    /// Enforce the given limits when transforming the index, to protect against pathological or
+    /// malicious index files. Exceeding any limit fails with [`Error::LimitExceeded`].
+    #[must_use]
+    pub fn limits(mut self, limits: ParseLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Verify the index content against the given checksum before parsing it, which fails with
+    /// [`Error::ChecksumMismatch`] for truncated or tampered downloads.
+    #[cfg(feature = "checksum")]
+    #[must_use]
+    pub fn checksum(mut self, checksum: Checksum) -> Self {
+        self.checksum = Some(checksum);
+        self
+    }
+
     /// URL to the search index that should be retrieved and passed to [`Self::transform_index`].
     #[must_use]
     pub fn url(&self) -> &str {
         &self.url
     }
 
+    /// Full description of the request to retrieve the content for [`Self::transform_index`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        Request::get(&self.url)
+    }
+
     /// Try to transform the raw index content into a simple "path-to-URL" mapping for each
     /// contained crate.
     pub fn transform_index(self, index_content: &str) -> Result<Index> {
-        let mappings = index::load(index_content)?;
+        self.try_transform_index(index_content)
+    }
+
+    /// Transform the index content like [`Self::transform_index`], but without consuming the
+    /// state, so it can be kept after an error.
+    pub(crate) fn try_transform_index(&self, index_content: &str) -> Result<Index> {
+        self.verify(index_content)?;
+        let loaded = index::load_with(index_content, None, None, &self.limits)
+            .map_err(|e| e.with_url(&self.url))?;
+        self.transform(loaded)
+    }
+
+    /// Transform the raw index content like [`Self::transform_index`], but try the given custom
+    /// parser first, before falling back to the built-in ones. This allows to support new index
+    /// formats before this crate does.
+    pub fn transform_index_with(
+        self,
+        parser: &impl IndexParser,
+        index_content: &str,
+    ) -> Result<Index> {
+        self.verify(index_content)?;
+        let loaded = index::load_with(index_content, Some(parser), None, &self.limits)
+            .map_err(|e| e.with_url(&self.url))?;
+        self.transform(loaded)
+    }
 
-        mappings
+    /// Transform the raw index content like [`Self::transform_index`], but in lenient mode.
+    /// Malformed items, and crates whose data can't be parsed at all, are skipped instead of
+    /// failing the whole index, and returned as warnings together with the index.
+    ///
+    /// The crate of this index must still be valid, otherwise the transformation fails with
+    /// [`Error::CrateDataMissing`].
+    pub fn transform_index_lenient(
+        self,
+        index_content: &str,
+    ) -> Result<(Index, Vec<IndexWarning>)> {
+        self.verify(index_content)?;
+        let mut warnings = Vec::new();
+        let loaded = index::load_with(index_content, None, Some(&mut warnings), &self.limits)
+            .map_err(|e| e.with_url(&self.url))?;
+        let index = self.transform(loaded)?;
+
+        Ok((index, warnings))
+    }
+
+    /// Check the index content against the expected checksum, if any.
+    #[cfg(feature = "checksum")]
+    fn verify(&self, index_content: &str) -> Result<()> {
+        if let Some(expected) = self.checksum {
+            let actual = Checksum::of(index_content);
+            if actual != expected {
+                return Err(Error::ChecksumMismatch {
+                    expected,
+                    actual,
+                    url: self.url.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "checksum"))]
+    #[allow(clippy::unused_self, clippy::unnecessary_wraps)]
+    fn verify(&self, _index_content: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn transform(&self, (format, mappings): (IndexFormat, index::Mappings)) -> Result<Index> {
+        progress::notify(self.observer.as_ref(), &Progress::IndexParsed { format });
+
+        let index = mappings
             .into_iter()
-            .find(|(crate_name, _)| crate_name == self.name)
+            .find(|(crate_name, _)| *crate_name == crates::crate_name(&self.name))
             .map(|(name, mapping)| Index {
                 name,
                 version: self.version.clone(),
-                mapping,
+                requested: self.requested.clone(),
+                mapping: mapping.links,
+                shadowed: mapping.shadowed,
                 std: self.std,
+                host: self.host.clone(),
+                root: self.root.clone(),
+                rustdoc_version: self.rustdoc_version.clone(),
+                format: Some(format),
+                resource_suffix: crates::resource_suffix(&self.url).map(ToOwned::to_owned),
+                metadata: None,
             })
-            .ok_or(Error::CrateDataMissing)
+            .ok_or_else(|| Error::CrateDataMissing {
+                name: self.name.clone().into_owned(),
+                url: self.url.clone(),
+            })?;
+
+        progress::notify(
+            self.observer.as_ref(),
+            &Progress::ItemsMapped {
+                count: index.mapping.len(),
+            },
+        );
+
+        Ok(index)
     }
 }
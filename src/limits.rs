@@ -0,0 +1,207 @@
+//! Limits for parsing untrusted search indexes.
+
+use std::fmt;
+
+use crate::{
+    error::{Error, Result},
+    index::{IndexWarning, RawCrateData, RawIndexData},
+};
+
+/// Limits that are enforced when parsing a search index with
+/// [`SearchIndex::limits`](crate::SearchIndex::limits). This protects services that load the
+/// indexes of user-specified crates from pathological or malicious index files.
+///
+/// By default, no limits are enforced.
+///
+/// Only [`Self::max_index_size`] is checked before the index is parsed, and therefore bounds the
+/// memory and time spent on parsing. The other limits are checked after each crate was
+/// deserialized, so they only protect the further transformation into the final mapping and the
+/// resulting [`Index`](crate::Index). Services that handle untrusted indexes should always set a
+/// maximum index size.
+///
+/// ```rust
+/// use docsearch::ParseLimits;
+///
+/// let limits = ParseLimits::new()
+///     .max_index_size(50 * 1024 * 1024)
+///     .max_items(200_000)
+///     .max_string_length(4096);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseLimits {
+    pub(crate) index_size: Option<usize>,
+    pub(crate) items: Option<usize>,
+    pub(crate) string_length: Option<usize>,
+}
+
+impl ParseLimits {
+    /// Create new limits, that don't restrict the index in any way.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum size of the whole index content in bytes. This is checked before parsing.
+    #[must_use]
+    pub fn max_index_size(mut self, bytes: usize) -> Self {
+        self.index_size = Some(bytes);
+        self
+    }
+
+    /// Set the maximum number of items in a single crate of the index. This is checked after
+    /// parsing, but before the items are transformed.
+    #[must_use]
+    pub fn max_items(mut self, items: usize) -> Self {
+        self.items = Some(items);
+        self
+    }
+
+    /// Set the maximum length in bytes of any single string in a crate, like item names, module
+    /// paths and descriptions. This is checked after parsing, but before the items are
+    /// transformed.
+    #[must_use]
+    pub fn max_string_length(mut self, bytes: usize) -> Self {
+        self.string_length = Some(bytes);
+        self
+    }
+
+    /// Check the size of the raw index content.
+    pub(crate) fn check_index(&self, index: &str) -> Result<()> {
+        check(Limit::IndexSize, index.len(), self.index_size)
+    }
+
+    /// Check the data of each crate in the index. Crates that exceed any limit fail the whole
+    /// index, or are dropped with a warning in lenient mode.
+    pub(crate) fn check_crates(
+        &self,
+        raw: RawIndexData,
+        warnings: Option<&mut Vec<IndexWarning>>,
+    ) -> Result<RawIndexData> {
+        if self.items.is_none() && self.string_length.is_none() {
+            return Ok(raw);
+        }
+
+        let warnings = if let Some(warnings) = warnings {
+            warnings
+        } else {
+            for data in raw.crates.values() {
+                self.check_crate(data)?;
+            }
+            return Ok(raw);
+        };
+
+        let crates = raw
+            .crates
+            .into_iter()
+            .filter_map(|(name, data)| match self.check_crate(&data) {
+                Ok(()) => Some((name, data)),
+                Err(e) => {
+                    warnings.push(IndexWarning::InvalidCrate {
+                        name,
+                        reason: e.to_string(),
+                    });
+                    None
+                }
+            })
+            .collect();
+
+        Ok(RawIndexData { crates })
+    }
+
+    fn check_crate(&self, data: &RawCrateData) -> Result<()> {
+        check(Limit::Items, data.t.len(), self.items)?;
+
+        if let Some(max) = self.string_length {
+            let longest = std::iter::once(&data.doc)
+                .chain(&data.n)
+                .chain(data.q.values())
+                .chain(&data.d)
+                .chain(data.p.iter().map(|(_, name)| name))
+                .map(String::len)
+                .max()
+                .unwrap_or_default();
+
+            check(Limit::StringLength, longest, Some(max))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Single limit of the [`ParseLimits`], that was exceeded by an index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Limit {
+    /// Size of the whole index content, set by [`ParseLimits::max_index_size`].
+    IndexSize,
+    /// Number of items in a crate, set by [`ParseLimits::max_items`].
+    Items,
+    /// Length of a single string, set by [`ParseLimits::max_string_length`].
+    StringLength,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::IndexSize => "index size",
+            Self::Items => "number of items",
+            Self::StringLength => "string length",
+        })
+    }
+}
+
+fn check(limit: Limit, value: usize, max: Option<usize>) -> Result<()> {
+    match max {
+        Some(max) if value > max => Err(Error::LimitExceeded { limit, value, max }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util, SearchIndex};
+
+    fn transform(limits: ParseLimits) -> Result<crate::Index> {
+        SearchIndex::from_parts("anyhow", "1.0.72".parse().unwrap(), "")
+            .limits(limits)
+            .transform_index(&test_util::anyhow_fixture())
+    }
+
+    #[test]
+    fn test_limits() {
+        assert!(transform(ParseLimits::new()).is_ok());
+        assert!(transform(
+            ParseLimits::new()
+                .max_index_size(1024 * 1024)
+                .max_items(1000)
+                .max_string_length(1024)
+        )
+        .is_ok());
+
+        assert!(matches!(
+            transform(ParseLimits::new().max_index_size(100)),
+            Err(Error::LimitExceeded {
+                limit: Limit::IndexSize,
+                max: 100,
+                ..
+            })
+        ));
+        assert!(matches!(
+            transform(ParseLimits::new().max_items(10)),
+            Err(Error::LimitExceeded {
+                limit: Limit::Items,
+                max: 10,
+                ..
+            })
+        ));
+        assert!(matches!(
+            transform(ParseLimits::new().max_string_length(10)),
+            Err(Error::LimitExceeded {
+                limit: Limit::StringLength,
+                max: 10,
+                ..
+            })
+        ));
+    }
+}
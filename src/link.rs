@@ -0,0 +1,643 @@
+//! Options to customize the links generated from an [`Index`](crate::Index).
+
+use std::{borrow::Cow, fmt};
+
+use crate::{DocHost, StdChannel, Version};
+
+/// Options for the generation of links, passed to
+/// [`Index::find_link_with`](crate::Index::find_link_with).
+///
+/// ```rust
+/// use docsearch::{LinkOptions, VersionStyle};
+///
+/// let options = LinkOptions::new().version_style(VersionStyle::Major);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkOptions {
+    pub(crate) version_style: VersionStyle,
+    pub(crate) host: Option<DocHost>,
+    pub(crate) style: LinkStyle,
+    pub(crate) root: Option<String>,
+    pub(crate) template: Option<String>,
+    pub(crate) std_channel: Option<StdChannel>,
+    pub(crate) max_length: Option<usize>,
+}
+
+impl LinkOptions {
+    /// Create new options with the default settings, which generate the same links as
+    /// [`Index::find_link`](crate::Index::find_link).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how the crate version is represented in docs.rs links.
+    #[must_use]
+    pub fn version_style(mut self, style: VersionStyle) -> Self {
+        self.version_style = style;
+        self
+    }
+
+    /// Generate links for the given host, instead of the one the index was retrieved from.
+    #[must_use]
+    pub fn host(mut self, host: DocHost) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// Generate links to the stdlib docs of the given release channel, like the stable docs,
+    /// instead of the stdlib host of the index. This only affects stdlib indexes.
+    #[must_use]
+    pub fn std_channel(mut self, channel: StdChannel) -> Self {
+        self.std_channel = Some(channel);
+        self
+    }
+
+    /// Set the output format of the links, like a Markdown link instead of the plain URL.
+    #[must_use]
+    pub fn style(mut self, style: LinkStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Keep the rendered links within the given number of characters, like the 2000 characters
+    /// of a Discord message. Long paths are shortened as described in
+    /// [`LinkStyle::render_limited`].
+    #[must_use]
+    pub fn max_length(mut self, max: usize) -> Self {
+        self.max_length = Some(max);
+        self
+    }
+
+    /// Generate links relative to the given docs root, like `../doc` or `/api`, instead of full
+    /// URLs with scheme and host. The links follow the directory layout of `rustdoc` without
+    /// any version, like `{root}/anyhow/struct.Error.html`, which allows to embed them into
+    /// locally hosted documentation or mdBook output.
+    ///
+    /// An empty root creates links relative to the docs root itself, like
+    /// `anyhow/struct.Error.html`. Trailing slashes are removed, except for the server root `/`,
+    /// which creates links like `/anyhow/struct.Error.html`.
+    ///
+    /// ```rust
+    /// use docsearch::LinkOptions;
+    ///
+    /// let options = LinkOptions::new().relative_to("../doc");
+    /// ```
+    #[must_use]
+    pub fn relative_to(mut self, root: impl Into<String>) -> Self {
+        let mut root = root.into();
+        let len = root.trim_end_matches('/').len();
+        root.truncate(if len == 0 && !root.is_empty() { 1 } else { len });
+        self.root = Some(root);
+        self
+    }
+
+    /// Generate links from a custom URL template, instead of the layout of the index's host. This
+    /// allows to target mirrors or proxies with a nonstandard layout. The template can contain
+    /// the following placeholders:
+    ///
+    /// - `{host}` for the base URL of the host, like `https://docs.rs` (or the stdlib host for
+    ///   stdlib indexes).
+    /// - `{name}` for the name of the crate as published, like `serde-yaml`.
+    /// - `{crate}` for the name of the crate as used in code, like `serde_yaml`.
+    /// - `{version}` for the version of the crate, formatted in the [`Self::version_style`].
+    /// - `{path}` for the path of the item page, relative to the docs root, like
+    ///   `serde_yaml/struct.Value.html`.
+    ///
+    /// A root set with [`Self::relative_to`] takes precedence over the template.
+    ///
+    /// ```rust
+    /// use docsearch::LinkOptions;
+    ///
+    /// let options = LinkOptions::new().template("https://proxy.example.com/{crate}@{version}/{path}");
+    /// ```
+    #[must_use]
+    pub fn template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// Render the link in the [`Self::style`], respecting the [`Self::max_length`] if any.
+    pub(crate) fn render(&self, path: &str, url: &str) -> String {
+        match self.max_length {
+            Some(max) => self.style.render_limited(path, url, max),
+            None => self.style.render(path, url),
+        }
+    }
+}
+
+/// Representation of the crate version in docs.rs links.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VersionStyle {
+    /// Use the exact version of the index, like `/tokio/1.32.0/tokio/`.
+    #[default]
+    Exact,
+    /// Only pin the major version (or the minor version for `0.x` releases), like `/tokio/1/tokio/`.
+    ///
+    /// docs.rs resolves these to the latest matching release, so the links keep working across
+    /// patch releases without the need to refetch the index.
+    Major,
+    /// Use the version as it was originally requested for the search, like `/tokio/latest/tokio/`
+    /// for [`Version::Latest`], instead of the version it was resolved to.
+    Requested,
+}
+
+impl VersionStyle {
+    /// Format the resolved or requested version as path segment for docs.rs links, according to
+    /// this style.
+    pub(crate) fn format(self, version: &Version, requested: &Version) -> String {
+        match (self, version) {
+            (Self::Major, Version::SemVer(v)) if v.major > 0 => v.major.to_string(),
+            (Self::Major, Version::SemVer(v)) => format!("{}.{}", v.major, v.minor),
+            (Self::Requested, _) => requested.to_url_segment(),
+            _ => version.to_url_segment(),
+        }
+    }
+}
+
+/// Output format of generated links, that decides how the item path and URL are combined and
+/// escaped.
+///
+/// ```rust
+/// use docsearch::LinkStyle;
+///
+/// assert_eq!(
+///     "[`anyhow::Error`](https://docs.rs/anyhow/latest/anyhow/struct.Error.html)",
+///     LinkStyle::Markdown.render(
+///         "anyhow::Error",
+///         "https://docs.rs/anyhow/latest/anyhow/struct.Error.html",
+///     ),
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LinkStyle {
+    /// Only the URL, without the path.
+    #[default]
+    Plain,
+    /// Markdown link with the path as inline code, like ``[`anyhow::Result`](https://...)``.
+    Markdown,
+    /// HTML anchor with the path as inline code, like
+    /// `<a href="https://..."><code>anyhow::Result</code></a>`.
+    Html,
+    /// Path as inline code followed by the URL in angle brackets, like
+    /// `` `anyhow::Result`: <https://...> ``. Chat apps like Discord don't support Markdown
+    /// links in regular messages, and the angle brackets suppress the link preview.
+    ChatCode,
+}
+
+impl LinkStyle {
+    /// Render the link to the given URL, with the path as link text.
+    #[must_use]
+    pub fn render(self, path: &str, url: &str) -> String {
+        match self {
+            Self::Plain => url.to_owned(),
+            Self::Markdown => markdown(path, url),
+            Self::Html => html(path, url),
+            Self::ChatCode => format!("{}: <{}>", code(path), escape_markdown_url(url)),
+        }
+    }
+
+    /// Render the link like [`Self::render`], but keep it within the given number of characters.
+    ///
+    /// The URL is never cut, as that would break the link. Instead, the leading segments of the
+    /// path are replaced with `…` until the link fits, like `` `…::io::Error` `` for
+    /// `std::io::Error`. If even the last segment doesn't fit, only the URL is kept. Links can
+    /// still exceed the limit if the URL alone is too long.
+    ///
+    /// ```rust
+    /// use docsearch::LinkStyle;
+    ///
+    /// let url = "https://doc.rust-lang.org/nightly/std/io/struct.Error.html";
+    ///
+    /// assert_eq!(
+    ///     format!("`…::io::Error`: <{url}>"),
+    ///     LinkStyle::ChatCode.render_limited("std::io::Error", url, 76),
+    /// );
+    /// assert_eq!(
+    ///     format!("<{url}>"),
+    ///     LinkStyle::ChatCode.render_limited("std::io::Error", url, 70),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn render_limited(self, path: &str, url: &str, max_length: usize) -> String {
+        let fits = |link: &str| link.chars().count() <= max_length;
+
+        let link = self.render(path, url);
+        if fits(&link) || self == Self::Plain {
+            return link;
+        }
+
+        let mut rest = path;
+        while let Some((_, tail)) = rest.split_once("::") {
+            rest = tail;
+
+            let link = self.render(&format!("…::{rest}"), url);
+            if fits(&link) {
+                return link;
+            }
+        }
+
+        match self {
+            Self::Plain => url.to_owned(),
+            Self::Markdown | Self::ChatCode => format!("<{}>", escape_markdown_url(url)),
+            Self::Html => format!("<a href=\"{0}\">{0}</a>", escape_html(url)),
+        }
+    }
+}
+
+/// Link to the docs of an item, split into its components, as returned by
+/// [`Index::find_link_parts`](crate::Index::find_link_parts). This allows to change the page or
+/// anchor independently, or to turn the link into a relative one, without parsing the URL.
+///
+/// The components are already percent-encoded and the full URL is available through the
+/// [`Display`](fmt::Display) implementation, in the form `{base}/{page}#{fragment}`.
+///
+/// ```rust
+/// use docsearch::Link;
+///
+/// let link = Link {
+///     base: "https://docs.rs/anyhow/1.0.72".to_owned(),
+///     page: "anyhow/struct.Error.html".to_owned(),
+///     fragment: Some("method.context".to_owned()),
+/// };
+///
+/// assert_eq!(
+///     "https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html#method.context",
+///     link.to_string(),
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Link {
+    /// Root of the docs that the page belongs to, like `https://docs.rs/anyhow/1.0.72` or a
+    /// custom root directory. This can be empty for links relative to the docs root.
+    pub base: String,
+    /// Path of the docs page, relative to the [`Self::base`], like `anyhow/struct.Error.html`.
+    pub page: String,
+    /// Anchor of an item within the page, like `method.context`, without the `#`.
+    pub fragment: Option<String>,
+}
+
+impl Link {
+    /// Create the link from the full URL of the page, by splitting off the page path again. If
+    /// the URL doesn't end with the page, the whole URL is used as base.
+    pub(crate) fn from_url(url: String, page: &str, fragment: Option<&str>) -> Self {
+        let (base, page) = match url.strip_suffix(page) {
+            Some(base) if !page.is_empty() => {
+                let trimmed = base.trim_end_matches('/');
+                let base = if trimmed.is_empty() && !base.is_empty() {
+                    "/"
+                } else {
+                    trimmed
+                };
+                (base.to_owned(), page)
+            }
+            _ => (url, ""),
+        };
+
+        Self {
+            base,
+            page: page.to_owned(),
+            fragment: fragment.map(|fragment| encode_path(fragment).into_owned()),
+        }
+    }
+
+    /// Full URL of the page, without the fragment.
+    #[must_use]
+    pub fn page_url(&self) -> String {
+        match (self.base.is_empty(), self.page.is_empty()) {
+            (_, true) => self.base.clone(),
+            (true, false) => self.page.clone(),
+            (false, false) if self.base == "/" => format!("/{}", self.page),
+            (false, false) => format!("{}/{}", self.base, self.page),
+        }
+    }
+}
+
+#[cfg(feature = "url")]
+impl Link {
+    /// Parse the full link into a [`url::Url`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the link isn't an absolute URL, like links relative to a custom docs root.
+    pub fn to_url(&self) -> crate::error::Result<url::Url> {
+        url::Url::parse(&self.to_string()).map_err(Into::into)
+    }
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.page_url())?;
+
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{fragment}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Percent-encode all characters of the URL path that aren't allowed in URLs, like the non-ASCII
+/// characters of unicode identifiers. The separators `/` and `#` as well as existing
+/// percent-encoded characters are kept.
+pub(crate) fn encode_path(path: &str) -> Cow<'_, str> {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    const fn is_allowed(b: u8) -> bool {
+        b.is_ascii_alphanumeric()
+            || matches!(
+                b,
+                b'-' | b'.'
+                    | b'_'
+                    | b'~'
+                    | b'/'
+                    | b'#'
+                    | b'%'
+                    | b':'
+                    | b'@'
+                    | b'!'
+                    | b'$'
+                    | b'&'
+                    | b'\''
+                    | b'('
+                    | b')'
+                    | b'*'
+                    | b'+'
+                    | b','
+                    | b';'
+                    | b'='
+            )
+    }
+
+    if path.bytes().all(is_allowed) {
+        return path.into();
+    }
+
+    let mut encoded = String::with_capacity(path.len() * 3);
+    for b in path.bytes() {
+        if is_allowed(b) {
+            encoded.push(char::from(b));
+        } else {
+            encoded.push('%');
+            encoded.push(char::from(HEX[usize::from(b >> 4)]));
+            encoded.push(char::from(HEX[usize::from(b & 0xf)]));
+        }
+    }
+
+    encoded.into()
+}
+
+/// Decode all percent-encoded characters of the URL path, the reverse of [`encode_path`]. Invalid
+/// escape sequences are kept as is.
+pub(crate) fn decode_path(path: &str) -> Cow<'_, str> {
+    if !path.contains('%') {
+        return path.into();
+    }
+
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| path.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        if let Some(b) = escaped {
+            decoded.push(b);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).map_or(path.into(), Into::into)
+}
+
+/// Render a Markdown link with the text formatted as inline code, like
+/// ``[`anyhow::Result`](https://docs.rs/...)``.
+///
+/// Backticks in the text are handled by using a longer code span delimiter and characters that
+/// would end the link destination early are percent-encoded.
+fn markdown(text: &str, url: &str) -> String {
+    format!("[{}]({})", code(text), escape_markdown_url(url))
+}
+
+/// Format the text as Markdown inline code, with a longer delimiter if the text contains
+/// backticks itself.
+fn code(text: &str) -> String {
+    let ticks = if text.contains('`') { "``" } else { "`" };
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+
+    format!("{ticks}{pad}{text}{pad}{ticks}")
+}
+
+/// Percent-encode the characters that aren't allowed in a Markdown link destination.
+fn escape_markdown_url(url: &str) -> Cow<'_, str> {
+    if !url.contains([' ', '(', ')', '<', '>']) {
+        return url.into();
+    }
+
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+        .replace('<', "%3C")
+        .replace('>', "%3E")
+        .into()
+}
+
+/// Render an HTML anchor with the text formatted as inline code, like
+/// `<a href="https://docs.rs/..."><code>anyhow::Result</code></a>`.
+fn html(text: &str, url: &str) -> String {
+    format!(
+        "<a href=\"{}\"><code>{}</code></a>",
+        escape_html(url),
+        escape_html(text)
+    )
+}
+
+/// Escape all characters that have a special meaning in HTML text or attribute values.
+pub(crate) fn escape_html(value: &str) -> Cow<'_, str> {
+    if !value.contains(['&', '<', '>', '"', '\'']) {
+        return value.into();
+    }
+
+    let mut escaped = String::with_capacity(value.len() + 8);
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_style() {
+        let format = |style: VersionStyle, version: &str| {
+            style.format(&version.parse().unwrap(), &Version::Latest)
+        };
+
+        assert_eq!("1.2.3", format(VersionStyle::Exact, "1.2.3"));
+        assert_eq!("1", format(VersionStyle::Major, "1.2.3"));
+        assert_eq!("0.2", format(VersionStyle::Major, "0.2.3"));
+        assert_eq!("latest", format(VersionStyle::Major, "latest"));
+        assert_eq!("latest", format(VersionStyle::Requested, "1.2.3"));
+        assert_eq!(
+            "%5E1.2",
+            VersionStyle::Requested.format(
+                &"1.2.3".parse().unwrap(),
+                &Version::Req("^1.2".parse().unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            "[`anyhow::Result`](https://docs.rs/anyhow/1.0.72/anyhow/type.Result.html)",
+            markdown(
+                "anyhow::Result",
+                "https://docs.rs/anyhow/1.0.72/anyhow/type.Result.html"
+            )
+        );
+        assert_eq!("[``a`b``](x%20%28y%29)", markdown("a`b", "x (y)"));
+        assert_eq!("[`` `a ``](x)", markdown("`a", "x"));
+    }
+
+    #[test]
+    fn test_html() {
+        assert_eq!(
+            "<a href=\"https://docs.rs/anyhow/1.0.72/anyhow/type.Result.html\"><code>anyhow::Result</code></a>",
+            html(
+                "anyhow::Result",
+                "https://docs.rs/anyhow/1.0.72/anyhow/type.Result.html"
+            )
+        );
+        assert_eq!(
+            "<a href=\"a?b=1&amp;c=&quot;\"><code>&lt;T&gt;</code></a>",
+            html("<T>", "a?b=1&c=\"")
+        );
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!("anyhow::Error", escape_html("anyhow::Error"));
+        assert_eq!(
+            "&lt;T as Trait&gt;::f &amp; &quot;g&quot;",
+            escape_html("<T as Trait>::f & \"g\"")
+        );
+    }
+
+    #[test]
+    fn test_link_style() {
+        let url = "https://docs.rs/anyhow/1.0.72/anyhow/type.Result.html";
+
+        assert_eq!(url, LinkStyle::Plain.render("anyhow::Result", url));
+        assert_eq!(
+            format!("`anyhow::Result`: <{url}>"),
+            LinkStyle::ChatCode.render("anyhow::Result", url)
+        );
+    }
+
+    #[test]
+    fn test_render_limited() {
+        let url = "https://docs.rs/tokio/1.35.0/tokio/io/unix/struct.AsyncFd.html";
+        let path = "tokio::io::unix::AsyncFd";
+
+        assert_eq!(
+            LinkStyle::Markdown.render(path, url),
+            LinkStyle::Markdown.render_limited(path, url, 200)
+        );
+        assert_eq!(
+            format!("[`…::unix::AsyncFd`]({url})"),
+            LinkStyle::Markdown.render_limited(path, url, 84)
+        );
+        assert_eq!(
+            format!("<a href=\"{url}\">{url}</a>"),
+            LinkStyle::Html.render_limited(path, url, 80)
+        );
+        assert_eq!(url, LinkStyle::Plain.render_limited(path, url, 10));
+    }
+
+    #[test]
+    fn test_link() {
+        let link = Link::from_url(
+            "https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html".to_owned(),
+            "anyhow/struct.Error.html",
+            Some("method.context"),
+        );
+        assert_eq!("https://docs.rs/anyhow/1.0.72", link.base);
+        assert_eq!("anyhow/struct.Error.html", link.page);
+        assert_eq!(Some("method.context"), link.fragment.as_deref());
+        assert_eq!(
+            "https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html#method.context",
+            link.to_string()
+        );
+
+        let link = Link::from_url("anyhow/index.html".to_owned(), "anyhow/index.html", None);
+        assert_eq!("", link.base);
+        assert_eq!("anyhow/index.html", link.to_string());
+
+        let link = Link::from_url("https://example.com/x?p=a".to_owned(), "a/b.html", None);
+        assert_eq!("https://example.com/x?p=a", link.base);
+        assert_eq!("", link.page);
+        assert_eq!("https://example.com/x?p=a", link.to_string());
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn test_link_to_url() {
+        let link = Link::from_url(
+            "https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html".to_owned(),
+            "anyhow/struct.Error.html",
+            Some("method.context"),
+        );
+        let url = link.to_url().unwrap();
+        assert_eq!(Some("docs.rs"), url.host_str());
+        assert_eq!("/anyhow/1.0.72/anyhow/struct.Error.html", url.path());
+        assert_eq!(Some("method.context"), url.fragment());
+
+        let link = Link::from_url("anyhow/index.html".to_owned(), "anyhow/index.html", None);
+        assert!(link.to_url().is_err());
+    }
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(
+            "anyhow/struct.Error.html#method.context",
+            encode_path("anyhow/struct.Error.html#method.context")
+        );
+        assert_eq!(
+            "k%C3%A4se/fn.%C3%A9t%C3%A9.html",
+            encode_path("käse/fn.été.html")
+        );
+        assert_eq!("a/fn.b%20c.html", encode_path("a/fn.b c.html"));
+    }
+
+    #[test]
+    fn test_decode_path() {
+        assert_eq!(
+            "käse/fn.été.html",
+            decode_path("k%C3%A4se/fn.%C3%A9t%C3%A9.html")
+        );
+        assert_eq!("a/%zz/%", decode_path("a/%zz/%"));
+        assert_eq!("%FF", decode_path("%FF"));
+    }
+}
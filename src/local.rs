@@ -0,0 +1,170 @@
+//! Loading of the search index from a local documentation directory, as generated by `cargo doc`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use tracing::debug;
+
+use crate::{
+    crates,
+    error::{Error, Result},
+    index, DocHost, Index, Version,
+};
+
+/// Load all crates from the search index in the given documentation directory.
+///
+/// Links of the resulting indexes are relative to the directory if the given path is relative, or
+/// `file://` URLs if the path is absolute.
+pub(crate) fn load(path: &Path) -> Result<Vec<Index>> {
+    let index_path = find_index_file(path)?;
+    debug!("found local index: {}", index_path.display());
+
+    let content = fs::read_to_string(&index_path)?;
+    let resource_suffix = index_path
+        .file_name()
+        .and_then(|name| crates::resource_suffix(name.to_str()?))
+        .map(ToOwned::to_owned);
+    let root = root_url(path);
+
+    let (format, mappings) = index::load(&content)?;
+
+    let mut indexes = mappings
+        .into_iter()
+        .map(|(name, mapping)| Index {
+            name,
+            version: Version::Latest,
+            requested: Version::Latest,
+            mapping: mapping.links,
+            shadowed: mapping.shadowed,
+            std: false,
+            host: DocHost::default(),
+            root: Some(root.clone()),
+            rustdoc_version: None,
+            format: Some(format),
+            resource_suffix: resource_suffix.clone(),
+            metadata: None,
+        })
+        .collect::<Vec<_>>();
+
+    indexes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(indexes)
+}
+
+/// Locate the search index file in the documentation directory. It is usually named
+/// `search-index.js`, but may contain a resource suffix like `search-index1.70.0.js` as well.
+fn find_index_file(path: &Path) -> Result<PathBuf> {
+    let mut candidates = fs::read_dir(path)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let is_index = path.extension()? == "js"
+                && path.file_stem()?.to_str()?.starts_with("search-index");
+
+            is_index.then_some(path)
+        })
+        .collect::<Vec<_>>();
+
+    candidates.sort();
+    candidates
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::IndexNotFound {
+            url: path.display().to_string(),
+        })
+}
+
+/// Create the root URL that links are based on, from the documentation directory path.
+fn root_url(path: &Path) -> String {
+    let root = path.to_string_lossy().replace('\\', "/");
+    let root = root.trim_end_matches('/');
+
+    if path.is_absolute() {
+        format!(
+            "file://{}{root}",
+            if root.starts_with('/') { "" } else { "/" }
+        )
+    } else {
+        root.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::LinkOptions;
+
+    #[test]
+    fn test_load() {
+        let dir = env::temp_dir().join(format!("docsearch-local-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::copy(
+            "src/index/fixtures/anyhow-1.0.72.js",
+            dir.join("search-index.js"),
+        )
+        .unwrap();
+
+        let indexes = load(&dir).unwrap();
+        assert_eq!(1, indexes.len());
+
+        let index = &indexes[0];
+        assert_eq!("anyhow", index.name);
+        assert_eq!(
+            Some(format!("{}/anyhow/type.Result.html", root_url(&dir))),
+            index.find_link(&"anyhow::Result".parse().unwrap())
+        );
+        assert_eq!(
+            Some("/api/anyhow/type.Result.html".to_owned()),
+            index.find_link_with(
+                &"anyhow::Result".parse().unwrap(),
+                &LinkOptions::new().relative_to("/api/")
+            )
+        );
+        assert_eq!(
+            Some("anyhow/index.html".to_owned()),
+            index.find_link_with(
+                &"anyhow".parse().unwrap(),
+                &LinkOptions::new().relative_to("")
+            )
+        );
+        assert_eq!(
+            Some("/anyhow/type.Result.html".to_owned()),
+            index.find_link_with(
+                &"anyhow::Result".parse().unwrap(),
+                &LinkOptions::new().relative_to("/")
+            )
+        );
+
+        let link = index
+            .find_link_parts_with(
+                &"anyhow::Error::context".parse().unwrap(),
+                &LinkOptions::new().relative_to("/api"),
+            )
+            .unwrap();
+        assert_eq!("/api", link.base);
+        assert_eq!("anyhow/struct.Error.html", link.page);
+        assert_eq!(Some("method.context"), link.fragment.as_deref());
+
+        let link = index
+            .find_link_parts_with(
+                &"anyhow::Error::context".parse().unwrap(),
+                &LinkOptions::new().relative_to("/"),
+            )
+            .unwrap();
+        assert_eq!("/", link.base);
+        assert_eq!("/anyhow/struct.Error.html#method.context", link.to_string());
+
+        assert!(index.root.as_deref().unwrap().starts_with("file:///"));
+        assert_eq!(Some(""), index.resource_suffix.as_deref());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_root_url() {
+        assert_eq!("target/doc", root_url(Path::new("target/doc/")));
+    }
+}
@@ -0,0 +1,108 @@
+//! Extension point for the discovery of the search index URL from a docs page.
+
+use crate::{
+    crates,
+    error::{Error, Result},
+    DocHost, Version,
+};
+
+/// Strategy to find the location of the search index in the content of a docs page, as downloaded
+/// from [`SearchPage::url`](crate::SearchPage::url).
+///
+/// The built-in [`DocsRsLocator`] handles the pages of docs.rs and the stdlib docs. Custom
+/// strategies can be passed to [`SearchPage::find_index_with`](crate::SearchPage::find_index_with)
+/// for nonstandard hosting setups. Any closure with the same signature as [`Self::locate`]
+/// implements this trait as well.
+///
+/// ```rust
+/// use docsearch::{IndexLocation, LocatorContext, Version};
+///
+/// let state = docsearch::start_search("anyhow", "1.0.72".parse().unwrap());
+/// let state = state
+///     .find_index_with(
+///         &|ctx: &LocatorContext<'_>, _body: &str| {
+///             Ok(IndexLocation {
+///                 version: ctx.version.clone(),
+///                 url: format!("https://docs.example.com/{}/search-index.js", ctx.name),
+///             })
+///         },
+///         "<html></html>",
+///     )
+///     .unwrap();
+///
+/// assert_eq!("https://docs.example.com/anyhow/search-index.js", state.url());
+/// ```
+pub trait IndexLocator {
+    /// Find the search index URL in the page content, and optionally refine the version of the
+    /// crate if the page contains more precise information than the context.
+    fn locate(&self, ctx: &LocatorContext<'_>, body: &str) -> Result<IndexLocation>;
+}
+
+impl<F> IndexLocator for F
+where
+    F: Fn(&LocatorContext<'_>, &str) -> Result<IndexLocation>,
+{
+    fn locate(&self, ctx: &LocatorContext<'_>, body: &str) -> Result<IndexLocation> {
+        self(ctx, body)
+    }
+}
+
+/// Information about the current search, that is passed to an [`IndexLocator`].
+#[derive(Clone, Copy, Debug)]
+pub struct LocatorContext<'a> {
+    /// Name of the searched crate.
+    pub name: &'a str,
+    /// Version of the crate, as far as it is resolved at this point.
+    pub version: &'a Version,
+    /// Whether the search is for the standard library.
+    pub std: bool,
+    /// Hosts that the documentation is retrieved from.
+    pub host: &'a DocHost,
+    /// Custom documentation root, if the search was started with
+    /// [`start_search_at`](crate::start_search_at). It takes precedence over the [`Self::host`].
+    pub root: Option<&'a str>,
+    /// URL of the page whose content is inspected.
+    pub url: &'a str,
+}
+
+/// Location of the search index, as found by an [`IndexLocator`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexLocation {
+    /// Version of the crate, possibly more concrete than the one of the [`LocatorContext`].
+    pub version: Version,
+    /// Full URL to the search index file.
+    pub url: String,
+}
+
+/// Default [`IndexLocator`] for docs.rs and the stdlib docs, that extracts the index URL from the
+/// `rustdoc-vars` element and resolves version requirements from the docs.rs header.
+///
+/// For searches with a custom documentation root, the index is expected right inside that root and
+/// the version stays as requested.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DocsRsLocator;
+
+impl IndexLocator for DocsRsLocator {
+    fn locate(&self, ctx: &LocatorContext<'_>, body: &str) -> Result<IndexLocation> {
+        if let Some(root) = ctx.root {
+            let index_path = crates::find_url(body).ok_or_else(|| Error::IndexNotFound {
+                url: ctx.url.to_owned(),
+            })?;
+            return Ok(IndexLocation {
+                version: ctx.version.clone(),
+                url: format!("{root}/{index_path}"),
+            });
+        }
+
+        let (version, url) = crates::find_index_url(
+            ctx.host,
+            ctx.std,
+            ctx.name,
+            ctx.version.clone(),
+            ctx.url,
+            body,
+        )?;
+
+        Ok(IndexLocation { version, url })
+    }
+}
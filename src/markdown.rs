@@ -0,0 +1,208 @@
+//! Resolution of intra-doc style references like ``[`anyhow::Result`]`` in Markdown text.
+
+use crate::{crates, Index, ItemType, LinkOptions, LinkStyle, SimplePath};
+
+/// Single reference like ``[`anyhow::Result`]``, that isn't a link yet.
+struct Reference<'a> {
+    /// Byte range of the whole reference in the text, including the brackets.
+    start: usize,
+    end: usize,
+    /// The path between the backticks.
+    path: &'a str,
+}
+
+/// Find all references in the text, skipping over fenced code blocks.
+fn references(text: &str) -> Vec<Reference<'_>> {
+    let mut refs = Vec::new();
+    let mut fence = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker));
+
+        match (fence, marker) {
+            (None, Some(marker)) => fence = Some(marker),
+            (Some(open), Some(marker)) if open == marker => fence = None,
+            (None, None) => refs.extend(line_references(line, offset)),
+            _ => {}
+        }
+
+        offset += line.len();
+    }
+
+    refs
+}
+
+/// Find all references in a single line, that is located at the offset in the whole text.
+fn line_references(line: &str, offset: usize) -> Vec<Reference<'_>> {
+    let mut refs = Vec::new();
+    let mut pos = 0;
+
+    while let Some(start) = line[pos..].find("[`").map(|start| pos + start) {
+        let inner = start + 2;
+        let end = match line[inner..].find("`]") {
+            Some(end) => inner + end + 2,
+            None => break,
+        };
+        let path = &line[inner..end - 2];
+        pos = inner;
+
+        // References followed by a link destination, label or colon are already links (or link
+        // definitions) and must not be touched.
+        let is_link = line[end..].starts_with(['(', '[', ':']);
+
+        if path.is_empty() || path.contains('`') || is_link {
+            continue;
+        }
+
+        refs.push(Reference {
+            start: offset + start,
+            end: offset + end,
+            path,
+        });
+        pos = end;
+    }
+
+    refs
+}
+
+/// Find the paths of all intra-doc style references like ``[`tokio::spawn`]`` in the Markdown
+/// text, which are not yet links. This allows to determine the indexes that are needed for
+/// [`rewrite_markdown`].
+///
+/// ```rust
+/// let paths = docsearch::markdown_references("Use [`tokio::spawn`] or [`std::thread::spawn`].")
+///     .map(|path| path.into_inner())
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(vec!["tokio::spawn", "std::thread::spawn"], paths);
+/// ```
+pub fn markdown_references(text: &str) -> impl Iterator<Item = SimplePath> + '_ {
+    references(text)
+        .into_iter()
+        .filter_map(|reference| reference.path.parse().ok())
+}
+
+/// Rewrite all intra-doc style references like ``[`tokio::spawn`]`` in the Markdown text into
+/// links to their docs, like ``[`tokio::spawn`](https://docs.rs/...)``.
+///
+/// Each reference is resolved with the index of the same crate name. References without a
+/// matching index or that point to unknown items are kept as is, as well as references in fenced
+/// code blocks and references that are already links.
+///
+/// ```no_run
+/// # let tokio: docsearch::Index = unimplemented!();
+/// # let std: docsearch::Index = unimplemented!();
+/// let readme = docsearch::rewrite_markdown(
+///     "Use [`tokio::spawn`] or [`std::thread::spawn`].",
+///     &[tokio, std],
+/// );
+/// ```
+#[must_use]
+pub fn rewrite_markdown(text: &str, indexes: &[Index]) -> String {
+    let options = LinkOptions::new().style(LinkStyle::Markdown);
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for reference in references(text) {
+        let path = match reference.path.parse::<SimplePath>() {
+            Ok(path) => path,
+            Err(_) => continue,
+        };
+        let link = indexes
+            .iter()
+            .filter(|index| crates::crate_name(&index.name) == path.crate_name())
+            .find_map(|index| index.find_link_with(&path, &options));
+
+        if let Some(link) = link {
+            out.push_str(&text[last..reference.start]);
+            out.push_str(&link);
+            last = reference.end;
+        }
+    }
+
+    out.push_str(&text[last..]);
+    out
+}
+
+pub(crate) fn intra_doc_link(index: &Index, path: &SimplePath) -> Option<String> {
+    if path.is_crate_only() {
+        return Some(path.to_string());
+    }
+
+    let kind = ItemType::from_link(index.lookup(path)?)?;
+
+    // Other items at the same path are either kept as shadowed links, or revealed by the links of
+    // items inside a module at the same path, which is in a different namespace.
+    let key = path.index_path();
+    let module_dir = format!("{}/", key.replace("::", "/"));
+    let is_ambiguous = index.shadowed.contains_key(key.as_ref())
+        || kind != ItemType::Module
+            && index
+                .mapping
+                .values()
+                .any(|link| link.starts_with(&module_dir));
+
+    Some(if is_ambiguous {
+        format!("{}@{path}", kind.disambiguator())
+    } else {
+        path.to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{test_util, SearchIndex};
+
+    #[test]
+    fn test_rewrite_markdown() {
+        let index = test_util::anyhow_index();
+
+        let text = "\
+Use [`anyhow::Result`] and [`anyhow::Context`], but not [`anyhow::Missing`].
+
+```rust
+// [`anyhow::Result`]
+```
+
+Already linked: [`anyhow::Error`](https://example.com) and [`anyhow::Error`][error].
+";
+
+        insta::assert_snapshot!(rewrite_markdown(text, &[index]));
+    }
+
+    #[test]
+    fn test_intra_doc_link() {
+        let fixture = fs::read_to_string("src/index/fixtures/syn-2.0.8.js").unwrap();
+        let index = SearchIndex::from_parts("syn", "2.0.8".parse().unwrap(), "")
+            .transform_index(&fixture)
+            .unwrap();
+        let link = |path: &str| intra_doc_link(&index, &path.parse().unwrap());
+
+        assert_eq!(Some("syn".to_owned()), link("syn"));
+        assert_eq!(Some("syn::Ident".to_owned()), link("syn::Ident"));
+        assert_eq!(Some("syn::token".to_owned()), link("syn::token"));
+        assert_eq!(Some("fn@syn::parse".to_owned()), link("syn::parse"));
+        assert_eq!(None, link("syn::Missing"));
+        assert_eq!(Some("syn::Token".to_owned()), link("syn::Token!"));
+        assert_eq!(None, link("syn::parse!"));
+        assert_eq!(Some("mod@syn::parse".to_owned()), link("mod@syn::parse"));
+        assert_eq!(Some("fn@syn::parse".to_owned()), link("fn@syn::parse"));
+        assert_eq!(None, link("struct@syn::parse"));
+    }
+
+    #[test]
+    fn test_markdown_references() {
+        let paths = markdown_references("[`a::b`] [`c`](x) [`not a path`] [`d::e`]")
+            .map(SimplePath::into_inner)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["a::b", "d::e"], paths);
+    }
+}
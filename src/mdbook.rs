@@ -0,0 +1,166 @@
+//! Implementation of the [mdBook preprocessor](https://rust-lang.github.io/mdBook/for_developers/preprocessors.html)
+//! protocol, that turns intra-doc style references like ``[`anyhow::Result`]`` in the chapters of
+//! a book into links to their docs.
+//!
+//! The module is IO-free like the rest of the crate. A preprocessor binary reads the input from
+//! stdin, loads the needed indexes (for example from a cache of serialized [`Index`]es) and
+//! writes the processed book back to stdout.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::io::Read;
+//!
+//! use docsearch::{mdbook, Index};
+//!
+//! # fn load_index(name: &str) -> Index { unimplemented!() }
+//! let mut args = std::env::args().skip(1);
+//! if args.next().as_deref() == Some("supports") {
+//!     let renderer = args.next().unwrap_or_default();
+//!     std::process::exit(if mdbook::supports_renderer(&renderer) { 0 } else { 1 });
+//! }
+//!
+//! let mut input = String::new();
+//! std::io::stdin().read_to_string(&mut input).unwrap();
+//!
+//! let indexes = mdbook::referenced_crates(&input)
+//!     .unwrap()
+//!     .iter()
+//!     .map(|name| load_index(name))
+//!     .collect::<Vec<_>>();
+//!
+//! print!("{}", mdbook::preprocess(&input, &indexes).unwrap());
+//! ```
+
+use std::collections::BTreeSet;
+
+use serde::de::IgnoredAny;
+use serde_json::Value;
+
+use crate::{error::Result, markdown, Index};
+
+/// Check whether the preprocessor supports the given renderer, as asked by mdBook with the
+/// `supports <renderer>` sub-command. The generated links are plain Markdown, so all renderers
+/// are supported.
+#[must_use]
+pub fn supports_renderer(_renderer: &str) -> bool {
+    true
+}
+
+/// Collect the names of all crates that are referenced in the chapters of the book. These are
+/// the indexes that need to be passed to [`preprocess`].
+///
+/// The input is the JSON data that mdBook passes to the preprocessor on stdin.
+pub fn referenced_crates(input: &str) -> Result<BTreeSet<String>> {
+    let (_, mut book) = serde_json::from_str::<(IgnoredAny, Value)>(input)?;
+    let mut crates = BTreeSet::new();
+
+    visit_chapters(&mut book, &mut |content| {
+        crates.extend(
+            markdown::markdown_references(content).map(|path| path.crate_name().to_owned()),
+        );
+    });
+
+    Ok(crates)
+}
+
+/// Resolve all references in the chapters of the book with the given indexes and return the
+/// processed book as JSON, to be written back to mdBook on stdout. References to items that
+/// aren't found in any of the indexes are kept as is.
+///
+/// The input is the JSON data that mdBook passes to the preprocessor on stdin.
+pub fn preprocess(input: &str, indexes: &[Index]) -> Result<String> {
+    let (_, mut book) = serde_json::from_str::<(IgnoredAny, Value)>(input)?;
+
+    visit_chapters(&mut book, &mut |content| {
+        *content = markdown::rewrite_markdown(content, indexes);
+    });
+
+    serde_json::to_string(&book).map_err(Into::into)
+}
+
+/// Call the visitor for the content of every chapter in the book, including nested ones.
+fn visit_chapters(book: &mut Value, visit: &mut impl FnMut(&mut String)) {
+    fn visit_items(items: &mut Value, visit: &mut impl FnMut(&mut String)) {
+        let items = match items.as_array_mut() {
+            Some(items) => items,
+            None => return,
+        };
+
+        for chapter in items.iter_mut().filter_map(|item| item.get_mut("Chapter")) {
+            if let Some(Value::String(content)) = chapter.get_mut("content") {
+                visit(content);
+            }
+
+            if let Some(sub_items) = chapter.get_mut("sub_items") {
+                visit_items(sub_items, visit);
+            }
+        }
+    }
+
+    if let Some(sections) = book.get_mut("sections") {
+        visit_items(sections, visit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    const INPUT: &str = r#"[
+        {"root": "/book", "config": {}, "renderer": "html", "mdbook_version": "0.4.35"},
+        {"sections": [
+            {"Chapter": {
+                "name": "Intro",
+                "content": "See [`anyhow::Result`].",
+                "number": [1],
+                "sub_items": [
+                    {"Chapter": {
+                        "name": "Errors",
+                        "content": "See [`anyhow::Error`] and [`std::vec::Vec`].",
+                        "number": [1, 1],
+                        "sub_items": [],
+                        "path": "errors.md",
+                        "source_path": "errors.md",
+                        "parent_names": ["Intro"]
+                    }}
+                ],
+                "path": "intro.md",
+                "source_path": "intro.md",
+                "parent_names": []
+            }},
+            "Separator"
+        ], "__non_exhaustive": null}
+    ]"#;
+
+    #[test]
+    fn test_referenced_crates() {
+        let crates = referenced_crates(INPUT).unwrap();
+        assert_eq!(
+            vec!["anyhow", "std"],
+            crates.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_preprocess() {
+        let index = test_util::anyhow_index();
+
+        let output = preprocess(INPUT, &[index]).unwrap();
+        let book = serde_json::from_str::<Value>(&output).unwrap();
+        let intro = &book["sections"][0]["Chapter"];
+
+        assert_eq!(
+            "See [`anyhow::Result`](https://docs.rs/anyhow/1.0.72/anyhow/type.Result.html).",
+            intro["content"]
+        );
+        assert_eq!(
+            "See [`anyhow::Error`](https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html) and \
+             [`std::vec::Vec`].",
+            intro["sub_items"][0]["Chapter"]["content"]
+        );
+        assert_eq!("Separator", book["sections"][1]);
+        assert!(book.get("__non_exhaustive").is_some());
+    }
+}
@@ -0,0 +1,242 @@
+//! Additional crate information from the crates.io API, that can be attached to an
+//! [`Index`](crate::Index), as well as suggestions for crate names.
+
+use std::fmt::Write;
+
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{
+    error::{Error, Result},
+    Request, Version,
+};
+
+/// Base URL for the crates.io API.
+const CRATES_IO_API_URL: &str = "https://crates.io/api/v1/crates";
+
+/// General information about a crate, as published on crates.io. Useful to show a crate card
+/// together with the doc links.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CrateMetadata {
+    /// Short description of the crate.
+    pub description: Option<String>,
+    /// URL of the crate's source repository.
+    pub repository: Option<String>,
+    /// URL of the crate's homepage.
+    pub homepage: Option<String>,
+    /// License of the crate as SPDX expression, like `MIT OR Apache-2.0`.
+    pub license: Option<String>,
+}
+
+/// Create the URL to the crates.io API for the given crate.
+pub(crate) fn get_url(name: &str) -> String {
+    format!("{CRATES_IO_API_URL}/{name}")
+}
+
+/// Parse the response of the crates.io API. The license is taken from the requested version, or
+/// the newest version if it's not a concrete one.
+pub(crate) fn parse(version: &Version, body: &str) -> Result<CrateMetadata> {
+    #[derive(Deserialize)]
+    struct Response {
+        #[serde(rename = "crate")]
+        krate: Crate,
+        #[serde(default)]
+        versions: Vec<CrateVersion>,
+    }
+
+    #[derive(Deserialize)]
+    struct Crate {
+        description: Option<String>,
+        repository: Option<String>,
+        homepage: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct CrateVersion {
+        #[serde(deserialize_with = "crate::version::deserialize_semver")]
+        num: semver::Version,
+        license: Option<String>,
+    }
+
+    let resp = serde_json::from_str::<Response>(body)?;
+    let license = match version {
+        Version::SemVer(v) => resp.versions.into_iter().find(|cv| cv.num == *v),
+        _ => resp.versions.into_iter().next(),
+    }
+    .and_then(|cv| cv.license);
+
+    Ok(CrateMetadata {
+        description: resp.krate.description.map(|d| d.trim().to_owned()),
+        repository: resp.krate.repository,
+        homepage: resp.krate.homepage,
+        license,
+    })
+}
+
+/// Find crates with similar names on crates.io, for when the searched crate doesn't exist. This
+/// allows to suggest alternatives like _"did you mean `serde_json`?"_.
+#[must_use]
+pub fn suggest_crates(name: &str) -> CrateSuggestions<'_> {
+    let mut url = format!("{CRATES_IO_API_URL}?per_page=20&q=");
+    for b in name.bytes() {
+        if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' {
+            url.push(b as char);
+        } else {
+            write!(url, "%{b:02X}").ok();
+        }
+    }
+
+    CrateSuggestions { name, url }
+}
+
+/// Single state to retrieve crate name suggestions. Use the [`Self::url`] function to get the URL
+/// of the crates.io search to download. Its content must then be passed to [`Self::parse`].
+pub struct CrateSuggestions<'a> {
+    name: &'a str,
+    url: String,
+}
+
+impl CrateSuggestions<'_> {
+    /// Maximum edit distance between names, relative to the searched name's length, to be
+    /// considered similar.
+    const MAX_DISTANCE_RATIO: f64 = 0.5;
+
+    /// URL to the crates.io search that should be retrieved and passed to [`Self::parse`].
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Full description of the request to retrieve the content for [`Self::parse`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        Request::get(&self.url)
+    }
+
+    /// Parse the search results downloaded from [`Self::url`] and return the names of crates
+    /// similar to the searched one, with the closest matches first.
+    ///
+    /// Names are compared case-insensitive and without a difference between `-` and `_`.
+    pub fn parse(self, body: &str) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct Response {
+            crates: Vec<Crate>,
+        }
+
+        #[derive(Deserialize)]
+        struct Crate {
+            name: String,
+        }
+
+        let normalize = |name: &str| name.to_lowercase().replace('-', "_");
+
+        let searched = normalize(self.name);
+        #[allow(clippy::cast_precision_loss)]
+        let max_distance = (searched.chars().count() as f64 * Self::MAX_DISTANCE_RATIO).ceil();
+
+        let mut suggestions = serde_json::from_str::<Response>(body)
+            .map_err(|e| Error::from(e).with_url(&self.url))?
+            .crates
+            .into_iter()
+            .filter_map(|c| {
+                let distance = edit_distance(&searched, &normalize(&c.name));
+                #[allow(clippy::cast_precision_loss)]
+                (distance as f64 <= max_distance).then_some((distance, c.name))
+            })
+            .collect::<Vec<_>>();
+
+        // Stable sort, to keep the relevance order of crates.io for equal distances.
+        suggestions.sort_by_key(|(distance, _)| *distance);
+
+        Ok(suggestions.into_iter().map(|(_, name)| name).collect())
+    }
+}
+
+/// Calculate the [Levenshtein distance](https://en.wikipedia.org/wiki/Levenshtein_distance)
+/// between two strings, the minimum number of single character edits to change one into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            let next = (prev + cost).min(row[j] + 1).min(row[j + 1] + 1);
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let body = r#"{
+            "crate": {
+                "name": "anyhow",
+                "description": "Flexible concrete Error type built on std::error::Error\n",
+                "repository": "https://github.com/dtolnay/anyhow",
+                "homepage": null
+            },
+            "versions": [
+                {"num": "1.0.72", "license": "MIT OR Apache-2.0"},
+                {"num": "1.0.0", "license": "MIT"}
+            ]
+        }"#;
+
+        let metadata = parse(&Version::Latest, body).unwrap();
+        assert_eq!(
+            CrateMetadata {
+                description: Some(
+                    "Flexible concrete Error type built on std::error::Error".to_owned()
+                ),
+                repository: Some("https://github.com/dtolnay/anyhow".to_owned()),
+                homepage: None,
+                license: Some("MIT OR Apache-2.0".to_owned()),
+            },
+            metadata
+        );
+
+        let metadata = parse(&"1.0.0".parse().unwrap(), body).unwrap();
+        assert_eq!(Some("MIT"), metadata.license.as_deref());
+    }
+
+    #[test]
+    fn test_suggest_crates() {
+        let state = suggest_crates("serde json");
+        assert_eq!(
+            "https://crates.io/api/v1/crates?per_page=20&q=serde%20json",
+            state.url()
+        );
+
+        let state = suggest_crates("serde-jsn");
+        let body = r#"{"crates": [
+            {"name": "serde_json_lenient"},
+            {"name": "serde_json"},
+            {"name": "serde-jsonrc"},
+            {"name": "tokio"}
+        ]}"#;
+        assert_eq!(
+            vec!["serde_json", "serde-jsonrc"],
+            state.parse(body).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("anyhow", "anyhow"));
+        assert_eq!(1, edit_distance("anyhow", "anyhw"));
+        assert_eq!(3, edit_distance("kitten", "sitting"));
+        assert_eq!(5, edit_distance("", "tokio"));
+    }
+}
@@ -0,0 +1,180 @@
+//! Lookup of methods, that can be defined on a type directly or come from a trait.
+
+use std::collections::HashMap;
+
+use crate::{Index, ItemType, LinkOptions, SimplePath};
+
+/// Link to a single method, as returned by [`Index::find_method_candidates`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MethodLink {
+    /// Where the method is defined.
+    pub source: MethodSource,
+    /// Full URL to the method's docs.
+    pub url: String,
+}
+
+/// Origin of a method, either the type itself or a trait.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MethodSource {
+    /// Inherent method, defined in an `impl` block of the type itself.
+    Inherent,
+    /// Method of the trait with the given path, like `anyhow::Context`.
+    Trait(String),
+}
+
+/// Lookup of method candidates in an [`Index`], as created by [`Index::method_index`]. Collecting
+/// the trait methods requires a walk over the whole mapping of the crate, which is done only once
+/// here instead of for every call to [`Index::find_method_candidates`].
+#[derive(Clone, Debug)]
+pub struct MethodIndex<'a> {
+    index: &'a Index,
+    /// Methods of all traits by their name, with the path of the trait and the link.
+    traits: HashMap<&'a str, Vec<(&'a str, &'a str)>>,
+}
+
+impl<'a> MethodIndex<'a> {
+    pub(crate) fn new(index: &'a Index) -> Self {
+        let mut traits = HashMap::<_, Vec<_>>::new();
+
+        for (path, link) in &index.mapping {
+            if !is_method(link) || !is_trait_page(link) {
+                continue;
+            }
+
+            if let Some((trait_, method)) = path.rsplit_once("::") {
+                traits
+                    .entry(method)
+                    .or_default()
+                    .push((trait_, link.as_str()));
+            }
+        }
+
+        Self { index, traits }
+    }
+
+    /// Find the candidates for a method like `anyhow::Error::context`. See
+    /// [`Index::find_method_candidates`] for the details of the heuristic.
+    #[must_use]
+    pub fn find_candidates(&self, path: &SimplePath) -> Vec<MethodLink> {
+        self.find_candidates_with(path, &LinkOptions::default())
+    }
+
+    /// Find the candidates for a method like [`Self::find_candidates`], but customize the
+    /// generated links with the given options, like [`Index::find_method_candidates_with`].
+    #[must_use]
+    pub fn find_candidates_with(
+        &self,
+        path: &SimplePath,
+        options: &LinkOptions,
+    ) -> Vec<MethodLink> {
+        let parent = match path.parent() {
+            Some(parent) => parent.index_path().into_owned(),
+            None => return Vec::new(),
+        };
+        let name = path.index_path();
+        let name = name.rsplit("::").next().unwrap_or_default();
+
+        // Methods of trait implementations are listed for the type as well. If the type doesn't
+        // have a method of that name at all, no trait can provide it either.
+        let direct = match self.index.mapping.get(&format!("{parent}::{name}")) {
+            Some(link) if is_method(link) => link,
+            _ => return Vec::new(),
+        };
+
+        let formatter = self.index.link_formatter(options);
+
+        let direct = MethodLink {
+            source: if is_trait_page(direct) {
+                MethodSource::Trait(parent.clone())
+            } else {
+                MethodSource::Inherent
+            },
+            url: formatter.format(direct),
+        };
+
+        // The search index doesn't record which trait a method of the type comes from. Therefore,
+        // all traits of the crate that have a method with the same name are considered.
+        let traits = self
+            .traits
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter(|(trait_, _)| *trait_ != parent)
+            .map(|(trait_, link)| MethodLink {
+                source: MethodSource::Trait((*trait_).to_owned()),
+                url: formatter.format(link),
+            });
+
+        std::iter::once(direct).chain(traits).collect()
+    }
+}
+
+/// Whether the link points to a method, either with or without default implementation.
+fn is_method(link: &str) -> bool {
+    matches!(
+        ItemType::from_link(link),
+        Some(ItemType::Method | ItemType::TyMethod)
+    )
+}
+
+/// Whether the link points into the docs page of a trait.
+fn is_trait_page(link: &str) -> bool {
+    let page = link.split_once('#').map_or(link, |(page, _)| page);
+    page.rsplit('/')
+        .next()
+        .map_or(false, |file| file.starts_with("trait."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn test_find() {
+        let index = test_util::anyhow_index();
+        let methods = MethodIndex::new(&index);
+        let find = |path: &str| methods.find_candidates(&path.parse().unwrap());
+
+        assert_eq!(
+            vec![
+                MethodLink {
+                    source: MethodSource::Inherent,
+                    url: "https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html#method.context"
+                        .to_owned(),
+                },
+                MethodLink {
+                    source: MethodSource::Trait("anyhow::Context".to_owned()),
+                    url: "https://docs.rs/anyhow/1.0.72/anyhow/trait.Context.html#tymethod.context"
+                        .to_owned(),
+                },
+            ],
+            find("anyhow::Error::context")
+        );
+        assert_eq!(
+            vec![MethodLink {
+                source: MethodSource::Trait("anyhow::Context".to_owned()),
+                url: "https://docs.rs/anyhow/1.0.72/anyhow/trait.Context.html#tymethod.context"
+                    .to_owned(),
+            }],
+            find("anyhow::Context::context")
+        );
+        assert!(find("anyhow::Chain::context").is_empty());
+        assert!(find("anyhow::Error").is_empty());
+        assert!(find("anyhow").is_empty());
+
+        let options = LinkOptions::new().relative_to("../doc");
+        assert_eq!(
+            vec![
+                "../doc/anyhow/struct.Error.html#method.context",
+                "../doc/anyhow/trait.Context.html#tymethod.context",
+            ],
+            methods
+                .find_candidates_with(&"anyhow::Error::context".parse().unwrap(), &options)
+                .into_iter()
+                .map(|method| method.url)
+                .collect::<Vec<_>>()
+        );
+    }
+}
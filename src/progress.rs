@@ -0,0 +1,79 @@
+//! Progress reporting for the transitions between the search states.
+
+use crate::{IndexFormat, Version};
+
+/// Step of a search, that was reached by a state transition. These are passed to the observer of
+/// [`SearchPage::observe`](crate::SearchPage::observe) or
+/// [`SearchIndex::observe`](crate::SearchIndex::observe), for example to render progress in CLIs
+/// while the multi-megabyte index of the stdlib is processed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Progress<'a> {
+    /// The docs page of the crate was downloaded and is searched for the index.
+    PageLocated {
+        /// URL of the docs page.
+        url: &'a str,
+    },
+    /// The URL of the search index was found, either in the docs page or from a known resource
+    /// suffix.
+    IndexUrlFound {
+        /// URL of the search index.
+        url: &'a str,
+        /// Version of the crate, that the index belongs to.
+        version: &'a Version,
+    },
+    /// The content of the search index was parsed.
+    IndexParsed {
+        /// Format of the index content.
+        format: IndexFormat,
+    },
+    /// The items of the crate were mapped to their links, completing the search.
+    ItemsMapped {
+        /// Number of items in the resulting [`Index`](crate::Index).
+        count: usize,
+    },
+}
+
+/// Callback that receives the [`Progress`] of a search.
+pub(crate) type Observer = Box<dyn Fn(&Progress<'_>) + Send + Sync>;
+
+/// Pass the progress to the observer, if there is one.
+pub(crate) fn notify(observer: Option<&Observer>, progress: &Progress<'_>) {
+    if let Some(observer) = observer {
+        observer(progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_observe() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&events);
+        let fixture = fs::read_to_string("src/index/fixtures/anyhow-1.0.72.js").unwrap();
+
+        let index = crate::start_search_at("https://docs.example.com", "anyhow", Version::Latest)
+            .observe(move |progress| recorder.lock().unwrap().push(format!("{progress:?}")))
+            .find_index(r#"<div id="rustdoc-vars" data-resource-suffix="-1.70.0"></div>"#)
+            .unwrap()
+            .transform_index(&fixture)
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                r#"PageLocated { url: "https://docs.example.com/anyhow/index.html" }"#.to_owned(),
+                r#"IndexUrlFound { url: "https://docs.example.com/search-index-1.70.0.js", version: Latest }"#.to_owned(),
+                "IndexParsed { format: V3 }".to_owned(),
+                format!("ItemsMapped {{ count: {} }}", index.mapping.len()),
+            ],
+            *events.lock().unwrap()
+        );
+    }
+}
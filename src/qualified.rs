@@ -0,0 +1,171 @@
+//! Parsing of paths in the fully-qualified syntax, like `<Vec<u8> as IntoIterator>::into_iter`.
+
+use std::str::FromStr;
+
+use crate::{error::ParseError, SimplePath};
+
+/// Path in the [fully-qualified syntax](https://doc.rust-lang.org/stable/reference/paths.html#qualified-paths)
+/// like `<anyhow::Error as std::error::Error>::source`, as it often appears in compiler
+/// diagnostics.
+///
+/// The item can either be the trait's item or an inherent item of the type with the same name,
+/// so the path resolves to multiple [`Self::candidates`].
+///
+/// ```rust
+/// use docsearch::QualifiedPath;
+///
+/// let path = "<anyhow::Error as std::error::Error>::source"
+///     .parse::<QualifiedPath>()
+///     .unwrap();
+///
+/// let candidates = path
+///     .candidates()
+///     .into_iter()
+///     .map(|path| path.into_inner())
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     vec!["std::error::Error::source", "anyhow::Error::source"],
+///     candidates
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QualifiedPath {
+    /// The type that the item belongs to, without generic arguments. This is [`None`] for types
+    /// that aren't simple paths, like `[T]` or `&str`.
+    pub self_ty: Option<SimplePath>,
+    /// The trait that the item is part of, if given with `as Trait`.
+    pub trait_: Option<SimplePath>,
+    /// Name of the item after the qualified part, like `source`.
+    pub item: String,
+}
+
+impl QualifiedPath {
+    /// All simple paths that this path can refer to, in order of preference. The trait item comes
+    /// first, as it was explicitly named, followed by the inherent item of the type.
+    #[must_use]
+    pub fn candidates(&self) -> Vec<SimplePath> {
+        [&self.trait_, &self.self_ty]
+            .into_iter()
+            .flatten()
+            .filter_map(|base| format!("{base}::{}", self.item).parse().ok())
+            .collect()
+    }
+}
+
+impl FromStr for QualifiedPath {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let inner = s
+            .strip_prefix('<')
+            .ok_or(ParseError::InvalidQualifiedPath)?;
+        let close = find_closing(inner).ok_or(ParseError::InvalidQualifiedPath)?;
+        let item = inner[close + 1..]
+            .strip_prefix("::")
+            .ok_or(ParseError::InvalidQualifiedPath)?;
+        let inner = &inner[..close];
+
+        let (self_ty, trait_) = match split_as(inner) {
+            Some((self_ty, trait_)) => (self_ty, Some(trait_)),
+            None => (inner, None),
+        };
+
+        let self_ty = strip_generics(self_ty).parse().ok();
+        let trait_ = trait_
+            .map(|trait_| strip_generics(trait_).parse::<SimplePath>())
+            .transpose()?;
+
+        if self_ty.is_none() && trait_.is_none() {
+            return Err(ParseError::InvalidQualifiedPath);
+        }
+
+        // Validate the item segments, without the parts before.
+        item.parse::<SimplePath>()?;
+
+        Ok(Self {
+            self_ty,
+            trait_,
+            item: item.to_owned(),
+        })
+    }
+}
+
+/// Find the position of the `>` that closes the already opened `<`.
+fn find_closing(value: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+
+    for (pos, c) in value.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if depth == 0 => return Some(pos),
+            '>' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Split the content of the qualified part at the top-level ` as `, into the type and the trait.
+fn split_as(value: &str) -> Option<(&str, &str)> {
+    let mut depth = 0_usize;
+
+    for (pos, c) in value.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            ' ' if depth == 0 && value[pos..].starts_with(" as ") => {
+                return Some((value[..pos].trim(), value[pos + 4..].trim()));
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Remove the generic arguments from a type, like `Vec<u8>` to `Vec`.
+fn strip_generics(value: &str) -> &str {
+    value.split_once('<').map_or(value, |(path, _)| path).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(value: &str) -> Vec<String> {
+        value
+            .parse::<QualifiedPath>()
+            .unwrap()
+            .candidates()
+            .into_iter()
+            .map(SimplePath::into_inner)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            vec![
+                "core::iter::IntoIterator::into_iter",
+                "alloc::vec::Vec::into_iter"
+            ],
+            candidates("<alloc::vec::Vec<u8> as core::iter::IntoIterator>::into_iter")
+        );
+        assert_eq!(
+            vec!["core::convert::From::from"],
+            candidates("<&str as core::convert::From<char>>::from")
+        );
+        assert_eq!(
+            vec!["anyhow::Error::msg"],
+            candidates("<anyhow::Error>::msg")
+        );
+
+        assert!("anyhow::Error::msg".parse::<QualifiedPath>().is_err());
+        assert!("<anyhow::Error as >::msg".parse::<QualifiedPath>().is_err());
+        assert!("<anyhow::Error>".parse::<QualifiedPath>().is_err());
+        assert!("<[u8]>::len".parse::<QualifiedPath>().is_err());
+    }
+}
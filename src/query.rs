@@ -0,0 +1,246 @@
+//! Prepared queries, that are normalized once and can be run against many indexes.
+
+use std::str::FromStr;
+
+use crate::{error::ParseError, is_in_module, Disambiguator, Index, LinkOptions, SimplePath};
+
+/// Query for an item that is parsed and normalized once, and can then be run against any number
+/// of indexes with [`Index::find_query`]. This avoids repeating the same work for each index,
+/// for example in services that check the same query against a whole registry of crates.
+///
+/// Besides the [disambiguator](SimplePath::disambiguator) of the path, a query can ignore the
+/// case of the path and expand aliases, like a renamed dependency.
+///
+/// ```rust
+/// use docsearch::Query;
+///
+/// let query = "json::value".parse::<Query>()
+///     .unwrap()
+///     .case_insensitive(true)
+///     .alias("json", "serde_json");
+///
+/// assert_eq!(vec!["json", "serde_json"], query.crate_names().collect::<Vec<_>>());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Query {
+    /// The path as originally given.
+    path: SimplePath,
+    /// Paths in the form of the index mapping, in order of preference. The first one is always
+    /// the original path, followed by expanded aliases.
+    keys: Vec<String>,
+    /// Lower-cased forms of the keys, if the case of paths is ignored.
+    folded: Option<Vec<String>>,
+}
+
+impl Query {
+    /// Create a new query for the given path, that matches the exact path only.
+    #[must_use]
+    pub fn new(path: SimplePath) -> Self {
+        let keys = vec![path.index_path().into_owned()];
+
+        Self {
+            path,
+            keys,
+            folded: None,
+        }
+    }
+
+    /// Ignore the case of the path when matching it against the items of an index, so that
+    /// `anyhow::error` finds `anyhow::Error`. Exact matches are still preferred.
+    #[must_use]
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.folded = enabled.then(|| self.keys.iter().map(|key| key.to_lowercase()).collect());
+        self
+    }
+
+    /// Additionally match the path with the prefix `from` replaced by `to`, like `json` by
+    /// `serde_json` for a dependency that was renamed in a project's `Cargo.toml`. The prefix
+    /// must cover full segments and can be a crate name or a longer path.
+    #[must_use]
+    pub fn alias(mut self, from: &str, to: &str) -> Self {
+        let original = &self.keys[0];
+
+        if is_in_module(original, from) {
+            let key = format!("{to}{}", &original[from.len()..]);
+            if !self.keys.contains(&key) {
+                if let Some(folded) = &mut self.folded {
+                    folded.push(key.to_lowercase());
+                }
+                self.keys.push(key);
+            }
+        }
+
+        self
+    }
+
+    /// The path as originally given.
+    #[must_use]
+    pub fn path(&self) -> &SimplePath {
+        &self.path
+    }
+
+    /// Names of all crates, whose indexes can contain a match for this query. Other indexes can
+    /// be skipped when running the query.
+    pub fn crate_names(&self) -> impl Iterator<Item = &str> {
+        self.keys.iter().map(|key| crate_of(key))
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self::new)
+    }
+}
+
+impl From<SimplePath> for Query {
+    fn from(path: SimplePath) -> Self {
+        Self::new(path)
+    }
+}
+
+pub(crate) fn find(
+    index: &Index,
+    query: &Query,
+    options: &LinkOptions,
+) -> Option<(SimplePath, String)> {
+    let disambiguator = query.path.disambiguator();
+
+    query
+        .keys
+        .iter()
+        .filter(|key| crate_of(key) == index.name)
+        .find_map(|key| find_exact(index, key, disambiguator, options))
+        .or_else(|| {
+            query
+                .folded
+                .as_ref()?
+                .iter()
+                .filter(|folded| eq_folded(crate_of(folded), &index.name))
+                .find_map(|folded| {
+                    let key = index.mapping.keys().find(|key| eq_folded(key, folded))?;
+                    find_exact(index, key, disambiguator, options)
+                })
+        })
+}
+
+/// Crate name of a path in the form of the index mapping.
+fn crate_of(key: &str) -> &str {
+    key.split_once("::").map_or(key, |(name, _)| name)
+}
+
+/// Compare the value to an already lower-cased one, without allocating a lower-cased copy of the
+/// value itself.
+fn eq_folded(value: &str, folded: &str) -> bool {
+    value
+        .chars()
+        .flat_map(char::to_lowercase)
+        .eq(folded.chars())
+}
+
+/// Find the item with exactly the given path in the form of the index mapping.
+fn find_exact(
+    index: &Index,
+    key: &str,
+    disambiguator: Option<Disambiguator>,
+    options: &LinkOptions,
+) -> Option<(SimplePath, String)> {
+    let path = SimplePath::from_index_path(key).ok()?;
+
+    let url = if !key.contains("::") && disambiguator.is_none() {
+        index.format_crate_link(key, options)
+    } else {
+        index.format_link(index.lookup_key(key, disambiguator)?, options)
+    };
+    let link = options.render(path.as_ref(), &url);
+
+    Some((path, link))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn test_find() {
+        let index = test_util::anyhow_index();
+        let find = |query: &Query| {
+            index
+                .find_query(query)
+                .map(|(path, link)| (path.into_inner(), link))
+        };
+
+        let query = "anyhow::error".parse::<Query>().unwrap();
+        assert_eq!(None, find(&query));
+
+        let query = query.case_insensitive(true);
+        assert_eq!(
+            Some((
+                "anyhow::Error".to_owned(),
+                "https://docs.rs/anyhow/1.0.72/anyhow/struct.Error.html".to_owned()
+            )),
+            find(&query)
+        );
+
+        let query = "eyre::Result"
+            .parse::<Query>()
+            .unwrap()
+            .alias("eyre", "anyhow");
+        assert_eq!(
+            vec!["eyre", "anyhow"],
+            query.crate_names().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some((
+                "anyhow::Result".to_owned(),
+                "https://docs.rs/anyhow/1.0.72/anyhow/type.Result.html".to_owned()
+            )),
+            find(&query)
+        );
+
+        let query = "eyre::Result"
+            .parse::<Query>()
+            .unwrap()
+            .alias("eyr", "anyhow");
+        assert_eq!(None, find(&query));
+
+        let query = "macro@anyhow::Error".parse::<Query>().unwrap();
+        assert_eq!(None, find(&query));
+
+        let query = "anyhow".parse::<Query>().unwrap();
+        assert_eq!(
+            Some((
+                "anyhow".to_owned(),
+                "https://docs.rs/anyhow/1.0.72/anyhow".to_owned()
+            )),
+            find(&query)
+        );
+
+        let query = "serde".parse::<Query>().unwrap().case_insensitive(true);
+        assert_eq!(None, find(&query));
+
+        let query = "Anyhow::Result"
+            .parse::<Query>()
+            .unwrap()
+            .case_insensitive(true);
+        assert_eq!(
+            Some((
+                "anyhow::Result".to_owned(),
+                "https://docs.rs/anyhow/1.0.72/anyhow/type.Result.html".to_owned()
+            )),
+            find(&query)
+        );
+
+        let options = LinkOptions::new()
+            .relative_to("../doc")
+            .style(crate::LinkStyle::Markdown);
+        assert_eq!(
+            Some("[`anyhow::Result`](../doc/anyhow/type.Result.html)".to_owned()),
+            index
+                .find_query_with(&query, &options)
+                .map(|(_, link)| link)
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Access to the search index data before it's turned into the mappings of an [`Index`].
+//!
+//! The [`Index`] only keeps the links of each item. Advanced consumers, that want to build their
+//! own mappings and keep further details like descriptions or parents, can use the same parsers
+//! through this module instead of reimplementing them.
+//!
+//! ```rust
+//! use docsearch::raw;
+//!
+//! # fn run(content: &str) -> docsearch::error::Result<()> {
+//! let (_, data) = raw::parse(content)?;
+//! let data = raw::transform(data);
+//!
+//! for item in &data.crates["anyhow"].items {
+//!     println!("{}::{} - {}", item.path, item.name, item.desc);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Index`]: crate::Index
+
+pub use crate::index::{CrateData, IndexData, IndexItem, RawCrateData, RawIndexData};
+use crate::{error::Result, index, IndexFormat};
+
+/// Parse the content of a search index with the built-in parser of its format, without any
+/// further transformation.
+pub fn parse(index: &str) -> Result<(IndexFormat, RawIndexData)> {
+    index::load_any(index, None)
+}
+
+/// Combine the separate vectors of each crate's raw data back together, into one full data set
+/// for each item. Module paths are resolved and parent indexes adjusted to point into
+/// [`CrateData::paths`].
+#[must_use]
+pub fn transform(raw: RawIndexData) -> IndexData {
+    index::transform(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::ItemType;
+
+    #[test]
+    fn test_parse() {
+        let content = fs::read_to_string("src/index/fixtures/anyhow-1.0.72.js").unwrap();
+        let (format, raw) = parse(&content).unwrap();
+        assert_eq!(IndexFormat::V3, format);
+
+        let data = transform(raw);
+        let krate = &data.crates["anyhow"];
+        let item = krate
+            .items
+            .iter()
+            .find(|item| item.name == "context" && item.parent_idx.is_some())
+            .unwrap();
+
+        assert_eq!("anyhow", item.path);
+        assert!(!item.desc.is_empty());
+        assert_eq!(
+            (ItemType::Trait, "Context".to_owned()),
+            krate.paths[item.parent_idx.unwrap()]
+        );
+    }
+}
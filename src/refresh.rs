@@ -0,0 +1,156 @@
+//! Refresh of a previously retrieved [`Index`], that avoids the download of the search index if
+//! the docs didn't change.
+
+use crate::{crates, error::Result, Index, Request, SearchIndex, SearchPage};
+
+/// Start the refresh of a previously retrieved (and likely cached) [`Index`]. Only the docs page
+/// is downloaded first, to compare the version and resource suffix with the ones of the index. The
+/// search index itself is only downloaded again if either of them changed.
+///
+/// The search is repeated with the originally requested version, so an index for
+/// [`Version::Latest`](crate::Version::Latest) is updated to the newest release.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn download(url: &str) -> String { unimplemented!() }
+/// # fn load_cached() -> docsearch::Index { unimplemented!() }
+/// use docsearch::RefreshStep;
+///
+/// let cached = load_cached();
+/// let state = docsearch::refresh(&cached);
+/// let body = download(state.url());
+///
+/// let index = match state.check(&body).unwrap() {
+///     RefreshStep::Unchanged => cached,
+///     RefreshStep::Changed(state) => {
+///         let body = download(state.url());
+///         state.transform_index(&body).unwrap()
+///     }
+/// };
+/// ```
+#[must_use]
+pub fn refresh(index: &Index) -> Refresh<'_> {
+    let page = match &index.root {
+        Some(root) => crate::start_search_at(root, &index.name, index.requested.clone()),
+        None => {
+            crate::start_search(&index.name, index.requested.clone()).with_host(index.host.clone())
+        }
+    };
+
+    Refresh { index, page }
+}
+
+/// Single state to check whether an [`Index`] is still up-to-date. Use the [`Self::url`] function
+/// to get the URL of the docs page to download. Its content must then be passed to [`Self::check`].
+pub struct Refresh<'a> {
+    index: &'a Index,
+    page: SearchPage<'a>,
+}
+
+impl<'a> Refresh<'a> {
+    /// URL to the docs page that should be retrieved and passed to [`Self::check`].
+    #[must_use]
+    pub fn url(&self) -> &str {
+        self.page.url()
+    }
+
+    /// Full description of the request to retrieve the content for [`Self::check`].
+    #[must_use]
+    pub fn request(&self) -> Request {
+        self.page.request()
+    }
+
+    /// Resolve the concrete version from the final URL of the docs page, after all redirects were
+    /// followed. See [`SearchPage::resolve_version`] for details.
+    #[must_use]
+    pub fn resolve_version(self, final_url: &str) -> Self {
+        Self {
+            page: self.page.resolve_version(final_url),
+            ..self
+        }
+    }
+
+    /// Switch to the alternative spelling of the crate name. The name of an [`Index`] is the one
+    /// used in code, which might differ from the published name. See
+    /// [`SearchPage::with_alternative_name`] for details.
+    #[must_use]
+    pub fn with_alternative_name(self) -> Option<Self> {
+        Some(Self {
+            page: self.page.with_alternative_name()?,
+            ..self
+        })
+    }
+
+    /// Compare the docs page content downloaded from [`Self::url`] with the index. If the version
+    /// and resource suffix are the same, the index is still up-to-date. Otherwise, the search
+    /// continues with the download of the new search index.
+    pub fn check(self, body: &str) -> Result<RefreshStep<'a>> {
+        let state = self.page.find_index(body)?;
+        let unchanged = state.version == self.index.version
+            && crates::resource_suffix(&state.url) == self.index.resource_suffix.as_deref();
+
+        Ok(if unchanged {
+            RefreshStep::Unchanged
+        } else {
+            RefreshStep::Changed(Box::new(state))
+        })
+    }
+
+    /// Compare the docs page content like [`Self::check`], but check the HTTP status code of the
+    /// response first. See [`SearchPage::find_index_with_status`] for the possible errors.
+    pub fn check_with_status(self, status: u16, body: &str) -> Result<RefreshStep<'a>> {
+        crates::check_status(
+            status,
+            &self.page.name,
+            &self.page.version,
+            &self.page.url,
+            Some(body),
+        )?;
+        self.check(body)
+    }
+}
+
+/// Result of checking an [`Index`] for changes.
+pub enum RefreshStep<'a> {
+    /// The index is still up-to-date and can be used as is.
+    Unchanged,
+    /// The docs changed and the new search index must be retrieved.
+    Changed(Box<SearchIndex<'a>>),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_refresh() {
+        let page = fs::read_to_string("src/fixtures/anyhow-1.0.72.html").unwrap();
+        let index = fs::read_to_string("src/index/fixtures/anyhow-1.0.72.js").unwrap();
+
+        let state = crate::start_search("anyhow", "1.0.72".parse().unwrap())
+            .find_index(&page)
+            .unwrap();
+        let cached = state.transform_index(&index).unwrap();
+
+        let state = refresh(&cached);
+        assert_eq!("https://docs.rs/anyhow/1.0.72/anyhow/", state.url());
+        assert!(matches!(
+            state.check(&page).unwrap(),
+            RefreshStep::Unchanged
+        ));
+
+        let page = fs::read_to_string("src/fixtures/anyhow-1.0.70.html").unwrap();
+        assert!(matches!(
+            refresh(&cached).check(&page).unwrap(),
+            RefreshStep::Changed(_)
+        ));
+
+        assert!(matches!(
+            refresh(&cached).check_with_status(429, ""),
+            Err(crate::error::Error::RateLimited { .. })
+        ));
+    }
+}
@@ -0,0 +1,137 @@
+//! Resolution of crate versions through the [sparse index](https://doc.rust-lang.org/cargo/reference/registry-index.html#sparse-protocol)
+//! of crates.io.
+
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{
+    error::{Error, Result},
+    Version,
+};
+
+/// Base URL for the sparse index of crates.io.
+const SPARSE_INDEX_URL: &str = "https://index.crates.io";
+
+/// Single release entry in the sparse index. Each line of an index file contains one of these.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Release {
+    /// Name of the crate, in its published spelling.
+    pub name: String,
+    /// Version of this release.
+    #[serde(deserialize_with = "crate::version::deserialize_semver")]
+    pub vers: semver::Version,
+    /// Whether this release was yanked.
+    pub yanked: bool,
+}
+
+/// Create the URL to the sparse index file for the given crate.
+///
+/// The index files are grouped into directories, based on the length of the crate name:
+///
+/// - Names of length 1 are located at `1/{name}`.
+/// - Names of length 2 are located at `2/{name}`.
+/// - Names of length 3 are located at `3/{first-char}/{name}`.
+/// - All other names are located at `{first-two}/{second-two}/{name}`.
+pub(crate) fn get_index_url(name: &str) -> String {
+    let name = name.to_ascii_lowercase();
+    let prefix = match name.len() {
+        0 => String::new(),
+        1 => "1".to_owned(),
+        2 => "2".to_owned(),
+        3 => format!("3/{}", name.get(..1).unwrap_or_default()),
+        _ => format!(
+            "{}/{}",
+            name.get(..2).unwrap_or_default(),
+            name.get(2..4).unwrap_or_default()
+        ),
+    };
+
+    format!("{SPARSE_INDEX_URL}/{prefix}/{name}")
+}
+
+/// Parse all releases from the sparse index file content, which contains one JSON object per line.
+pub(crate) fn parse_releases(body: &str) -> Result<Vec<Release>> {
+    let releases = body
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str::<Release>)
+        .collect::<Result<Vec<_>, _>>()?;
+    debug!("found {} releases in the sparse index", releases.len());
+
+    Ok(releases)
+}
+
+/// Parse the sparse index file content and find the release that matches the requested version.
+///
+/// For [`Version::Latest`], the highest version that is neither yanked nor a pre-release is picked.
+/// Likewise, a [`Version::Req`] picks the highest version matching the requirement, that isn't
+/// yanked. A specific version must exist and must not be yanked.
+pub(crate) fn find_release(name: &str, version: &Version, body: &str) -> Result<Release> {
+    let releases = parse_releases(body)?;
+
+    match version {
+        Version::Latest | Version::Req(_) => releases
+            .into_iter()
+            .filter(|r| !r.yanked && version.matches(&r.vers))
+            .max_by(|a, b| a.vers.cmp(&b.vers))
+            .ok_or_else(|| Error::VersionNotFound {
+                name: name.to_owned(),
+                version: version.to_string(),
+            }),
+        Version::SemVer(v) => {
+            let release = releases.into_iter().find(|r| r.vers == *v).ok_or_else(|| {
+                Error::VersionNotFound {
+                    name: name.to_owned(),
+                    version: version.to_string(),
+                }
+            })?;
+
+            if release.yanked {
+                Err(Error::VersionYanked {
+                    name: release.name,
+                    version: release.vers,
+                })
+            } else {
+                Ok(release)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = r#"
+{"name":"Anyhow","vers":"1.0.71","deps":[],"cksum":"","features":{},"yanked":false}
+{"name":"Anyhow","vers":"1.0.72","deps":[],"cksum":"","features":{},"yanked":true}
+{"name":"Anyhow","vers":"2.0.0-alpha.1","deps":[],"cksum":"","features":{},"yanked":false}
+"#;
+
+    #[test]
+    fn test_get_index_url() {
+        assert_eq!("https://index.crates.io/1/a", get_index_url("a"));
+        assert_eq!("https://index.crates.io/2/ab", get_index_url("ab"));
+        assert_eq!("https://index.crates.io/3/a/abc", get_index_url("abc"));
+        assert_eq!(
+            "https://index.crates.io/an/yh/anyhow",
+            get_index_url("Anyhow")
+        );
+    }
+
+    #[test]
+    fn test_find_release() {
+        let release = find_release("anyhow", &Version::Latest, BODY).unwrap();
+        assert_eq!("Anyhow", release.name);
+        assert_eq!("1.0.71", release.vers.to_string());
+
+        let err = find_release("anyhow", &"1.0.72".parse().unwrap(), BODY).unwrap_err();
+        assert!(matches!(err, Error::VersionYanked { name, .. } if name == "Anyhow"));
+
+        let release = find_release("anyhow", &"^1.0.60".parse().unwrap(), BODY).unwrap();
+        assert_eq!("1.0.71", release.vers.to_string());
+
+        let err = find_release("anyhow", &"1.0.0".parse().unwrap(), BODY).unwrap_err();
+        assert!(matches!(err, Error::VersionNotFound { name, .. } if name == "anyhow"));
+    }
+}
@@ -0,0 +1,186 @@
+//! Description of the HTTP requests, that need to be sent to advance a search.
+
+use std::fmt::{self, Display};
+
+/// User agent that identifies this library, as requested by docs.rs for automated access.
+pub(crate) const USER_AGENT: &str = concat!(
+    "docsearch/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/dnaka91/docsearch)"
+);
+
+/// A single HTTP request that must be sent by the caller, to retrieve the content for the next
+/// search state.
+///
+/// This makes the library guidance like the required headers or redirect policy explicit, instead
+/// of only providing a bare URL.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Request {
+    /// The HTTP method to use.
+    pub method: Method,
+    /// Full URL of the resource.
+    pub url: String,
+    /// Additional headers that should be sent with the request.
+    pub headers: Vec<(&'static str, &'static str)>,
+    /// Whether redirects should be followed. If disabled, the redirect response itself is the
+    /// expected result of the request.
+    pub follow_redirects: bool,
+}
+
+impl Request {
+    /// Create a `GET` request for the given URL with the default headers, that follows redirects.
+    pub(crate) fn get(url: impl Into<String>) -> Self {
+        Self {
+            method: Method::Get,
+            url: url.into(),
+            headers: vec![("User-Agent", USER_AGENT), ("Accept-Encoding", "gzip")],
+            follow_redirects: true,
+        }
+    }
+
+    /// Create a `HEAD` request for the given URL with the default headers, that doesn't follow
+    /// redirects.
+    pub(crate) fn head(url: impl Into<String>) -> Self {
+        Self {
+            method: Method::Head,
+            url: url.into(),
+            headers: vec![("User-Agent", USER_AGENT)],
+            follow_redirects: false,
+        }
+    }
+}
+
+/// Convert the request into an [`http::Request`] with the same method, URL and headers, and an
+/// empty body.
+///
+/// The [`follow_redirects`](Request::follow_redirects) flag has no equivalent in the `http` types,
+/// and must still be respected by the client.
+///
+/// # Errors
+///
+/// Will return `Err` if the URL isn't a valid [`http::Uri`].
+#[cfg(feature = "http")]
+impl TryFrom<&Request> for http::Request<()> {
+    type Error = http::Error;
+
+    fn try_from(request: &Request) -> Result<Self, Self::Error> {
+        request
+            .headers
+            .iter()
+            .fold(
+                Self::builder()
+                    .method(http::Method::from(request.method))
+                    .uri(&request.url),
+                |builder, (name, value)| builder.header(*name, *value),
+            )
+            .body(())
+    }
+}
+
+/// Convert the request into an [`http::Request`], like the conversion from `&Request`.
+#[cfg(feature = "http")]
+impl TryFrom<Request> for http::Request<()> {
+    type Error = http::Error;
+
+    fn try_from(request: Request) -> Result<Self, Self::Error> {
+        Self::try_from(&request)
+    }
+}
+
+/// HTTP method of a [`Request`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// Retrieve the full content of a resource.
+    Get,
+    /// Only retrieve the headers of a resource.
+    Head,
+}
+
+impl Method {
+    /// Name of the method, as used in HTTP.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Head => "HEAD",
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<Method> for http::Method {
+    fn from(method: Method) -> Self {
+        match method {
+            Method::Get => Self::GET,
+            Method::Head => Self::HEAD,
+        }
+    }
+}
+
+impl Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Response to a [`Request`], that is fed back into a [`Search`](crate::Search).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    /// HTTP status code of the response.
+    pub status: u16,
+    /// Full content of the response.
+    pub body: String,
+    /// Final URL of the response after all redirects were followed, if known.
+    pub final_url: Option<String>,
+}
+
+impl Response {
+    /// Create a new response with the given status code and content.
+    #[must_use]
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+            final_url: None,
+        }
+    }
+
+    /// Set the final URL after all redirects were followed, which allows to resolve the concrete
+    /// version like [`SearchPage::resolve_version`](crate::SearchPage::resolve_version).
+    #[must_use]
+    pub fn final_url(mut self, url: impl Into<String>) -> Self {
+        self.final_url = Some(url.into());
+        self
+    }
+}
+
+/// Take the status and body of an [`http::Response`], to feed it into a
+/// [`Search`](crate::Search). The final URL isn't part of the `http` types, and must be set with
+/// [`Response::final_url`] if the client followed any redirects.
+#[cfg(feature = "http")]
+impl From<http::Response<String>> for Response {
+    fn from(response: http::Response<String>) -> Self {
+        Self::new(response.status().as_u16(), response.into_body())
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http() {
+        let request = http::Request::try_from(Request::head("https://docs.rs/anyhow")).unwrap();
+        assert_eq!(http::Method::HEAD, request.method());
+        assert_eq!("https://docs.rs/anyhow", request.uri());
+        assert_eq!(USER_AGENT, request.headers()["user-agent"]);
+
+        assert!(http::Request::try_from(Request::get("not a url")).is_err());
+
+        let response = http::Response::builder()
+            .status(404)
+            .body("missing".to_owned())
+            .unwrap();
+        assert_eq!(Response::new(404, "missing"), Response::from(response));
+    }
+}
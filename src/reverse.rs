@@ -0,0 +1,158 @@
+//! Reverse lookup from documentation URLs back to the simple path of an item.
+
+use std::collections::HashMap;
+
+use crate::{crates, link, Index, SimplePath};
+
+/// Reverse lookup from docs URLs back to the simple paths of an [`Index`], as created by
+/// [`Index::reverse_index`]. The mapping from URLs to paths is built once up front, so this is
+/// the better choice when resolving many URLs against the same index.
+///
+/// ```no_run
+/// # let index: docsearch::Index = unimplemented!();
+/// let reverse = index.reverse_index();
+///
+/// for url in ["anyhow/struct.Error.html", "anyhow/trait.Context.html"] {
+///     println!("{url}: {:?}", reverse.find_path(url));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReverseIndex<'a> {
+    name: &'a str,
+    urls: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> ReverseIndex<'a> {
+    pub(crate) fn new(index: &'a Index) -> Self {
+        Self {
+            name: &index.name,
+            urls: index
+                .mapping
+                .iter()
+                .map(|(path, url)| (url.as_str(), path.as_str()))
+                .collect(),
+        }
+    }
+
+    /// Find the simple path of the item that the given URL points to. See [`Index::find_path`]
+    /// for the accepted forms of URLs.
+    #[must_use]
+    pub fn find_path(&self, url: &str) -> Option<SimplePath> {
+        let url = link::decode_path(url);
+        let (page, fragment) = match strip_origin(&url).split_once('#') {
+            Some((page, fragment)) => (page, Some(fragment)),
+            None => (strip_origin(&url), None),
+        };
+        let page = page.split_once('?').map_or(page, |(page, _)| page);
+
+        let lookup = |page: &str, fragment: Option<&str>| {
+            candidates(page).find_map(|page| {
+                let page = normalize_module(page, self.name)?;
+                match fragment {
+                    Some(fragment) => self
+                        .urls
+                        .get(format!("{page}#{fragment}").as_str())
+                        .copied(),
+                    None if page == self.name => Some(page.as_str()),
+                    None => self.urls.get(page.as_str()).copied(),
+                }
+                .map(ToOwned::to_owned)
+            })
+        };
+
+        // Prefer the exact item with the fragment, but fall back to the page itself, as not all
+        // anchors on a page are part of the index (like trait implementations).
+        fragment
+            .and_then(|fragment| lookup(page, Some(fragment)))
+            .or_else(|| lookup(page, None))
+            .and_then(|path| SimplePath::from_index_path(&path).ok())
+    }
+}
+
+/// Remove the scheme and host from an absolute URL, as well as any leading slashes.
+fn strip_origin(url: &str) -> &str {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').map_or("", |(_, path)| path),
+        None => url,
+    };
+
+    path.trim_start_matches('/')
+}
+
+/// Iterate over all suffixes of the path that start at a segment boundary, from the longest to
+/// the shortest. This skips any prefix like `<name>/<version>/` of docs.rs URLs.
+fn candidates(path: &str) -> impl Iterator<Item = &str> {
+    std::iter::once(path).chain(
+        path.match_indices('/')
+            .map(move |(pos, _)| &path[pos + 1..]),
+    )
+}
+
+/// Convert the URL of a module page like `<crate>/<module>/index.html` into the form used in the
+/// index mapping, which is `<crate>/mod.<module>.html`. The crate's root page is turned into the
+/// crate name.
+fn normalize_module(page: &str, crate_name: &str) -> Option<String> {
+    let page = page.trim_end_matches("index.html");
+    if !page.ends_with('/') {
+        return (!page.is_empty()).then(|| page.to_owned());
+    }
+
+    let module = page.trim_end_matches('/');
+    if module.is_empty() {
+        return None;
+    }
+
+    Some(match module.rsplit_once('/') {
+        Some((parent, name)) => format!("{parent}/mod.{name}.html"),
+        None if module == crates::crate_name(crate_name) => crate_name.to_owned(),
+        None => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::SearchIndex;
+
+    #[test]
+    fn test_find_path() {
+        let content = fs::read_to_string("src/index/fixtures/syn-2.0.8.js").unwrap();
+        let index = SearchIndex::from_parts("syn", "2.0.8".parse().unwrap(), "")
+            .transform_index(&content)
+            .unwrap();
+
+        let reverse = ReverseIndex::new(&index);
+        let find = |url: &str| reverse.find_path(url).map(|path| path.to_string());
+
+        assert_eq!(
+            Some("syn::Ident"),
+            find("https://docs.rs/syn/2.0.8/syn/struct.Ident.html").as_deref()
+        );
+        assert_eq!(
+            Some("syn::Attribute::parse_outer"),
+            find("/syn/latest/syn/struct.Attribute.html#method.parse_outer").as_deref()
+        );
+        assert_eq!(
+            Some("syn::Attribute"),
+            find("syn/struct.Attribute.html#impl-Clone-for-Attribute").as_deref()
+        );
+        assert_eq!(
+            Some("syn::buffer"),
+            find("https://docs.rs/syn/2/syn/buffer/index.html").as_deref()
+        );
+        assert_eq!(
+            Some("syn"),
+            find("https://docs.rs/syn/2.0.8/syn/").as_deref()
+        );
+        assert_eq!(
+            Some("syn::Ident"),
+            find("https://docs.rs/syn/2.0.8/syn/struct.Ident.html?search=x").as_deref()
+        );
+        assert_eq!(
+            None,
+            find("https://docs.rs/syn/2.0.8/syn/struct.Missing.html")
+        );
+    }
+}
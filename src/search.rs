@@ -0,0 +1,435 @@
+//! Single poll-style driver for the whole search, on top of the individual search states.
+
+use std::{future::Future, mem};
+
+use crate::{
+    crates,
+    error::{Error, Result},
+    locator::DocsRsLocator,
+    AsyncHttpFetch, HttpFetch, Index, Request, Response, SearchIndex, SearchPage, Version,
+};
+
+/// Driver for a whole search, that hides the individual states like [`SearchPage`] and
+/// [`SearchIndex`] behind a single loop. It tells which [`Request`] to send next, and the
+/// [`Response`] is then fed back until the [`Index`] is done.
+///
+/// Failed responses don't end the search, so the same request can be retried after an error like
+/// [`Error::RateLimited`](crate::error::Error::RateLimited).
+///
+/// If the docs of the crate don't exist, the search is retried once with the alternative spelling
+/// of the crate name, like `serde-yaml` for `serde_yaml`. See
+/// [`SearchPage::with_alternative_name`] for details. If that fails as well, the error still
+/// refers to the crate name as requested.
+///
+/// ```no_run
+/// use docsearch::{Index, Response, Search, Step, Version};
+///
+/// # fn fetch(request: &docsearch::Request) -> Response { unimplemented!() }
+/// let mut search = Search::new("anyhow", Version::Latest);
+///
+/// let index = loop {
+///     match search.next_step() {
+///         Step::Fetch(request) => search.feed(&fetch(&request)).unwrap(),
+///         Step::Done(index) => break *index,
+///     }
+/// };
+/// ```
+pub struct Search<'a> {
+    state: State<'a>,
+    /// Crate name as requested by the caller, once the search switched to the alternative
+    /// spelling of it.
+    requested: Option<String>,
+}
+
+enum State<'a> {
+    Page(SearchPage<'a>),
+    Index(SearchIndex<'a>),
+    Done(Box<Index>),
+    Finished,
+}
+
+/// Next step of a [`Search`], as returned by [`Search::next_step`].
+#[derive(Debug)]
+pub enum Step {
+    /// Send the request and pass the response to [`Search::feed`].
+    Fetch(Request),
+    /// The search is complete.
+    Done(Box<Index>),
+}
+
+impl<'a> Search<'a> {
+    /// Start a new search for the crate, like [`start_search`](crate::start_search).
+    #[must_use]
+    pub fn new(name: &'a str, version: Version) -> Self {
+        crate::start_search(name, version).into()
+    }
+
+    /// Get the next step of the search, which is either a request to send, or the final
+    /// [`Index`].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if called again after the search was [`Step::Done`].
+    pub fn next_step(&mut self) -> Step {
+        match mem::replace(&mut self.state, State::Finished) {
+            State::Page(page) => {
+                let request = page.request();
+                self.state = State::Page(page);
+                Step::Fetch(request)
+            }
+            State::Index(index) => {
+                let request = index.request();
+                self.state = State::Index(index);
+                Step::Fetch(request)
+            }
+            State::Done(index) => Step::Done(index),
+            State::Finished => panic!("search was already finished"),
+        }
+    }
+
+    /// Feed the response for the last [`Step::Fetch`] into the search, to advance to the next
+    /// step.
+    ///
+    /// If the response can't be used, because of an error status or content that can't be parsed,
+    /// the search stays at the current step, so the request can be retried.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if no request is pending, because the search is already done.
+    pub fn feed(&mut self, response: &Response) -> Result<()> {
+        match mem::replace(&mut self.state, State::Finished) {
+            State::Page(page) => {
+                if let Err(e) = crates::check_status(
+                    response.status,
+                    &page.name,
+                    &page.version,
+                    &page.url,
+                    Some(&response.body),
+                ) {
+                    let not_found =
+                        matches!(e, Error::CrateNotFound(_) | Error::VersionNotFound { .. });
+                    let mut page = page;
+                    if not_found && self.requested.is_none() {
+                        let name = page.name.to_string();
+                        if page.rename() {
+                            self.state = State::Page(page);
+                            self.requested = Some(name);
+                            return Ok(());
+                        }
+                    }
+
+                    self.state = State::Page(page);
+                    return Err(match (e, &self.requested) {
+                        (Error::CrateNotFound(_), Some(name)) => Error::CrateNotFound(name.clone()),
+                        (Error::VersionNotFound { version, .. }, Some(name)) => {
+                            Error::VersionNotFound {
+                                name: name.clone(),
+                                version,
+                            }
+                        }
+                        (e, _) => e,
+                    });
+                }
+
+                let page = match &response.final_url {
+                    Some(url) => page.resolve_version(url),
+                    None => page,
+                };
+
+                match page.locate(&DocsRsLocator, &response.body) {
+                    Ok(location) => {
+                        self.state = State::Index(page.into_index(location, &response.body));
+                    }
+                    Err(e) => {
+                        self.state = State::Page(page);
+                        return Err(e);
+                    }
+                }
+            }
+            State::Index(index) => {
+                let result = crates::check_status(
+                    response.status,
+                    &index.name,
+                    &index.version,
+                    &index.url,
+                    None,
+                )
+                .and_then(|()| index.try_transform_index(&response.body));
+
+                match result {
+                    Ok(done) => self.state = State::Done(Box::new(done)),
+                    Err(e) => {
+                        self.state = State::Index(index);
+                        return Err(e);
+                    }
+                }
+            }
+            State::Done(_) | State::Finished => panic!("no request is pending"),
+        }
+
+        Ok(())
+    }
+
+    /// Send all requests with the given client, until the search is done.
+    pub fn run(mut self, fetcher: &impl HttpFetch) -> Result<Index> {
+        loop {
+            match self.next_step() {
+                Step::Fetch(request) => self.feed(&fetcher.fetch(&request)?)?,
+                Step::Done(index) => return Ok(*index),
+            }
+        }
+    }
+
+    /// Send all requests with the given async client, until the search is done.
+    pub async fn run_async(mut self, fetcher: &impl AsyncHttpFetch) -> Result<Index> {
+        loop {
+            match self.next_step() {
+                Step::Fetch(request) => self.feed(&fetcher.fetch(&request).await?)?,
+                Step::Done(index) => return Ok(*index),
+            }
+        }
+    }
+}
+
+/// Run a whole search for the crate, with the given function to download the content of each
+/// [`Request`]. This drives the [`Search`] internally, so the HTTP client can be any async
+/// client, without writing the loop over the search steps.
+///
+/// The function must return the body of a successful response, and an error for anything else.
+/// Its errors are returned as [`Error::Fetch`]. As the status of failed responses isn't known,
+/// the search isn't retried with the alternative spelling of the crate name, unlike with
+/// [`Search::run_async`].
+///
+/// ```no_run
+/// # async fn run() -> docsearch::error::Result<()> {
+/// use docsearch::Version;
+///
+/// let client = reqwest::Client::new();
+/// let index = docsearch::search_with("anyhow", Version::Latest, |request| {
+///     let response = client.get(request.url).send();
+///     async move { response.await?.error_for_status()?.text().await }
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn search_with<F, Fut, E>(name: &str, version: Version, mut fetch: F) -> Result<Index>
+where
+    F: FnMut(Request) -> Fut,
+    Fut: Future<Output = Result<String, E>>,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let mut search = Search::new(name, version);
+
+    loop {
+        match search.next_step() {
+            Step::Fetch(request) => {
+                let url = request.url.clone();
+                let body = fetch(request).await.map_err(|e| Error::Fetch {
+                    url,
+                    source: e.into(),
+                })?;
+                search.feed(&Response::new(200, body))?;
+            }
+            Step::Done(index) => return Ok(*index),
+        }
+    }
+}
+
+/// Run a whole search for the crate with a built-in [`reqwest`] client. This is the simplest way
+/// to get the [`Index`] of a crate, if the HTTP client doesn't need to be customized.
+///
+/// ```no_run
+/// # async fn run() -> docsearch::error::Result<()> {
+/// use docsearch::Version;
+///
+/// let index = docsearch::search("anyhow", Version::Latest).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+pub async fn search(name: &str, version: Version) -> Result<Index> {
+    Search::new(name, version)
+        .run_async(&reqwest::Client::new())
+        .await
+}
+
+/// Run a whole search for the crate like [`search`], but with a blocking [`ureq`] client. This
+/// suits CLI tools and build scripts, that don't use an async runtime.
+///
+/// ```no_run
+/// use docsearch::Version;
+///
+/// let index = docsearch::search_blocking("anyhow", Version::Latest).unwrap();
+/// ```
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub fn search_blocking(name: &str, version: Version) -> Result<Index> {
+    Search::new(name, version).run(&ureq::Agent::new())
+}
+
+impl<'a> From<SearchPage<'a>> for Search<'a> {
+    fn from(page: SearchPage<'a>) -> Self {
+        Self {
+            state: State::Page(page),
+            requested: None,
+        }
+    }
+}
+
+impl<'a> From<SearchIndex<'a>> for Search<'a> {
+    fn from(index: SearchIndex<'a>) -> Self {
+        Self {
+            state: State::Index(index),
+            requested: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_search_alternative_name() {
+        let mut search = Search::new("serde_yaml", Version::Latest);
+
+        search.feed(&Response::new(404, "")).unwrap();
+        assert!(matches!(
+            search.next_step(),
+            Step::Fetch(r) if r.url == "https://docs.rs/serde-yaml/latest/serde_yaml/"
+        ));
+
+        let err = search.feed(&Response::new(404, "")).unwrap_err();
+        assert!(matches!(err, Error::CrateNotFound(name) if name == "serde_yaml"));
+    }
+
+    #[test]
+    fn test_search() {
+        let fixture = fs::read_to_string("src/index/fixtures/anyhow-1.0.72.js").unwrap();
+        let mut search = Search::from(crate::start_search_at(
+            "https://docs.example.com",
+            "anyhow",
+            Version::Latest,
+        ));
+
+        let request = match search.next_step() {
+            Step::Fetch(request) => request,
+            Step::Done(_) => panic!("expected a request"),
+        };
+        assert_eq!("https://docs.example.com/anyhow/index.html", request.url);
+
+        let err = search.feed(&Response::new(429, "")).unwrap_err();
+        assert!(matches!(err, Error::RateLimited { .. }));
+
+        search
+            .feed(&Response::new(200, "<html></html>"))
+            .unwrap_err();
+        assert!(matches!(search.next_step(), Step::Fetch(r) if r.url == request.url));
+
+        search
+            .feed(&Response::new(
+                200,
+                r#"<div id="rustdoc-vars" data-resource-suffix="-1.70.0"></div>"#,
+            ))
+            .unwrap();
+
+        let request = match search.next_step() {
+            Step::Fetch(request) => request,
+            Step::Done(_) => panic!("expected a request"),
+        };
+        assert_eq!(
+            "https://docs.example.com/search-index-1.70.0.js",
+            request.url
+        );
+
+        search
+            .feed(&Response::new(200, "not an index"))
+            .unwrap_err();
+        assert!(matches!(search.next_step(), Step::Fetch(r) if r.url == request.url));
+
+        search.feed(&Response::new(200, fixture)).unwrap();
+
+        match search.next_step() {
+            Step::Done(index) => assert_eq!("anyhow", index.name),
+            Step::Fetch(_) => panic!("expected the index"),
+        }
+    }
+
+    /// Client that serves the docs page and index of `anyhow` from the fixtures.
+    struct FixtureFetch;
+
+    impl FixtureFetch {
+        fn response(request: &Request) -> Response {
+            if request.url.contains("search-index") {
+                let fixture = fs::read_to_string("src/index/fixtures/anyhow-1.0.72.js").unwrap();
+                Response::new(200, fixture)
+            } else {
+                Response::new(
+                    200,
+                    r#"<div id="rustdoc-vars" data-resource-suffix="-1.70.0"></div>"#,
+                )
+            }
+        }
+    }
+
+    impl HttpFetch for FixtureFetch {
+        fn fetch(&self, request: &Request) -> Result<Response> {
+            Ok(Self::response(request))
+        }
+    }
+
+    impl AsyncHttpFetch for FixtureFetch {
+        fn fetch<'a>(&'a self, request: &'a Request) -> crate::FetchFuture<'a> {
+            Box::pin(async move { Ok(Self::response(request)) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let search = || Search::new("anyhow", "1.0.72".parse().unwrap());
+
+        let index = search().run(&FixtureFetch).unwrap();
+        assert_eq!("anyhow", index.name);
+
+        let index = search().run_async(&FixtureFetch).await.unwrap();
+        assert_eq!("anyhow", index.name);
+    }
+
+    #[tokio::test]
+    async fn test_search_with() {
+        let fixture = fs::read_to_string("src/index/fixtures/anyhow-1.0.72.js").unwrap();
+        let mut urls = Vec::new();
+
+        let index = search_with("anyhow", "1.0.72".parse().unwrap(), |request| {
+            let body = if request.url.contains("search-index") {
+                fixture.clone()
+            } else {
+                r#"<div id="rustdoc-vars" data-resource-suffix="-20230714-1.73.0-nightly-ad963232d"></div>"#
+                    .to_owned()
+            };
+            urls.push(request.url);
+            async move { Ok::<_, Error>(body) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!("anyhow", index.name);
+        assert_eq!(
+            vec![
+                "https://docs.rs/anyhow/1.0.72/anyhow/",
+                "https://docs.rs/anyhow/1.0.72/search-index-20230714-1.73.0-nightly-ad963232d.js",
+            ],
+            urls
+        );
+
+        let err = search_with("anyhow", Version::Latest, |_| async {
+            Err::<String, _>("connection refused")
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::Fetch { .. }));
+    }
+}
@@ -0,0 +1,143 @@
+//! Integration with the [`tower`](https://docs.rs/tower) ecosystem, to send the requests of a
+//! search through any [`Service`].
+
+use std::{future::poll_fn, marker::PhantomData};
+
+use tower_service::Service;
+
+use crate::{
+    error::Error,
+    fetch::{AsyncHttpFetch, FetchFuture},
+    Request,
+};
+
+/// Adapter that sends the requests of a search through a [`Service`], so middleware for retries,
+/// timeouts or tracing can be composed from the tower ecosystem. Use it with
+/// [`Search::run_async`](crate::Search::run_async).
+///
+/// The service receives [`http::Request`]s with an empty body of type `B`, and must respond with
+/// the body already collected into a [`String`]. It's cloned for each request, as usual for tower
+/// services.
+///
+/// Redirects must be followed by the service itself, for example with the `FollowRedirect`
+/// middleware of `tower-http`.
+///
+/// ```no_run
+/// # async fn run<S>(service: S) -> docsearch::error::Result<()>
+/// # where
+/// #     S: tower_service::Service<http::Request<String>, Response = http::Response<String>>
+/// #         + Clone
+/// #         + Send
+/// #         + Sync,
+/// #     S::Future: Send,
+/// #     S::Error: std::error::Error + Send + Sync + 'static,
+/// # {
+/// use docsearch::{Search, TowerFetch, Version};
+///
+/// let index = Search::new("anyhow", Version::Latest)
+///     .run_async(&TowerFetch::new(service))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TowerFetch<S, B = String> {
+    service: S,
+    _body: PhantomData<fn() -> B>,
+}
+
+impl<S, B> TowerFetch<S, B> {
+    /// Wrap the service, to use it for sending requests.
+    pub fn new(service: S) -> Self {
+        Self {
+            service,
+            _body: PhantomData,
+        }
+    }
+
+    /// Unwrap the inner service again.
+    pub fn into_inner(self) -> S {
+        self.service
+    }
+}
+
+impl<S, B> AsyncHttpFetch for TowerFetch<S, B>
+where
+    S: Service<http::Request<B>, Response = http::Response<String>> + Clone + Send + Sync,
+    S::Future: Send,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    B: Default + Send,
+{
+    fn fetch<'a>(&'a self, request: &'a Request) -> FetchFuture<'a> {
+        let mut service = self.service.clone();
+
+        Box::pin(async move {
+            let fetch_error = |e: Box<dyn std::error::Error + Send + Sync>| Error::Fetch {
+                url: request.url.clone(),
+                source: e,
+            };
+
+            let http_request = http::Request::try_from(request)
+                .map_err(|e| fetch_error(e.into()))?
+                .map(|()| B::default());
+
+            poll_fn(|cx| service.poll_ready(cx))
+                .await
+                .map_err(|e| fetch_error(e.into()))?;
+            let response = service
+                .call(http_request)
+                .await
+                .map_err(|e| fetch_error(e.into()))?;
+
+            Ok(response.into())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        fs,
+        future::{ready, Ready},
+        task::{Context, Poll},
+    };
+
+    use super::*;
+    use crate::Search;
+
+    /// Service that serves the docs page and index of `anyhow` from the fixtures.
+    #[derive(Clone)]
+    struct FixtureService;
+
+    impl Service<http::Request<()>> for FixtureService {
+        type Response = http::Response<String>;
+        type Error = Infallible;
+        type Future = Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<()>) -> Self::Future {
+            assert!(request.headers().contains_key("user-agent"));
+
+            let body = if request.uri().path().contains("search-index") {
+                fs::read_to_string("src/index/fixtures/anyhow-1.0.72.js").unwrap()
+            } else {
+                r#"<div id="rustdoc-vars" data-resource-suffix="-1.70.0"></div>"#.to_owned()
+            };
+
+            ready(Ok(http::Response::new(body)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tower_fetch() {
+        let index = Search::new("anyhow", "1.0.72".parse().unwrap())
+            .run_async(&TowerFetch::new(FixtureService))
+            .await
+            .unwrap();
+
+        assert_eq!("anyhow", index.name);
+    }
+}
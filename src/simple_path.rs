@@ -2,11 +2,15 @@
 //! this crate to make easy use of the path.
 
 use std::{
+    borrow::Cow,
     fmt::{self, Display},
     str::FromStr,
 };
 
-use crate::{error::ParseError, STD_CRATES};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{crates, error::ParseError, ItemType, STD_CRATES};
 
 /// Path for any item within a crate (or just the crate itself) like `std::vec::Vec`,
 /// `anyhow::Result` or `thiserror`.
@@ -16,7 +20,166 @@ use crate::{error::ParseError, STD_CRATES};
 /// ```rust
 /// "anyhow::Result".parse::<docsearch::SimplePath>().unwrap();
 /// ```
-pub struct SimplePath(String, usize);
+///
+/// A leading `::` like in `::std::vec::Vec` is accepted, but not kept as part of the path. The
+/// same goes for surrounding whitespace and backticks, as well as trailing punctuation, so a path
+/// can be taken as is from chat messages like `` `anyhow::Result`. ``.
+///
+/// Paths of macros can be given with a trailing `!` like `std::vec!`, which restricts the lookup
+/// to macros. Otherwise, the module `std::vec` could be found instead. Similarly, the path can be
+/// prefixed with any of rustdoc's [disambiguators](https://doc.rust-lang.org/rustdoc/write-documentation/linking-to-items-by-name.html#namespaces-and-disambiguators)
+/// like `fn@syn::parse` or `mod@syn::parse`. Neither is part of the path itself, but available as
+/// [`Self::disambiguator`].
+///
+/// ```rust
+/// use docsearch::{Disambiguator, SimplePath};
+///
+/// let path = "struct@anyhow::Error".parse::<SimplePath>().unwrap();
+/// assert_eq!("anyhow::Error", path.as_ref());
+/// assert_eq!(Some(Disambiguator::Struct), path.disambiguator());
+/// ```
+///
+/// Paths are (de-)serialized as plain strings in the same form as they're parsed, including the
+/// disambiguator.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SimplePath(String, usize, Option<Disambiguator>);
+
+/// Restriction of a [`SimplePath`] to a specific kind of item, for paths that are shared by
+/// multiple items. These mirror the disambiguators of rustdoc's intra-doc links.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Disambiguator {
+    /// Any kind of macro, given with a trailing `!` like `std::vec!` or as `macro@` prefix.
+    Macro,
+    /// Derive macro, given as `derive@` prefix.
+    Derive,
+    /// Module, given as `mod@` or `module@` prefix.
+    Module,
+    /// Struct, given as `struct@` prefix.
+    Struct,
+    /// Enum, given as `enum@` prefix.
+    Enum,
+    /// Union, given as `union@` prefix.
+    Union,
+    /// Trait, given as `trait@` prefix.
+    Trait,
+    /// Function or method, given as `fn@` or `function@` prefix.
+    Function,
+    /// Method, given as `method@` prefix.
+    Method,
+    /// Constant, given as `const@` or `constant@` prefix.
+    Constant,
+    /// Static, given as `static@` prefix.
+    Static,
+    /// Primitive type, given as `prim@` or `primitive@` prefix.
+    Primitive,
+    /// Struct field, given as `field@` prefix.
+    Field,
+    /// Enum variant, given as `variant@` prefix.
+    Variant,
+    /// Any item in the type namespace, given as `type@` prefix.
+    Type,
+    /// Any item in the value namespace, given as `value@` prefix.
+    Value,
+}
+
+impl Disambiguator {
+    /// Parse the prefix of a path like `struct` in `struct@anyhow::Error`, without the `@`.
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        Some(match prefix {
+            "macro" => Self::Macro,
+            "derive" => Self::Derive,
+            "mod" | "module" => Self::Module,
+            "struct" => Self::Struct,
+            "enum" => Self::Enum,
+            "union" => Self::Union,
+            "trait" => Self::Trait,
+            "fn" | "function" => Self::Function,
+            "method" => Self::Method,
+            "const" | "constant" => Self::Constant,
+            "static" => Self::Static,
+            "prim" | "primitive" => Self::Primitive,
+            "field" => Self::Field,
+            "variant" => Self::Variant,
+            "type" => Self::Type,
+            "value" => Self::Value,
+            _ => return None,
+        })
+    }
+
+    /// Prefix of this disambiguator like `struct` for `struct@anyhow::Error`, without the `@`.
+    #[must_use]
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::Macro => "macro",
+            Self::Derive => "derive",
+            Self::Module => "mod",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Union => "union",
+            Self::Trait => "trait",
+            Self::Function => "fn",
+            Self::Method => "method",
+            Self::Constant => "const",
+            Self::Static => "static",
+            Self::Primitive => "prim",
+            Self::Field => "field",
+            Self::Variant => "variant",
+            Self::Type => "type",
+            Self::Value => "value",
+        }
+    }
+
+    /// Check whether an item of the given type matches this disambiguator.
+    pub(crate) fn matches(self, ty: ItemType) -> bool {
+        match self {
+            Self::Macro => matches!(
+                ty,
+                ItemType::Macro | ItemType::ProcAttribute | ItemType::ProcDerive
+            ),
+            Self::Derive => ty == ItemType::ProcDerive,
+            Self::Module => ty == ItemType::Module,
+            Self::Struct => ty == ItemType::Struct,
+            Self::Enum => ty == ItemType::Enum,
+            Self::Union => ty == ItemType::Union,
+            Self::Trait => matches!(ty, ItemType::Trait | ItemType::TraitAlias),
+            Self::Function => matches!(
+                ty,
+                ItemType::Function | ItemType::Method | ItemType::TyMethod
+            ),
+            Self::Method => matches!(ty, ItemType::Method | ItemType::TyMethod),
+            Self::Constant => matches!(ty, ItemType::Constant | ItemType::AssocConst),
+            Self::Static => ty == ItemType::Static,
+            Self::Primitive => ty == ItemType::Primitive,
+            Self::Field => ty == ItemType::StructField,
+            Self::Variant => ty == ItemType::Variant,
+            Self::Type => matches!(
+                ty,
+                ItemType::Module
+                    | ItemType::Struct
+                    | ItemType::Enum
+                    | ItemType::Union
+                    | ItemType::Trait
+                    | ItemType::TraitAlias
+                    | ItemType::Typedef
+                    | ItemType::AssocType
+                    | ItemType::ForeignType
+                    | ItemType::Primitive
+                    | ItemType::Variant
+            ),
+            Self::Value => matches!(
+                ty,
+                ItemType::Function
+                    | ItemType::Method
+                    | ItemType::TyMethod
+                    | ItemType::Constant
+                    | ItemType::AssocConst
+                    | ItemType::Static
+                    | ItemType::Variant
+            ),
+        }
+    }
+}
 
 impl SimplePath {
     /// Get back the original string.
@@ -26,6 +189,91 @@ impl SimplePath {
         self.0
     }
 
+    /// Create a path from its individual segments, without the need to format them into a string
+    /// first.
+    ///
+    /// ```rust
+    /// let path = docsearch::SimplePath::from_segments(["tokio", "sync", "mpsc"]).unwrap();
+    /// assert_eq!("tokio::sync::mpsc", path.as_ref());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails, if there are no segments or any of them isn't a valid identifier.
+    pub fn from_segments<I, S>(segments: I) -> Result<Self, ParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut path = String::new();
+        let mut index = 0;
+
+        for segment in segments {
+            let segment = segment.as_ref();
+            if !is_identifier(segment) {
+                return Err(ParseError::InvalidIdentifier);
+            }
+
+            if path.is_empty() {
+                index = segment.len();
+            } else {
+                path.push_str("::");
+            }
+            path.push_str(segment);
+        }
+
+        if path.is_empty() {
+            return Err(ParseError::TooShort);
+        }
+
+        Ok(Self(path, index, None))
+    }
+
+    /// Parse a path in a lenient mode, that accepts the forms of types found in compiler output,
+    /// like `alloc::vec::Vec<T>`, `&'a mut alloc::string::String` or
+    /// `core::option::Option<&'a str>`. References, lifetimes, `dyn`/`impl` and generic arguments
+    /// are stripped to recover the simple path of the type.
+    ///
+    /// ```rust
+    /// let path = docsearch::SimplePath::parse_lenient("&'a core::option::Option<&'a str>").unwrap();
+    /// assert_eq!("core::option::Option", path.as_ref());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails, if there is no valid simple path left after stripping, like for `&[u8]`.
+    pub fn parse_lenient(value: &str) -> Result<Self, ParseError> {
+        strip_type_noise(trim_noise(value)).parse()
+    }
+
+    /// Parse a path that may be relative to the crate root like `crate::module::Item`, as it's
+    /// common in doc comments, and resolve it against the given crate. Paths that don't start with
+    /// `crate` are parsed as absolute paths.
+    ///
+    /// ```rust
+    /// let path = docsearch::SimplePath::resolve_relative("crate::sync::Mutex", "tokio").unwrap();
+    /// assert_eq!("tokio::sync::Mutex", path.as_ref());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the resolved path isn't valid. Paths relative to the current module, starting
+    /// with `self` or `super`, can't be resolved without knowing the module and fail as well.
+    pub fn resolve_relative(value: &str, crate_name: &str) -> Result<Self, ParseError> {
+        let value = trim_noise(value);
+        let (prefix, path) = match value.split_once('@') {
+            Some((prefix, path)) => (&value[..=prefix.len()], path),
+            None => ("", value),
+        };
+
+        match path.strip_prefix("crate") {
+            Some(rest) if rest.is_empty() || rest.starts_with("::") => {
+                format!("{prefix}{}{rest}", crates::crate_name(crate_name)).parse()
+            }
+            _ => value.parse(),
+        }
+    }
+
     /// Crate name part of this path.
     ///
     /// This can be used as argument for the [`start_search`](crate::start_search) function.
@@ -34,15 +282,128 @@ impl SimplePath {
         &self.0[..self.1]
     }
 
+    /// Individual segments of this path, starting with the crate name.
+    ///
+    /// ```rust
+    /// let path = "tokio::sync::Mutex".parse::<docsearch::SimplePath>().unwrap();
+    /// assert_eq!(vec!["tokio", "sync", "Mutex"], path.segments().collect::<Vec<_>>());
+    /// ```
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.0.split("::")
+    }
+
+    /// Name of the item that this path points to, the last segment. For paths that only consist
+    /// of the crate name, this is the crate name.
+    #[must_use]
+    pub fn item_name(&self) -> &str {
+        self.0.rsplit("::").next().unwrap_or(&self.0)
+    }
+
+    /// Path of the parent module or item, without the last segment. Paths that only consist of
+    /// the crate name have no parent.
+    ///
+    /// ```rust
+    /// let path = "tokio::sync::Mutex".parse::<docsearch::SimplePath>().unwrap();
+    /// assert_eq!("tokio::sync", path.parent().unwrap().as_ref());
+    /// ```
+    #[must_use]
+    pub fn parent(&self) -> Option<Self> {
+        let (parent, _) = self.0.rsplit_once("::")?;
+        Some(Self(parent.to_owned(), self.1, None))
+    }
+
+    /// Create a new path with the given segment appended to this path.
+    ///
+    /// ```rust
+    /// let path = "tokio::sync".parse::<docsearch::SimplePath>().unwrap();
+    /// assert_eq!("tokio::sync::Mutex", path.join("Mutex").unwrap().as_ref());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the segment isn't a valid identifier.
+    pub fn join(&self, segment: &str) -> Result<Self, ParseError> {
+        if !is_identifier(segment) {
+            return Err(ParseError::InvalidIdentifier);
+        }
+
+        Ok(Self(format!("{}::{segment}", self.0), self.1, None))
+    }
+
+    /// Create a copy of this path with the crate name replaced by the given one. This is useful
+    /// for dependencies that were renamed in a project's `Cargo.toml`.
+    ///
+    /// ```rust
+    /// let path = "json::Value".parse::<docsearch::SimplePath>().unwrap();
+    /// assert_eq!("serde_json::Value", path.with_crate_name("serde_json").unwrap().as_ref());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the name isn't a valid identifier.
+    pub fn with_crate_name(&self, name: &str) -> Result<Self, ParseError> {
+        if !is_identifier(name) {
+            return Err(ParseError::InvalidIdentifier);
+        }
+
+        Ok(Self(
+            format!("{name}{}", &self.0[self.1..]),
+            name.len(),
+            self.2,
+        ))
+    }
+
     /// Whether this path is for the standard library.
     #[must_use]
     pub fn is_std(&self) -> bool {
         STD_CRATES.contains(&self.crate_name())
     }
 
+    /// Restriction to a specific kind of item, like macros for `std::vec!`.
+    #[must_use]
+    pub fn disambiguator(&self) -> Option<Disambiguator> {
+        self.2
+    }
+
     /// Whether the path only contains the crate name and no item information.
     pub(crate) fn is_crate_only(&self) -> bool {
-        self.0.len() == self.1
+        self.0.len() == self.1 && self.2.is_none()
+    }
+
+    /// Create a path from its form in the index mapping, where raw identifiers are stored without
+    /// the `r#` prefix, like `foo::try`. Segments that are keywords get the prefix back.
+    pub(crate) fn from_index_path(path: &str) -> Result<Self, ParseError> {
+        if path.split("::").all(is_identifier) {
+            return path.parse();
+        }
+
+        path.split("::")
+            .map(|segment| {
+                if is_identifier(segment) {
+                    Cow::Borrowed(segment)
+                } else {
+                    Cow::Owned(format!("r#{segment}"))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("::")
+            .parse()
+    }
+
+    /// Form of this path as used in the index mapping, with the `r#` prefix removed from all raw
+    /// identifiers, like `foo::try` for `foo::r#try`.
+    pub(crate) fn index_path(&self) -> Cow<'_, str> {
+        if !self.0.contains("r#") {
+            return Cow::Borrowed(&self.0);
+        }
+
+        Cow::Owned(
+            self.0
+                .split("::")
+                .map(|segment| segment.strip_prefix("r#").unwrap_or(segment))
+                .collect::<Vec<_>>()
+                .join("::"),
+        )
     }
 }
 
@@ -50,6 +411,22 @@ impl FromStr for SimplePath {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = trim_noise(s);
+        let (s, prefix) = match s.split_once('@') {
+            Some((prefix, s)) => (
+                s,
+                Some(Disambiguator::from_prefix(prefix).ok_or(Self::Err::InvalidDisambiguator)?),
+            ),
+            None => (s, None),
+        };
+        let (s, disambiguator) = match (s.strip_suffix('!'), prefix) {
+            (Some(s), None | Some(Disambiguator::Macro)) => (s, Some(Disambiguator::Macro)),
+            (Some(_), Some(_)) => return Err(Self::Err::InvalidDisambiguator),
+            (None, prefix) => (s, prefix),
+        };
+        // Paths can start with `::` to refer to the crate from the extern prelude.
+        let s = s.strip_prefix("::").unwrap_or(s);
+
         if s.is_empty() {
             return Err(Self::Err::TooShort);
         }
@@ -60,7 +437,34 @@ impl FromStr for SimplePath {
 
         let index = s.find("::").unwrap_or(s.len());
 
-        Ok(Self(s.to_owned(), index))
+        Ok(Self(s.to_owned(), index, disambiguator))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SimplePath {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.2 {
+            None => serializer.serialize_str(&self.0),
+            Some(Disambiguator::Macro) => serializer.collect_str(&format_args!("{}!", self.0)),
+            Some(disambiguator) => {
+                serializer.collect_str(&format_args!("{}@{}", disambiguator.prefix(), self.0))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SimplePath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Cow::<'de, str>::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
     }
 }
 
@@ -76,6 +480,53 @@ impl Display for SimplePath {
     }
 }
 
+/// Remove cosmetic noise around a path, as it's common when copying it from a chat message or
+/// Markdown text, like in `` `anyhow::Result`. ``. That is surrounding whitespace and backticks, as
+/// well as trailing punctuation.
+fn trim_noise(value: &str) -> &str {
+    value
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '`')
+        .trim_end_matches(|c: char| c.is_whitespace() || matches!(c, '`' | '.' | ',' | ';' | '?'))
+}
+
+/// Reduce a type as printed by the compiler to its simple path, by removing references, lifetimes,
+/// `dyn`/`impl` keywords, generic arguments and any additional trait bounds.
+fn strip_type_noise(value: &str) -> String {
+    let mut value = value.trim_start();
+
+    loop {
+        let stripped = if let Some(rest) = value.strip_prefix('&') {
+            rest
+        } else if let Some(rest) = value.strip_prefix('\'') {
+            rest.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_')
+        } else if let Some(rest) = ["mut ", "dyn ", "impl "]
+            .into_iter()
+            .find_map(|keyword| value.strip_prefix(keyword))
+        {
+            rest
+        } else {
+            break;
+        };
+
+        value = stripped.trim_start();
+    }
+
+    let mut path = String::with_capacity(value.len());
+    let mut depth = 0_usize;
+
+    for c in value.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => break,
+            c if depth == 0 => path.push(c),
+            _ => {}
+        }
+    }
+
+    path
+}
+
 /// Check whether the given value is an identifier or a keyword.
 ///
 /// An identifier is any nonempty Unicode string of the following form:
@@ -146,7 +597,7 @@ fn is_non_keyword_identifier(value: &str) -> bool {
         "use", "where", "while", "async", "await", "dyn",
     ];
     const RESERVED_KEYWORDS: &[&str] = &[
-        "abstract", "become", "box", "do", "final", "macro", "override", "priv", "typeof",
+        "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof",
         "unsized", "virtual", "yield",
     ];
 
@@ -176,7 +627,15 @@ mod tests {
 
     #[test]
     fn parse_valid() {
-        let inputs = &["anyhow", "anyhow::Result", "special::__", "__", "r#unsafe"];
+        let inputs = &[
+            "anyhow",
+            "anyhow::Result",
+            "special::__",
+            "__",
+            "r#unsafe",
+            "std::vec!",
+            "::std::vec::Vec",
+        ];
 
         for input in inputs {
             assert!(input.parse::<SimplePath>().is_ok());
@@ -185,10 +644,201 @@ mod tests {
 
     #[test]
     fn parse_invalid() {
-        let inputs = &["", "a::::b", "::", "_", "unsafe", "Self", "r#Self"];
+        let inputs = &[
+            "", "a::::b", "::", "_", "unsafe", "Self", "r#Self", "!", "a!!",
+        ];
 
         for input in inputs {
             assert!(input.parse::<SimplePath>().is_err());
         }
     }
+
+    #[test]
+    fn index_path() {
+        let path = "foo::r#try::r#Bar".parse::<SimplePath>().unwrap();
+        assert_eq!("foo::try::Bar", path.index_path());
+
+        let path = SimplePath::from_index_path("foo::try::Bar").unwrap();
+        assert_eq!("foo::r#try::Bar", path.as_ref());
+        assert_eq!("foo", path.crate_name());
+    }
+
+    #[test]
+    fn parse_noise() {
+        for input in [
+            "  anyhow::Result\n",
+            "`anyhow::Result`",
+            "anyhow::Result.",
+            "`anyhow::Result`?",
+        ] {
+            let path = input.parse::<SimplePath>().unwrap();
+            assert_eq!("anyhow::Result", path.as_ref());
+        }
+
+        let path = "`std::vec!`,".parse::<SimplePath>().unwrap();
+        assert_eq!("std::vec", path.as_ref());
+        assert_eq!(Some(Disambiguator::Macro), path.disambiguator());
+
+        assert!(" . ".parse::<SimplePath>().is_err());
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let inputs = [
+            ("alloc::vec::Vec<T>", "alloc::vec::Vec"),
+            ("core::option::Option<&'a str>", "core::option::Option"),
+            ("&'a mut alloc::string::String", "alloc::string::String"),
+            ("&dyn core::error::Error + Send", "core::error::Error"),
+            (
+                "impl core::iter::Iterator<Item = u8>",
+                "core::iter::Iterator",
+            ),
+            ("alloc::vec::Vec<u8>::push", "alloc::vec::Vec::push"),
+            ("`anyhow::Result<()>`.", "anyhow::Result"),
+        ];
+
+        for (input, expected) in inputs {
+            assert_eq!(expected, SimplePath::parse_lenient(input).unwrap().as_ref());
+        }
+
+        assert!(SimplePath::parse_lenient("&[u8]").is_err());
+        assert!(SimplePath::parse_lenient("&'a").is_err());
+    }
+
+    #[test]
+    fn from_segments() {
+        let path = SimplePath::from_segments(["tokio", "sync", "mpsc"]).unwrap();
+        assert_eq!("tokio::sync::mpsc", path.as_ref());
+        assert_eq!("tokio", path.crate_name());
+
+        let path = SimplePath::from_segments(vec!["anyhow".to_owned()]).unwrap();
+        assert!(path.is_crate_only());
+
+        assert!(matches!(
+            SimplePath::from_segments(Vec::<&str>::new()),
+            Err(ParseError::TooShort)
+        ));
+        assert!(matches!(
+            SimplePath::from_segments(["tokio", "sync::mpsc"]),
+            Err(ParseError::InvalidIdentifier)
+        ));
+        assert!(SimplePath::from_segments(["tokio", ""]).is_err());
+    }
+
+    #[test]
+    fn accessors() {
+        let path = "tokio::sync::Mutex".parse::<SimplePath>().unwrap();
+        assert_eq!(
+            vec!["tokio", "sync", "Mutex"],
+            path.segments().collect::<Vec<_>>()
+        );
+        assert_eq!("Mutex", path.item_name());
+
+        let parent = path.parent().unwrap();
+        assert_eq!("tokio::sync", parent.as_ref());
+        assert_eq!("tokio", parent.crate_name());
+
+        let root = parent.parent().unwrap();
+        assert!(root.is_crate_only());
+        assert_eq!("tokio", root.item_name());
+        assert!(root.parent().is_none());
+
+        let joined = root.join("io").unwrap();
+        assert_eq!("tokio::io", joined.as_ref());
+        assert_eq!("tokio", joined.crate_name());
+        assert!(root.join("io::Read").is_err());
+        assert!(root.join("").is_err());
+
+        let renamed = path.with_crate_name("tokio_new").unwrap();
+        assert_eq!("tokio_new::sync::Mutex", renamed.as_ref());
+        assert_eq!("tokio_new", renamed.crate_name());
+        assert!(path.with_crate_name("my crate").is_err());
+        assert!(path.with_crate_name("").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        use serde_test::Token;
+
+        let path = "anyhow::Result".parse::<SimplePath>().unwrap();
+        serde_test::assert_tokens(&path, &[Token::Str("anyhow::Result")]);
+
+        let path = "std::vec!".parse::<SimplePath>().unwrap();
+        serde_test::assert_tokens(&path, &[Token::Str("std::vec!")]);
+
+        let path = "function@syn::parse".parse::<SimplePath>().unwrap();
+        serde_test::assert_ser_tokens(&path, &[Token::Str("fn@syn::parse")]);
+
+        serde_test::assert_de_tokens_error::<SimplePath>(
+            &[Token::Str("a::::b")],
+            "One or more segments aren't valid identifiers",
+        );
+    }
+
+    #[test]
+    fn resolve_relative() {
+        let resolve =
+            |value| SimplePath::resolve_relative(value, "async-std").map(SimplePath::into_inner);
+
+        assert_eq!(
+            "async_std::task::spawn",
+            resolve("crate::task::spawn").unwrap()
+        );
+        assert_eq!("async_std", resolve("crate").unwrap());
+        assert_eq!("anyhow::Result", resolve("anyhow::Result").unwrap());
+        assert_eq!("crates::Foo", resolve("crates::Foo").unwrap());
+
+        let path = SimplePath::resolve_relative("fn@crate::task::spawn", "async_std").unwrap();
+        assert_eq!("async_std::task::spawn", path.as_ref());
+        assert_eq!(Some(Disambiguator::Function), path.disambiguator());
+
+        assert!(resolve("self::task").is_err());
+        assert!(resolve("super::task").is_err());
+    }
+
+    #[test]
+    fn parse_leading_colons() {
+        let path = "::std::vec::Vec".parse::<SimplePath>().unwrap();
+        assert_eq!("std::vec::Vec", path.as_ref());
+        assert_eq!("std", path.crate_name());
+
+        let path = "struct@::std::vec::Vec".parse::<SimplePath>().unwrap();
+        assert_eq!("std::vec::Vec", path.as_ref());
+    }
+
+    #[test]
+    fn parse_macro() {
+        let path = "std::vec!".parse::<SimplePath>().unwrap();
+        assert_eq!("std::vec", path.as_ref());
+        assert_eq!(Some(Disambiguator::Macro), path.disambiguator());
+        assert!(!path.is_crate_only());
+
+        let path = "std::vec".parse::<SimplePath>().unwrap();
+        assert_eq!(None, path.disambiguator());
+    }
+
+    #[test]
+    fn parse_disambiguator() {
+        let path = "fn@syn::parse".parse::<SimplePath>().unwrap();
+        assert_eq!("syn::parse", path.as_ref());
+        assert_eq!("syn", path.crate_name());
+        assert_eq!(Some(Disambiguator::Function), path.disambiguator());
+
+        let path = "macro@std::vec!".parse::<SimplePath>().unwrap();
+        assert_eq!(Some(Disambiguator::Macro), path.disambiguator());
+
+        let path = "module@std::vec".parse::<SimplePath>().unwrap();
+        assert_eq!(Some(Disambiguator::Module), path.disambiguator());
+
+        assert!(matches!(
+            "fn@std::vec!".parse::<SimplePath>(),
+            Err(ParseError::InvalidDisambiguator)
+        ));
+        assert!(matches!(
+            "class@std::vec".parse::<SimplePath>(),
+            Err(ParseError::InvalidDisambiguator)
+        ));
+        assert!("fn@".parse::<SimplePath>().is_err());
+    }
 }
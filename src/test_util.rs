@@ -0,0 +1,17 @@
+//! Shared fixtures for the tests of the different modules.
+
+use std::fs;
+
+use crate::{Index, SearchIndex};
+
+/// Raw content of the search index fixture for `anyhow@1.0.72`.
+pub(crate) fn anyhow_fixture() -> String {
+    fs::read_to_string("src/index/fixtures/anyhow-1.0.72.js").unwrap()
+}
+
+/// Index of `anyhow@1.0.72`, transformed from the [`anyhow_fixture`].
+pub(crate) fn anyhow_index() -> Index {
+    SearchIndex::from_parts("anyhow", "1.0.72".parse().unwrap(), "")
+        .transform_index(&anyhow_fixture())
+        .unwrap()
+}
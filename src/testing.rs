@@ -0,0 +1,174 @@
+//! Helpers for deterministic tests of applications that embed this crate, without network access.
+//!
+//! The [`OfflineFetch`] client serves canned docs pages and small search indexes, that are
+//! generated from a list of item paths. It can drive a [`Search`](crate::Search) like any other
+//! HTTP client.
+//!
+//! ```rust
+//! use docsearch::{testing::OfflineFetch, ItemType, Search, Version};
+//!
+//! let fetch = OfflineFetch::new().with_crate(
+//!     "my-crate",
+//!     Version::Latest,
+//!     &[
+//!         (ItemType::Struct, "my_crate::Config"),
+//!         (ItemType::Function, "my_crate::io::load"),
+//!     ],
+//! );
+//!
+//! let index = Search::new("my-crate", Version::Latest).run(&fetch).unwrap();
+//!
+//! assert_eq!(
+//!     Some("https://docs.rs/my_crate/latest/my_crate/io/fn.load.html".to_owned()),
+//!     index.find_link(&"my_crate::io::load".parse().unwrap())
+//! );
+//! ```
+
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use crate::{
+    crates,
+    error::Result,
+    fetch::{AsyncHttpFetch, FetchFuture, HttpFetch},
+    ItemType, Request, Response, Version,
+};
+
+/// Resource suffix of the canned docs pages from [`OfflineFetch::with_crate`].
+pub const RESOURCE_SUFFIX: &str = "-1.70.0";
+
+/// Create a minimal docs page, that points to the search index with the given resource suffix,
+/// like `-1.70.0` for `search-index-1.70.0.js`.
+#[must_use]
+pub fn docs_page(resource_suffix: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><title>Docs</title></head><body>\
+        <div id=\"rustdoc-vars\" data-root-path=\"../\" data-resource-suffix=\"{resource_suffix}\">\
+        </div></body></html>"
+    )
+}
+
+/// Create a search index in the current format, that contains the given items of a single
+/// crate. Each item is given by its type and full path, like `my_crate::io::load`, with the crate
+/// name as used in code at the start.
+#[must_use]
+pub fn search_index(name: &str, items: &[(ItemType, &str)]) -> String {
+    let (types, names, paths) = items.iter().fold(
+        (Vec::new(), Vec::new(), Vec::new()),
+        |(mut types, mut names, mut paths), (ty, path)| {
+            let (module, name) = path.rsplit_once("::").unwrap_or((path, ""));
+            types.push(*ty as u8);
+            names.push(name);
+            paths.push(module);
+            (types, names, paths)
+        },
+    );
+
+    let data = json!({
+        "doc": "",
+        "t": types,
+        "n": names,
+        "q": paths,
+        "d": vec![""; items.len()],
+        "i": vec![0; items.len()],
+        "p": [],
+    });
+    let data = data.to_string().replace('\\', r"\\").replace('\'', r"\'");
+
+    format!(
+        "var searchIndex = JSON.parse('{{\\\n\"{}\":{data}\\\n}}');\n\
+        if (typeof exports !== 'undefined') {{exports.searchIndex = searchIndex}};\n",
+        crates::crate_name(name)
+    )
+}
+
+/// HTTP client, that serves canned responses instead of sending requests. Any request for an
+/// unknown URL is answered with a `404` status.
+#[derive(Clone, Debug, Default)]
+pub struct OfflineFetch {
+    responses: HashMap<String, Response>,
+}
+
+impl OfflineFetch {
+    /// Create a new client without any responses.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve the given response for all requests to the URL.
+    #[must_use]
+    pub fn with_response(mut self, url: impl Into<String>, response: Response) -> Self {
+        self.responses.insert(url.into(), response);
+        self
+    }
+
+    /// Serve the docs page and search index for a crate on docs.rs, with the given items as
+    /// described in [`search_index`].
+    #[must_use]
+    pub fn with_crate(self, name: &str, version: Version, items: &[(ItemType, &str)]) -> Self {
+        let page = crate::start_search(name, version);
+        let page_url = page.url().to_owned();
+        let fetch = self.with_response(page_url, Response::new(200, docs_page(RESOURCE_SUFFIX)));
+
+        match page.with_resource_suffix(RESOURCE_SUFFIX) {
+            Ok(index) => {
+                fetch.with_response(index.url(), Response::new(200, search_index(name, items)))
+            }
+            Err(_) => fetch,
+        }
+    }
+
+    fn response(&self, request: &Request) -> Response {
+        self.responses
+            .get(&request.url)
+            .cloned()
+            .unwrap_or_else(|| Response::new(404, ""))
+    }
+}
+
+impl HttpFetch for OfflineFetch {
+    fn fetch(&self, request: &Request) -> Result<Response> {
+        Ok(self.response(request))
+    }
+}
+
+impl AsyncHttpFetch for OfflineFetch {
+    fn fetch<'a>(&'a self, request: &'a Request) -> FetchFuture<'a> {
+        Box::pin(async move { Ok(self.response(request)) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::Error, Search};
+
+    #[tokio::test]
+    async fn test_offline_fetch() {
+        let fetch = OfflineFetch::new().with_crate(
+            "my-crate",
+            "1.2.0".parse().unwrap(),
+            &[
+                (ItemType::Struct, "my_crate::Config"),
+                (ItemType::Macro, "my_crate::it's"),
+            ],
+        );
+
+        let index = Search::new("my-crate", "1.2.0".parse().unwrap())
+            .run_async(&fetch)
+            .await
+            .unwrap();
+        assert_eq!(
+            Some("https://docs.rs/my_crate/1.2.0/my_crate/struct.Config.html".to_owned()),
+            index.find_link(&"my_crate::Config".parse().unwrap())
+        );
+        assert!(index.mapping.contains_key("my_crate::it's"));
+
+        let err = Search::new("other", Version::Latest)
+            .run(&fetch)
+            .unwrap_err();
+        assert!(matches!(err, Error::CrateNotFound { .. }));
+    }
+}
@@ -0,0 +1,169 @@
+//! Hierarchical view of the items in an [`Index`], grouped by their modules.
+
+use crate::{crates, Index, ItemType};
+
+/// Single module of a crate with all its direct items and sub-modules, as returned by
+/// [`Index::module_tree`]. The root of the tree is the crate itself.
+///
+/// Members of other items, like methods or enum variants, are not part of the tree, as they don't
+/// belong to a module directly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleTree {
+    /// Name of the module, the last segment of its path.
+    pub name: String,
+    /// Full simple path of the module, like `tokio::sync`.
+    pub path: String,
+    /// Sub-modules, sorted by name.
+    pub modules: Vec<ModuleTree>,
+    /// Items directly contained in this module, except modules, sorted by name.
+    pub items: Vec<TreeItem>,
+}
+
+/// Single item inside a [`ModuleTree`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeItem {
+    /// Name of the item, the last segment of its path.
+    pub name: String,
+    /// Full simple path of the item, like `tokio::sync::Mutex`.
+    pub path: String,
+    /// Type of the item.
+    pub kind: ItemType,
+}
+
+impl ModuleTree {
+    fn new(path: &str) -> Self {
+        Self {
+            name: path.rsplit("::").next().unwrap_or(path).to_owned(),
+            path: path.to_owned(),
+            modules: Vec::new(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Get the module at the given path, relative to this module. Any missing modules along the
+    /// way are created.
+    fn module_mut(&mut self, path: &str) -> &mut Self {
+        let mut module = self;
+
+        for name in path.split("::").filter(|name| !name.is_empty()) {
+            let pos = match module
+                .modules
+                .binary_search_by(|m| m.name.as_str().cmp(name))
+            {
+                Ok(pos) => pos,
+                Err(pos) => {
+                    let path = format!("{}::{name}", module.path);
+                    module.modules.insert(pos, Self::new(&path));
+                    pos
+                }
+            };
+
+            module = &mut module.modules[pos];
+        }
+
+        module
+    }
+
+    /// Total count of items in this module and all its sub-modules, including the modules.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len() + self.modules.iter().map(|m| 1 + m.len()).sum::<usize>()
+    }
+
+    /// Whether this module neither contains any items nor sub-modules.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.modules.is_empty()
+    }
+}
+
+pub(crate) fn build(index: &Index) -> ModuleTree {
+    let crate_name = crates::crate_name(&index.name);
+    let mut root = ModuleTree::new(&crate_name);
+
+    for (path, link) in &index.mapping {
+        let kind = match ItemType::from_link(link) {
+            Some(kind) if !link.contains('#') => kind,
+            _ => continue,
+        };
+        let relative = match path
+            .strip_prefix(root.path.as_str())
+            .and_then(|p| p.strip_prefix("::"))
+        {
+            Some(relative) => relative,
+            None => continue,
+        };
+
+        if kind == ItemType::Module {
+            root.module_mut(relative);
+            continue;
+        }
+
+        let (parent, name) = relative.rsplit_once("::").unwrap_or(("", relative));
+        let module = root.module_mut(parent);
+        let pos = module
+            .items
+            .binary_search_by(|item| item.name.as_str().cmp(name))
+            .unwrap_or_else(|pos| pos);
+
+        module.items.insert(
+            pos,
+            TreeItem {
+                name: name.to_owned(),
+                path: path.clone(),
+                kind,
+            },
+        );
+    }
+
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn test_build() {
+        let index = test_util::anyhow_index();
+
+        let tree = build(&index);
+        assert_eq!("anyhow", tree.name);
+        assert_eq!("anyhow", tree.path);
+        assert!(tree.modules.is_empty());
+        assert!(tree
+            .items
+            .iter()
+            .all(|item| item.path == format!("anyhow::{}", item.name)));
+
+        let error = tree.items.iter().find(|item| item.name == "Error").unwrap();
+        assert_eq!(ItemType::Struct, error.kind);
+
+        let context = tree
+            .items
+            .iter()
+            .find(|item| item.name == "Context")
+            .unwrap();
+        assert_eq!(ItemType::Trait, context.kind);
+    }
+
+    #[test]
+    fn test_module_mut() {
+        let mut tree = ModuleTree::new("tokio");
+        tree.module_mut("sync::mpsc");
+        tree.module_mut("io");
+        tree.module_mut("sync");
+
+        assert_eq!(3, tree.len());
+        assert_eq!(
+            vec!["tokio::io", "tokio::sync"],
+            tree.modules
+                .iter()
+                .map(|m| m.path.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("tokio::sync::mpsc", tree.modules[1].modules[0].path);
+        assert_eq!("mpsc", tree.modules[1].modules[0].name);
+    }
+}
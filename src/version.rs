@@ -1,27 +1,110 @@
 use std::{
+    cmp::Ordering,
     fmt::{self, Display},
     str::FromStr,
 };
 
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use serde::{de, Deserialize, Deserializer};
 
-/// Crate version that can be either the latest available or a specific one.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+/// Crate version that can be either the latest available, a specific one or the latest one
+/// matching a requirement.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Version {
     /// The latest available version.
+    #[default]
     Latest,
     /// A specific, [`semver`]-compliant version.
     SemVer(semver::Version),
+    /// The latest version matching a [`semver`] requirement like `^1.2` or `~0.3`. It is resolved
+    /// to a concrete version during the search.
+    Req(semver::VersionReq),
+}
+
+impl Version {
+    /// Whether this is a concrete version like `1.0.72`, that doesn't need to be resolved any
+    /// further. Both [`Self::Latest`] and version requirements are resolved during a search.
+    #[must_use]
+    pub fn is_concrete(&self) -> bool {
+        matches!(self, Self::SemVer(_))
+    }
+
+    /// Check whether the given concrete version is matched by this version. The latest version
+    /// matches any version, that is not a pre-release.
+    pub(crate) fn matches(&self, version: &semver::Version) -> bool {
+        match self {
+            Self::Latest => version.pre.is_empty(),
+            Self::SemVer(v) => v == version,
+            Self::Req(req) => req.matches(version),
+        }
+    }
+
+    /// Format the version for use as a path segment in URLs, encoding any characters of version
+    /// requirements that aren't allowed in a path.
+    pub(crate) fn to_url_segment(&self) -> String {
+        match self {
+            Self::Req(req) => req
+                .to_string()
+                .replace('^', "%5E")
+                .replace('<', "%3C")
+                .replace('>', "%3E")
+                .replace(' ', "%20"),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Position of the variant, used for ordering.
+    const fn rank(&self) -> u8 {
+        match self {
+            Self::Latest => 0,
+            Self::SemVer(_) => 1,
+            Self::Req(_) => 2,
+        }
+    }
+}
+
+/// Deserialize a [`semver::Version`] from its string form, for the internally parsed API
+/// responses. This avoids depending on the `serde` feature of [`semver`], which is only enabled
+/// together with the public `serde` feature of this crate.
+pub(crate) fn deserialize_semver<'de, D>(deserializer: D) -> Result<semver::Version, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::SemVer(a), Self::SemVer(b)) => a.cmp(b),
+            (Self::Req(a), Self::Req(b)) => a.to_string().cmp(&b.to_string()),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
 }
 
 impl FromStr for Version {
     type Err = semver::Error;
 
+    /// Parse a version, which is either `latest`, a concrete version like `1.2.3` or a version
+    /// requirement like `^1.2` (or just `1.2`).
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(if s == "latest" {
             Self::Latest
+        } else if let Ok(v) = s.parse() {
+            Self::SemVer(v)
         } else {
-            Self::SemVer(s.parse()?)
+            Self::Req(s.parse()?)
         })
     }
 }
@@ -31,12 +114,7 @@ impl Display for Version {
         match self {
             Self::Latest => f.write_str("latest"),
             Self::SemVer(v) => v.fmt(f),
+            Self::Req(r) => r.fmt(f),
         }
     }
 }
-
-impl Default for Version {
-    fn default() -> Self {
-        Self::Latest
-    }
-}
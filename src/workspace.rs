@@ -0,0 +1,445 @@
+//! Resolution of queries against all dependencies of a Cargo project, driven by the output of
+//! `cargo metadata` or the project's `Cargo.lock` file.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use docsearch::{workspace::Workspace, SimplePath};
+//!
+//! let workspace = Workspace::read(None).unwrap();
+//! let query = "anyhow::Result".parse::<SimplePath>().unwrap();
+//!
+//! if let Some(state) = workspace.start_search(&query) {
+//!     // Continue with the search as usual, but for the exact version used in the project.
+//!     println!("{}", state.url());
+//! }
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Result},
+    Index, SearchPage, SimplePath, Version,
+};
+
+/// Single dependency of a Cargo project.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dependency {
+    /// Name of the package as published on the registry, like `serde_json` or `tokio-util`.
+    pub name: String,
+    /// Exact version of the package that the project builds against.
+    pub version: semver::Version,
+}
+
+impl Dependency {
+    /// Name of the crate as used in paths, which has any `-` replaced with `_`.
+    #[must_use]
+    pub fn crate_name(&self) -> String {
+        self.name.replace('-', "_")
+    }
+
+    /// Start a search for this dependency, pinned to its exact version.
+    #[must_use]
+    pub fn start_search(&self) -> SearchPage<'_> {
+        crate::start_search(&self.name, Version::SemVer(self.version.clone()))
+    }
+}
+
+/// Dependency information of a Cargo project (or workspace), as reported by `cargo metadata`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Workspace {
+    dependencies: Vec<Dependency>,
+    renames: BTreeMap<String, String>,
+    target_directory: PathBuf,
+}
+
+impl Workspace {
+    /// Run `cargo metadata` for the given manifest (or the project in the current directory if
+    /// [`None`]) and collect the dependency information from its output.
+    pub fn read(manifest_path: Option<&Path>) -> Result<Self> {
+        let mut cmd = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+        cmd.args(["metadata", "--format-version", "1"]);
+
+        if let Some(path) = manifest_path {
+            cmd.arg("--manifest-path").arg(path);
+        }
+
+        let output = cmd.output()?;
+        if !output.status.success() {
+            return Err(Error::CargoMetadata(
+                String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            ));
+        }
+
+        Self::from_metadata(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Parse the JSON output of `cargo metadata --format-version 1` and collect the dependency
+    /// information from it. Members of the workspace itself are not considered dependencies, but
+    /// any dependencies they rename (with `package = "..."` in their `Cargo.toml`) are recorded.
+    pub fn from_metadata(json: &str) -> Result<Self> {
+        let metadata = serde_json::from_str::<Metadata>(json)?;
+
+        let renames = metadata
+            .packages
+            .iter()
+            .filter(|package| metadata.workspace_members.contains(&package.id))
+            .flat_map(|package| &package.dependencies)
+            .filter_map(|dep| {
+                dep.rename
+                    .as_ref()
+                    .map(|rename| (rename.replace('-', "_"), dep.name.clone()))
+            })
+            .collect();
+
+        let mut dependencies = metadata
+            .packages
+            .into_iter()
+            .filter(|package| !metadata.workspace_members.contains(&package.id))
+            .map(|package| Dependency {
+                name: package.name,
+                version: package.version,
+            })
+            .collect::<Vec<_>>();
+
+        dependencies.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.version.cmp(&b.version)));
+        dependencies.dedup();
+
+        Ok(Self {
+            dependencies,
+            renames,
+            target_directory: metadata.target_directory,
+        })
+    }
+
+    /// Add further dependency renames, mapping the crate name as used in code to the actual name
+    /// of the package. Renames to names that aren't valid crate names are ignored.
+    #[must_use]
+    pub fn with_renames(mut self, renames: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.renames.extend(renames);
+        self
+    }
+
+    /// Renamed dependencies, mapping the crate name as used in code to the actual package name.
+    #[must_use]
+    pub fn renames(&self) -> &BTreeMap<String, String> {
+        &self.renames
+    }
+
+    /// Translate the given path to use the actual crate name, if the crate is a renamed
+    /// dependency. The resulting path can then be used to query the crate's [`Index`].
+    #[must_use]
+    pub fn translate(&self, path: &SimplePath) -> SimplePath {
+        translate(&self.renames, path)
+    }
+
+    /// All dependencies of the project, sorted by name and version.
+    #[must_use]
+    pub fn dependencies(&self) -> &[Dependency] {
+        &self.dependencies
+    }
+
+    /// Find the dependency that provides the crate of the given path. If the same crate is used
+    /// in multiple versions, the latest one is picked.
+    #[must_use]
+    pub fn find(&self, path: &SimplePath) -> Option<&Dependency> {
+        find_dependency(&self.dependencies, &self.renames, path)
+    }
+
+    /// Start a search for the crate of the given path, pinned to the exact version the project
+    /// depends on. Returns [`None`] if the crate is not a dependency of the project.
+    #[must_use]
+    pub fn start_search(&self, path: &SimplePath) -> Option<SearchPage<'_>> {
+        self.find(path).map(Dependency::start_search)
+    }
+
+    /// Create a search for each of the project's dependencies, to resolve queries against all of
+    /// them at once.
+    #[must_use]
+    pub fn search_plans(&self) -> Vec<SearchPage<'_>> {
+        self.dependencies
+            .iter()
+            .map(Dependency::start_search)
+            .collect()
+    }
+
+    /// Load the indexes of all locally generated docs of the project, as created by `cargo doc`.
+    pub fn local_docs(&self) -> Result<Vec<Index>> {
+        crate::from_local_docs(self.target_directory.join("doc"))
+    }
+}
+
+/// Exact versions of all registry dependencies of a project, as recorded in its `Cargo.lock` file.
+///
+/// Using these versions for a search ensures that the generated links exactly match the code that
+/// the project builds against.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lockfile {
+    packages: Vec<Dependency>,
+    renames: BTreeMap<String, String>,
+}
+
+impl Lockfile {
+    /// Read and parse the `Cargo.lock` file at the given location.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Add dependency renames, mapping the crate name as used in code to the actual name of the
+    /// package. The lockfile itself doesn't contain this information. Renames to names that aren't
+    /// valid crate names are ignored, like for [`Workspace::with_renames`].
+    #[must_use]
+    pub fn with_renames(mut self, renames: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.renames.extend(renames);
+        self
+    }
+
+    /// Translate the given path to use the actual crate name, if the crate is a renamed
+    /// dependency. The resulting path can then be used to query the crate's [`Index`].
+    #[must_use]
+    pub fn translate(&self, path: &SimplePath) -> SimplePath {
+        translate(&self.renames, path)
+    }
+
+    /// All registry packages in the lockfile, sorted by name and version. Local packages like
+    /// workspace members or path dependencies, as well as git dependencies, are not included, as
+    /// they aren't published.
+    #[must_use]
+    pub fn packages(&self) -> &[Dependency] {
+        &self.packages
+    }
+
+    /// Find the locked package that provides the crate of the given path. If the same crate is
+    /// locked in multiple versions, the latest one is picked.
+    #[must_use]
+    pub fn find(&self, path: &SimplePath) -> Option<&Dependency> {
+        find_dependency(&self.packages, &self.renames, path)
+    }
+
+    /// Start a search for the crate of the given path, pinned to the exact version from the
+    /// lockfile. Returns [`None`] if the crate is not part of the lockfile.
+    #[must_use]
+    pub fn start_search(&self, path: &SimplePath) -> Option<SearchPage<'_>> {
+        self.find(path).map(Dependency::start_search)
+    }
+}
+
+impl FromStr for Lockfile {
+    type Err = Error;
+
+    /// Parse the content of a `Cargo.lock` file.
+    ///
+    /// The lockfile has a very simple structure of `[[package]]` tables with one `key = "value"`
+    /// pair per line. Therefore, only the `name`, `version` and `source` keys are extracted line by
+    /// line instead of pulling in a full TOML parser. Only packages with a `registry+` or `sparse+`
+    /// source are kept, as git and path dependencies have no docs on docs.rs.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        #[derive(Clone, Copy, Default)]
+        struct Entry<'a> {
+            name: Option<&'a str>,
+            version: Option<&'a str>,
+            registry: bool,
+        }
+
+        fn finish(entry: Entry<'_>) -> Result<Option<Dependency>> {
+            if !entry.registry {
+                return Ok(None);
+            }
+
+            match (entry.name, entry.version) {
+                (Some(name), Some(version)) => Ok(Some(Dependency {
+                    name: name.to_owned(),
+                    version: version.parse()?,
+                })),
+                _ => Err(Error::InvalidLockfile),
+            }
+        }
+
+        let mut packages = Vec::new();
+        let mut entry = None;
+
+        for line in s.lines().map(str::trim) {
+            if line.starts_with('[') {
+                if let Some(dep) = entry.take().map(finish).transpose()?.flatten() {
+                    packages.push(dep);
+                }
+                if line == "[[package]]" {
+                    entry = Some(Entry::default());
+                }
+                continue;
+            }
+
+            let (entry, (key, value)) = match (&mut entry, line.split_once('=')) {
+                (Some(entry), Some(pair)) => (entry, pair),
+                _ => continue,
+            };
+            let value = value.trim().trim_matches('"');
+
+            match key.trim() {
+                "name" => entry.name = Some(value),
+                "version" => entry.version = Some(value),
+                "source" => {
+                    entry.registry = value.starts_with("registry+") || value.starts_with("sparse+");
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(dep) = entry.map(finish).transpose()?.flatten() {
+            packages.push(dep);
+        }
+
+        packages.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.version.cmp(&b.version)));
+        packages.dedup();
+
+        Ok(Self {
+            packages,
+            renames: BTreeMap::new(),
+        })
+    }
+}
+
+/// Get the actual crate name for the one used in the given path, respecting renamed dependencies.
+fn crate_name(renames: &BTreeMap<String, String>, path: &SimplePath) -> String {
+    renames.get(path.crate_name()).map_or_else(
+        || path.crate_name().to_owned(),
+        |name| name.replace('-', "_"),
+    )
+}
+
+/// Replace the crate name of the given path, if it is a renamed dependency. Renames to names that
+/// aren't valid crate names are ignored.
+fn translate(renames: &BTreeMap<String, String>, path: &SimplePath) -> SimplePath {
+    path.with_crate_name(&crate_name(renames, path))
+        .unwrap_or_else(|_| path.clone())
+}
+
+/// Find the dependency for the crate of the given path in a list of dependencies, that is sorted
+/// by name and version.
+fn find_dependency<'a>(
+    deps: &'a [Dependency],
+    renames: &BTreeMap<String, String>,
+    path: &SimplePath,
+) -> Option<&'a Dependency> {
+    let name = crate_name(renames, path);
+    deps.iter().rev().find(|dep| dep.crate_name() == name)
+}
+
+/// The relevant parts of the `cargo metadata` output.
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<Package>,
+    workspace_members: Vec<String>,
+    target_directory: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    id: String,
+    name: String,
+    #[serde(deserialize_with = "crate::version::deserialize_semver")]
+    version: semver::Version,
+    #[serde(default)]
+    dependencies: Vec<PackageDependency>,
+}
+
+#[derive(Deserialize)]
+struct PackageDependency {
+    name: String,
+    rename: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_metadata() {
+        let json = r#"{
+            "packages": [
+                {
+                    "id": "app 0.1.0 (path+file:///app)",
+                    "name": "app",
+                    "version": "0.1.0",
+                    "dependencies": [{"name": "anyhow", "rename": "eyre-like"}]
+                },
+                {"id": "serde-json 1.0.0", "name": "serde-json", "version": "1.0.0"},
+                {"id": "anyhow 1.0.70", "name": "anyhow", "version": "1.0.70"},
+                {"id": "anyhow 1.0.72", "name": "anyhow", "version": "1.0.72"}
+            ],
+            "workspace_members": ["app 0.1.0 (path+file:///app)"],
+            "target_directory": "/app/target"
+        }"#;
+
+        let workspace = Workspace::from_metadata(json).unwrap();
+        assert_eq!(3, workspace.dependencies().len());
+
+        let dep = workspace.find(&"anyhow::Result".parse().unwrap()).unwrap();
+        assert_eq!("1.0.72", dep.version.to_string());
+
+        let dep = workspace
+            .find(&"serde_json::Value".parse().unwrap())
+            .unwrap();
+        assert_eq!("serde-json", dep.name);
+
+        assert!(workspace.find(&"tokio::spawn".parse().unwrap()).is_none());
+
+        let path = "eyre_like::Result".parse().unwrap();
+        assert_eq!("anyhow", workspace.find(&path).unwrap().name);
+        assert_eq!("anyhow::Result", workspace.translate(&path).as_ref());
+    }
+
+    #[test]
+    fn test_lockfile() {
+        let lockfile = r#"
+# This file is automatically @generated by Cargo.
+version = 3
+
+[[package]]
+name = "anyhow"
+version = "1.0.72"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "3b13c32d80ecc7ab747b80c3784bce54ee8a7a0cc4fbda9bf4cda2cf6fe90854"
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "anyhow",
+]
+
+[[package]]
+name = "serde-json"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "tokio"
+version = "1.35.1"
+source = "git+https://github.com/tokio-rs/tokio?branch=master#8c7f7d5d0a28e3ab1b3ba0e7c2fc8e4a4e3b4d29"
+"#
+        .parse::<Lockfile>()
+        .unwrap();
+
+        assert_eq!(2, lockfile.packages().len());
+
+        let state = lockfile
+            .start_search(&"anyhow::Result".parse().unwrap())
+            .unwrap();
+        assert_eq!("https://docs.rs/anyhow/1.0.72/anyhow/", state.url());
+
+        assert!(lockfile
+            .find(&"serde_json::Value".parse().unwrap())
+            .is_some());
+        assert!(lockfile.find(&"app::main".parse().unwrap()).is_none());
+        assert!(lockfile.find(&"tokio::spawn".parse().unwrap()).is_none());
+    }
+}